@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, mem};
 
 use darling::{
     ast::{self, NestedMeta, Style},
@@ -8,8 +8,8 @@ use darling::{
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
 use syn::{
-    parse2, parse_macro_input, spanned::Spanned, DeriveInput, Expr, Generics, Index, Meta, Path,
-    Type, Visibility,
+    ext::IdentExt as _, parse2, parse_macro_input, spanned::Spanned, Attribute, DeriveInput, Expr,
+    Generics, Index, LitStr, Meta, Path, Type, Visibility,
 };
 
 #[cfg(test)]
@@ -66,6 +66,16 @@ impl FromMeta for FieldTryFrom {
     }
 }
 
+/// Handles `from_builder` attributes for building from a previous version of the configuration.
+#[derive(Debug, FromMeta)]
+struct RootFromBuilder {
+    /// The previous configuration type, whose builder is used if the target's own can't be built.
+    ty: Expr,
+
+    /// A function converting the previous configuration type into the target.
+    with: Path,
+}
+
 /// Handles requesting to forward `serde` attributes.
 #[derive(Debug)]
 struct ForwardSerde {
@@ -87,6 +97,176 @@ impl ToTokens for ForwardSerde {
     }
 }
 
+impl ForwardSerde {
+    /// The `rename_all` being forwarded, if any.
+    fn rename_all(&self) -> Option<&NestedMeta> {
+        self.items.iter().find(|item| {
+            matches!(
+                item,
+                NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("rename_all")
+            )
+        })
+    }
+
+    /// The name given by a forwarded `name = "..."`, e.g. `rename`, or by its `deserialize = "..."`
+    /// when given separately for serializing and deserializing, as the builder is only deserialized.
+    fn deserialize_name(&self, name: &str) -> Option<String> {
+        let lit_str = |expr: &Expr| match expr {
+            Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => Some(lit.value()),
+            _ => None,
+        };
+
+        self.items.iter().find_map(|item| match item {
+            NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident(name) => {
+                lit_str(&name_value.value)
+            }
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident(name) => list
+                .parse_args_with(
+                    syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated,
+                )
+                .ok()?
+                .iter()
+                .find(|name_value| name_value.path.is_ident("deserialize"))
+                .and_then(|name_value| lit_str(&name_value.value)),
+            _ => None,
+        })
+    }
+
+    /// The rule given by a forwarded `rename_all` or `rename_all_fields`, if any.
+    fn rename_rule(&self, name: &str) -> Option<RenameRule> {
+        self.deserialize_name(name)
+            .and_then(|rule| RenameRule::from_name(&rule))
+    }
+
+    /// Whether `flatten` is being forwarded.
+    fn flatten(&self) -> bool {
+        self.items.iter().any(
+            |item| matches!(item, NestedMeta::Meta(Meta::Path(path)) if path.is_ident("flatten")),
+        )
+    }
+
+    /// Whether `name` is being forwarded in any form, e.g. `untagged`.
+    fn contains(&self, name: &str) -> bool {
+        self.items.iter().any(|item| match item {
+            NestedMeta::Meta(meta) => meta.path().is_ident(name),
+            NestedMeta::Lit(_) => false,
+        })
+    }
+
+    /// Whether a deserialization function is being forwarded, with `with` or `deserialize_with`.
+    fn deserializes_with(&self) -> bool {
+        self.items.iter().any(|item| {
+            matches!(
+                item,
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("with") || name_value.path.is_ident("deserialize_with")
+            )
+        })
+    }
+
+    /// The `tag` being forwarded, if any, for internally or adjacently tagged enums.
+    fn tag(&self) -> Option<&Expr> {
+        self.items.iter().find_map(|item| match item {
+            NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("tag") => {
+                Some(&name_value.value)
+            }
+            _ => None,
+        })
+    }
+}
+
+/// A serde `rename_all` rule, used to work out the keys the builder is deserialized from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Renames a field, given in `snake_case`, as serde does.
+    fn apply_to_field(self, field: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => field.to_owned(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Pascal => field
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            Self::Camel => {
+                let pascal = Self::Pascal.apply_to_field(field);
+                let mut chars = pascal.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Renames a variant, given in `PascalCase`, as serde does.
+    fn apply_to_variant(self, variant: &str) -> String {
+        // Serde only splits words at uppercase characters, unlike `to_snake_case`.
+        let snake = || {
+            let mut snake = String::new();
+            for (index, c) in variant.char_indices() {
+                if index > 0 && c.is_uppercase() {
+                    snake.push('_');
+                }
+                snake.push(c.to_ascii_lowercase());
+            }
+            snake
+        };
+
+        match self {
+            Self::Pascal => variant.to_owned(),
+            Self::Lower => variant.to_ascii_lowercase(),
+            Self::Upper => variant.to_ascii_uppercase(),
+            Self::Camel => {
+                let mut chars = variant.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            Self::Snake => snake(),
+            Self::ScreamingSnake => snake().to_ascii_uppercase(),
+            Self::Kebab => snake().replace('_', "-"),
+            Self::ScreamingKebab => snake().to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
 impl FromMeta for ForwardSerde {
     fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
         let items = items.to_vec();
@@ -95,6 +275,58 @@ impl FromMeta for ForwardSerde {
     }
 }
 
+/// Handles the `secret` attribute, which may carry a classification, e.g. `secret = "pii"`.
+#[derive(Debug)]
+struct FieldSecret {
+    classification: Option<String>,
+}
+
+impl FromMeta for FieldSecret {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self {
+            classification: None,
+        })
+    }
+
+    fn from_string(classification: &str) -> darling::Result<Self> {
+        Ok(Self {
+            classification: Some(classification.to_owned()),
+        })
+    }
+}
+
+/// Handles the `source_kinds` attribute, listing the kinds of source permitted to set a field, e.g.
+/// `source_kinds(env, file)`.
+#[derive(Debug)]
+struct FieldSourceKinds {
+    kinds: Vec<Ident>,
+}
+
+impl FromMeta for FieldSourceKinds {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let kinds = items
+            .iter()
+            .map(|item| {
+                let variant = match item {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("env") => "Env",
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("file") => "File",
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("other") => "Other",
+                    _ => {
+                        return Err(syn::Error::new(
+                            item.span(),
+                            "Expected a source kind, one of `env`, `file` or `other`",
+                        ))
+                    }
+                };
+
+                Ok(Ident::new(variant, item.span()))
+            })
+            .collect::<Result<Vec<_>, syn::Error>>()?;
+
+        Ok(Self { kinds })
+    }
+}
+
 /// Parser for a default attribute.
 #[derive(Debug)]
 struct FieldDefaulter {
@@ -126,36 +358,49 @@ struct VariantImplementer {
     /// The fields.
     fields: ast::Fields<SpannedValue<FieldImplementer>>,
 
-    /// Optional explicit override of the variant's discriminant.
-    discriminant: Option<Expr>,
-
     /// Optional attributes to forward to serde.
     forward_serde: Option<ForwardSerde>,
+
+    /// Whether this variant is built if no source selects one.
+    default: Flag,
 }
 
 impl VariantImplementer {
     /// Define the builder variant for a given target variant
-    fn define_builder(var_impl: &SpannedValue<Self>) -> syn::Result<TokenStream> {
+    ///
+    /// `deserialize` is whether the builder derives `Deserialize`, and so may have serde attributes.
+    fn define_builder(
+        var_impl: &SpannedValue<Self>,
+        deserialize: bool,
+    ) -> syn::Result<TokenStream> {
         let Self {
             ident,
             fields,
-            discriminant,
             forward_serde,
+            ..
         } = var_impl.as_ref();
 
+        if let (Some(_), false) = (forward_serde, deserialize) {
+            return Err(syn::Error::new(
+                var_impl.span(),
+                "Cannot support `forward_serde` confik attribute alongside `no_deserialize`",
+            ));
+        }
+
         let field_vec = fields
             .iter()
-            .map(FieldImplementer::define_builder)
+            .enumerate()
+            .map(|(index, field)| {
+                FieldImplementer::define_builder(Some(ident), index, field, deserialize)
+            })
             .collect::<Result<Vec<_>, _>>()?;
         let fields = ast::Fields::new(fields.style, field_vec).into_token_stream();
 
-        let discriminant = discriminant
-            .as_ref()
-            .map(|disc| quote_spanned!(disc.span() => = discriminant));
-
+        // Discriminants aren't copied to the builder, so they can't collide with that of
+        // `ConfigBuilderUndefined`. `from_repr` matches against the target's instead.
         Ok(quote_spanned! { var_impl.span() =>
             #forward_serde
-            #ident #fields #discriminant
+            #ident #fields
         })
     }
 
@@ -228,6 +473,53 @@ impl VariantImplementer {
         }
     }
 
+    /// Builds this variant from default builders for its fields, for when no source selects a
+    /// variant.
+    fn impl_try_build_default(var_impl: &SpannedValue<Self>) -> TokenStream {
+        let Self { ident, fields, .. } = var_impl.as_ref();
+
+        let default_fields = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let default = quote!(::std::default::Default::default());
+                match fields.style {
+                    Style::Struct => {
+                        let ident = FieldIdent::new(&field.ident, index);
+                        let cfg = field.cfg();
+                        quote!(#cfg #ident: #default)
+                    }
+                    _ => default,
+                }
+            })
+            .collect::<Vec<_>>();
+        let bracketed_default_fields =
+            ast::Fields::new(fields.style, default_fields).into_token_stream();
+
+        quote_spanned! {var_impl.span() =>
+            Self::ConfigBuilderUndefined => Self::#ident #bracketed_default_fields.try_build()?
+        }
+    }
+
+    fn impl_redact_display(var_impl: &SpannedValue<Self>) -> TokenStream {
+        let Self { ident, fields, .. } = var_impl.as_ref();
+
+        let extract_us_fields = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldImplementer::extract_for_match(index, field, "us"))
+            .collect::<Vec<_>>();
+        let bracketed_extract_us_fields =
+            ast::Fields::new(fields.style, extract_us_fields).into_token_stream();
+
+        let redacted_debug = FieldImplementer::impl_redacted_debug(ident, fields, "us");
+
+        quote_spanned! {var_impl.span() =>
+            Self::#ident #bracketed_extract_us_fields => { #redacted_debug }
+        }
+    }
+
     fn impl_contains_non_secret_data(var_impl: &SpannedValue<Self>) -> TokenStream {
         let Self { ident, fields, .. } = var_impl.as_ref();
 
@@ -241,21 +533,210 @@ impl VariantImplementer {
         let bracketed_extract_us_fields =
             ast::Fields::new(style, extract_us_fields).into_token_stream();
 
+        let string = ident.to_string();
+
         let contains_non_secret_data = fields
             .as_ref()
             .iter()
             .enumerate()
             .map(|(index, field)| {
-                FieldImplementer::impl_contains_non_secret_data(index, field, Some("us"))
+                let cfg = field.cfg();
+                let check =
+                    FieldImplementer::impl_contains_non_secret_data(index, field, Some("us"));
+                quote!(#cfg { contains |= #check.map_err(|err| err.prepend(#string))?; })
+            })
+            .collect::<Vec<_>>();
+
+        // Selecting a variant is data in itself, even if it has no fields, so start from `true`.
+        // Fields are still all checked (note the non-short-circuiting `|=`) so that any secrets
+        // within them are found.
+        quote_spanned! {var_impl.span() =>
+            Self::#ident #bracketed_extract_us_fields => {
+                #[allow(unused_mut)]
+                let mut contains = true;
+                #( #contains_non_secret_data )*
+                contains
+            }
+        }
+    }
+
+    fn impl_clear_env_ignored(var_impl: &SpannedValue<Self>) -> TokenStream {
+        let Self { ident, fields, .. } = var_impl.as_ref();
+
+        let extract_us_fields = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldImplementer::extract_for_match(index, field, "us"))
+            .collect::<Vec<_>>();
+        let bracketed_extract_us_fields =
+            ast::Fields::new(fields.style, extract_us_fields).into_token_stream();
+
+        let clear_env_ignored = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let cfg = field.cfg();
+                let clear = FieldImplementer::impl_clear_env_ignored(index, field, Some("us"));
+                quote!(#cfg { #clear; })
+            })
+            .collect::<Vec<_>>();
+
+        quote_spanned! {var_impl.span() =>
+            Self::#ident #bracketed_extract_us_fields => { #( #clear_env_ignored )* }
+        }
+    }
+
+    fn impl_check_env_only(var_impl: &SpannedValue<Self>) -> TokenStream {
+        let Self { ident, fields, .. } = var_impl.as_ref();
+
+        let extract_us_fields = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldImplementer::extract_for_match(index, field, "us"))
+            .collect::<Vec<_>>();
+        let bracketed_extract_us_fields =
+            ast::Fields::new(fields.style, extract_us_fields).into_token_stream();
+
+        let string = ident.to_string();
+
+        let check_env_only = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let cfg = field.cfg();
+                let check = FieldImplementer::impl_check_env_only(index, field, Some("us"));
+                quote!(#cfg { #check.map_err(|err| err.prepend(#string))?; })
+            })
+            .collect::<Vec<_>>();
+
+        quote_spanned! {var_impl.span() =>
+            Self::#ident #bracketed_extract_us_fields => {
+                #( #check_env_only )*
+            }
+        }
+    }
+
+    fn impl_check_source_kind(var_impl: &SpannedValue<Self>) -> TokenStream {
+        let Self { ident, fields, .. } = var_impl.as_ref();
+
+        let extract_us_fields = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldImplementer::extract_for_match(index, field, "us"))
+            .collect::<Vec<_>>();
+        let bracketed_extract_us_fields =
+            ast::Fields::new(fields.style, extract_us_fields).into_token_stream();
+
+        let string = ident.to_string();
+
+        let check_source_kind = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let cfg = field.cfg();
+                let check = FieldImplementer::impl_check_source_kind(index, field, Some("us"));
+                quote!(#cfg { #check.map_err(|err| err.prepend(#string))?; })
             })
             .collect::<Vec<_>>();
 
+        quote_spanned! {var_impl.span() =>
+            Self::#ident #bracketed_extract_us_fields => {
+                #( #check_source_kind )*
+            }
+        }
+    }
+
+    fn impl_check_conflicts(var_impl: &SpannedValue<Self>) -> TokenStream {
+        let Self { ident, fields, .. } = var_impl.as_ref();
+
+        let style = fields.style;
+        let extract_us_fields = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldImplementer::extract_for_match(index, field, "us"))
+            .collect::<Vec<_>>();
+        let bracketed_extract_us_fields =
+            ast::Fields::new(style, extract_us_fields).into_token_stream();
+
+        let extract_other_fields = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldImplementer::extract_for_match(index, field, "other"))
+            .collect::<Vec<_>>();
+        let bracketed_extract_other_fields =
+            ast::Fields::new(style, extract_other_fields).into_token_stream();
+
+        let pattern = quote! {
+            (Self::#ident #bracketed_extract_us_fields, Self::#ident #bracketed_extract_other_fields)
+        };
+
+        // The variant itself is the value if there are no fields to merge.
+        if fields.is_empty() {
+            return quote_spanned! {var_impl.span() =>
+                #pattern => return ::std::result::Result::Err(::confik::Conflict::default())
+            };
+        }
+
         let string = ident.to_string();
 
+        let check_conflicts = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let cfg = field.cfg();
+                let check = FieldImplementer::impl_check_conflicts(index, field, true);
+                quote!(#cfg { #check.map_err(|err| err.prepend(#string))?; })
+            })
+            .collect::<Vec<_>>();
+
         quote_spanned! {var_impl.span() =>
-            Self::#ident #bracketed_extract_us_fields => false #( | #contains_non_secret_data.map_err(|err| err.prepend(#string))? )*
+            #pattern => {
+                #( #check_conflicts )*
+            }
         }
     }
+
+    fn impl_to_builder(
+        var_impl: &SpannedValue<Self>,
+        builder_name: &Ident,
+    ) -> syn::Result<TokenStream> {
+        let Self { ident, fields, .. } = var_impl.as_ref();
+
+        let style = fields.style;
+        let extract_us_fields = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldImplementer::extract_for_match(index, field, "us"))
+            .collect::<Vec<_>>();
+        let bracketed_extract_us_fields =
+            ast::Fields::new(style, extract_us_fields).into_token_stream();
+
+        let field_to_builder = fields
+            .as_ref()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let us_ident = FieldImplementer::prefixed_ident(index, field, "us");
+                FieldImplementer::impl_to_builder(index, field, style, quote!(#us_ident))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        let bracketed_field_to_builder =
+            ast::Fields::new(style, field_to_builder).into_token_stream();
+
+        Ok(quote_spanned! {var_impl.span() =>
+            Self::#ident #bracketed_extract_us_fields => #builder_name::#ident #bracketed_field_to_builder
+        })
+    }
 }
 
 /// A field may have an explicit ident, i.e. `struct A { b: () }`, or might use an index,
@@ -305,13 +786,29 @@ impl ToTokens for FieldIdent<'_> {
 /// Implementer for struct fields, including those embedded inside an enum, e.g.,
 /// `enum A { B { c: () } }`
 #[derive(Debug, FromField)]
-#[darling(attributes(confik))]
+#[darling(attributes(confik), forward_attrs(cfg))]
 struct FieldImplementer {
     /// Whether to default the field to a value if it's not present.
     default: Option<FieldDefaulter>,
 
+    /// A reference to a lazily initialised static, e.g. a `LazyLock`, which the field is cloned
+    /// from if it's not present.
+    default_lazy: Option<Expr>,
+
+    /// A function giving the field's builder when a source doesn't contain the field, instead of
+    /// an empty builder.
+    serde_default: Option<Path>,
+
     /// Whether the field is a secret, and should be implemented via `SecretBuilder`.
-    secret: Flag,
+    secret: Option<FieldSecret>,
+
+    /// Whether the secret field's default is only a placeholder, so is never built and a source
+    /// must still provide the secret.
+    default_is_placeholder: Flag,
+
+    /// Whether the secret field is shown as a short hash of its value by `redact_display`, rather
+    /// than `[redacted]`.
+    log_hash: Flag,
 
     /// A type which implements `Configuration`, for which the field implements `From`.
     /// Enables handling foreign types.
@@ -321,6 +818,10 @@ struct FieldImplementer {
     /// Enables handling foreign types.
     try_from: Option<FieldTryFrom>,
 
+    /// A function transforming the error from `try_from`, before it is wrapped in
+    /// `FailedTryInto`.
+    map_err: Option<Expr>,
+
     /// The field name, if a named field.
     ///
     /// If not, then you will probably want to enumerate through the list of these and
@@ -330,11 +831,95 @@ struct FieldImplementer {
     /// The field type.
     ty: Type,
 
+    /// The name of a field identifying the items of a list, which are then merged by that field.
+    merge_key: Option<String>,
+
+    /// Whether the field should never be set by an `EnvSource`.
+    env_ignore: Flag,
+
+    /// Whether the secret field should only be set by an environment source, even if other sources
+    /// allow secrets.
+    env_only: Flag,
+
+    /// The kinds of source which may set the field, any others are an error.
+    source_kinds: Option<SpannedValue<FieldSourceKinds>>,
+
+    /// Whether to flatten the field into its parent, e.g. to collect unknown keys into a map.
+    flatten: Flag,
+
+    /// Whether to expand a leading `~` in a `PathBuf` field to the home directory.
+    expand_tilde: Flag,
+
+    /// The scheme a `Url` field must have, checked when it's built.
+    url_scheme: Option<LitStr>,
+
+    /// Whether to read an integer field from a human-readable byte size, e.g. `"512 MiB"`.
+    bytesize: Flag,
+
+    /// Whether to read a `Duration` field from an ISO 8601 duration, e.g. `"PT1H30M"`.
+    iso8601: Flag,
+
+    /// Whether to read a `SystemTime` field from an RFC 3339 timestamp, e.g.
+    /// `"2024-01-01T00:00:00Z"`.
+    rfc3339: Flag,
+
     /// Optional attributes to forward to serde.
     forward_serde: Option<ForwardSerde>,
+
+    /// Whether the field is never read from a source, only set programmatically or defaulted, so
+    /// its type needn't implement `Deserialize`.
+    no_serde: Flag,
+
+    /// The field's `cfg` attributes, which gate everything generated for the field in the same way.
+    attrs: Vec<Attribute>,
 }
 
 impl FieldImplementer {
+    /// The field's `cfg` attributes, to be placed before anything generated for the field.
+    fn cfg(&self) -> TokenStream {
+        let attrs = &self.attrs;
+        quote!(#( #attrs )*)
+    }
+
+    /// The name of the type naming the field's merge key, if it has one.
+    ///
+    /// Defined by [`Self::define_merge_key`] and used in the builder's field type.
+    fn merge_key_marker(
+        variant: Option<&Ident>,
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+    ) -> Ident {
+        let variant = variant.map_or(String::new(), ToString::to_string);
+        let field = field_impl
+            .ident
+            .as_ref()
+            .map_or(field_index.to_string(), ToString::to_string);
+
+        format_ident!("__ConfikMergeKey_{}_{}", variant, field)
+    }
+
+    /// Define the type naming the field's merge key, if it has one.
+    fn define_merge_key(
+        variant: Option<&Ident>,
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+    ) -> Option<TokenStream> {
+        let merge_key = field_impl.merge_key.as_ref()?;
+        let marker = Self::merge_key_marker(variant, field_index, field_impl);
+        let cfg = field_impl.cfg();
+
+        Some(quote_spanned! { field_impl.span() =>
+            #cfg
+            #[allow(non_camel_case_types)]
+            struct #marker;
+
+            #cfg
+            impl ::confik::MergeKey for #marker {
+                const NAME: &'static str = #merge_key;
+            }
+        })
+    }
+
     /// Produces a new ident with a prefix.
     fn prefixed_ident(
         field_index: usize,
@@ -370,26 +955,368 @@ impl FieldImplementer {
             .map(|ident| quote_spanned!(ident.span() => #ident: ));
 
         let ident = Self::prefixed_ident(field_index, field_impl, ident_prefix);
+        let cfg = field_impl.cfg();
+
+        quote_spanned!(field_impl.span() => #cfg #maybe_field_specifier #ident)
+    }
 
-        quote_spanned!(field_impl.span() => #maybe_field_specifier #ident)
+    /// Format the given fields, extracted using [`Self::extract_for_match`], like `Debug` but with
+    /// any secrets redacted.
+    fn impl_redacted_debug(
+        name: &Ident,
+        fields: &ast::Fields<SpannedValue<Self>>,
+        ident_prefix: &str,
+    ) -> TokenStream {
+        let name = name.to_string();
+
+        let field_debug = fields.iter().enumerate().map(|(index, field_impl)| {
+            let ident = Self::prefixed_ident(index, field_impl, ident_prefix);
+            let value = if field_impl.log_hash.is_present() {
+                quote_spanned!(field_impl.log_hash.span() => &::confik::__log_hash(#ident))
+            } else if field_impl.secret.is_some() {
+                quote!(&"[redacted]")
+            } else if field_impl.no_serde.is_present() {
+                // Never read from a source, so can't hold a secret.
+                quote!(#ident)
+            } else {
+                match (&field_impl.from, &field_impl.try_from) {
+                    (Some(FieldFrom { ty }), _) | (None, Some(FieldTryFrom { ty })) => {
+                        quote_spanned!(ty.span() => &::confik::__redacted_from_field::<#ty, _>(#ident))
+                    }
+                    (None, None) => {
+                        quote_spanned!(field_impl.ty.span() => &::confik::__redacted_field(#ident))
+                    }
+                }
+            };
+
+            let cfg = field_impl.cfg();
+
+            match &field_impl.ident {
+                Some(ident) => {
+                    let ident = ident.to_string();
+                    quote_spanned!(field_impl.span() => #cfg debug.field(#ident, #value);)
+                }
+                None => quote_spanned!(field_impl.span() => debug.field(#value);),
+            }
+        });
+
+        match fields.style {
+            Style::Struct => quote! {
+                let mut debug = f.debug_struct(#name);
+                #( #field_debug )*
+                debug.finish()
+            },
+            Style::Tuple => quote! {
+                let mut debug = f.debug_tuple(#name);
+                #( #field_debug )*
+                debug.finish()
+            },
+            Style::Unit => quote!(f.write_str(#name)),
+        }
     }
 
     /// Define the builder field for a given target field.
-    fn define_builder(field_impl: &SpannedValue<Self>) -> syn::Result<TokenStream> {
+    ///
+    /// `deserialize` is whether the builder derives `Deserialize`, and so may have serde attributes.
+    fn define_builder(
+        variant: Option<&Ident>,
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+        deserialize: bool,
+    ) -> syn::Result<TokenStream> {
         let Self {
-            ty,
             ident,
+            default,
+            default_lazy,
+            serde_default,
+            ty,
             secret,
-            forward_serde,
+            default_is_placeholder,
+            log_hash,
             from,
             try_from,
+            merge_key,
+            env_ignore,
+            env_only,
+            source_kinds,
+            flatten,
+            bytesize,
+            iso8601,
+            rfc3339,
+            forward_serde,
+            no_serde,
+            attrs,
             ..
         } = field_impl.as_ref();
 
+        if let (Some(attr), None) = (attrs.first(), ident) {
+            return Err(syn::Error::new(
+                attr.span(),
+                "`cfg` attributes are only supported on named fields",
+            ));
+        }
+
+        // These are all implemented with serde attributes, which a hand-written `Deserialize`
+        // wouldn't know about.
+        if !deserialize {
+            let unsupported = [
+                serde_default
+                    .as_ref()
+                    .map(|serde_default| (serde_default.span(), "serde_default")),
+                forward_serde
+                    .as_ref()
+                    .map(|_| (field_impl.span(), "forward_serde")),
+                flatten.is_present().then(|| (flatten.span(), "flatten")),
+                bytesize.is_present().then(|| (bytesize.span(), "bytesize")),
+                iso8601.is_present().then(|| (iso8601.span(), "iso8601")),
+                rfc3339.is_present().then(|| (rfc3339.span(), "rfc3339")),
+            ];
+            if let Some((span, name)) = unsupported.into_iter().flatten().next() {
+                return Err(syn::Error::new(
+                    span,
+                    format!("Cannot support `{name}` confik attribute alongside `no_deserialize`"),
+                ));
+            }
+        }
+
+        // These all affect how the field is read from a source, which it never is.
+        if no_serde.is_present() {
+            let unsupported = [
+                secret.as_ref().map(|_| (field_impl.span(), "secret")),
+                from.as_ref().map(|from| (from.ty.span(), "from")),
+                try_from
+                    .as_ref()
+                    .map(|try_from| (try_from.ty.span(), "try_from")),
+                merge_key.as_ref().map(|_| (field_impl.span(), "merge_key")),
+                serde_default
+                    .as_ref()
+                    .map(|serde_default| (serde_default.span(), "serde_default")),
+                forward_serde
+                    .as_ref()
+                    .map(|_| (field_impl.span(), "forward_serde")),
+                flatten.is_present().then(|| (flatten.span(), "flatten")),
+                bytesize.is_present().then(|| (bytesize.span(), "bytesize")),
+                iso8601.is_present().then(|| (iso8601.span(), "iso8601")),
+                rfc3339.is_present().then(|| (rfc3339.span(), "rfc3339")),
+            ];
+            if let Some((span, name)) = unsupported.into_iter().flatten().next() {
+                return Err(syn::Error::new(
+                    span,
+                    format!("Cannot support `{name}` confik attribute alongside `no_serde`"),
+                ));
+            }
+        }
+
+        // A flattened secret has no path of its own to list in `Configuration::secret_paths`.
+        let flattened =
+            flatten.is_present() || forward_serde.as_ref().map_or(false, ForwardSerde::flatten);
+        if let (Some(_), true) = (secret, flattened) {
+            return Err(syn::Error::new(
+                field_impl.span(),
+                "Cannot support `flatten` confik attribute alongside `secret`",
+            ));
+        }
+
+        if let (Some(_), Some(default_lazy)) = (default, default_lazy) {
+            return Err(syn::Error::new(
+                default_lazy.span(),
+                "Cannot support both `default` and `default_lazy` confik attributes",
+            ));
+        }
+
+        if default_is_placeholder.is_present() {
+            if secret.is_none() {
+                return Err(syn::Error::new(
+                    default_is_placeholder.span(),
+                    "`default_is_placeholder` requires the `secret` confik attribute",
+                ));
+            }
+
+            if default.is_none() && default_lazy.is_none() {
+                return Err(syn::Error::new(
+                    default_is_placeholder.span(),
+                    "`default_is_placeholder` requires the `default` confik attribute",
+                ));
+            }
+        }
+
+        if env_only.is_present() {
+            if secret.is_none() {
+                return Err(syn::Error::new(
+                    env_only.span(),
+                    "`env_only` requires the `secret` confik attribute",
+                ));
+            }
+
+            if env_ignore.is_present() {
+                let msg = "Cannot support both `env_only` and `env_ignore` confik attributes";
+                let mut err = syn::Error::new(env_only.span(), msg);
+                err.combine(syn::Error::new(env_ignore.span(), msg));
+                return Err(err);
+            }
+
+            if let Some(source_kinds) = source_kinds {
+                let msg = "Cannot support both `env_only` and `source_kinds` confik attributes";
+                let mut err = syn::Error::new(env_only.span(), msg);
+                err.combine(syn::Error::new(source_kinds.span(), msg));
+                return Err(err);
+            }
+        }
+
+        if log_hash.is_present() {
+            if secret.is_none() {
+                return Err(syn::Error::new(
+                    log_hash.span(),
+                    "`log_hash` requires the `secret` confik attribute",
+                ));
+            }
+
+            // These only expose their values explicitly, so aren't hashed, and would otherwise fail
+            // with a less helpful error.
+            if is_secret_type(ty) {
+                return Err(syn::Error::new(
+                    log_hash.span(),
+                    "Cannot support `log_hash` confik attribute on `secrecy` types, which don't \
+                     expose their values",
+                ));
+            }
+        }
+
+        // Unless given a function, a source not containing the field gives an empty builder.
+        let serde_default = match (serde_default, secret) {
+            (Some(serde_default), Some(_)) => {
+                return Err(syn::Error::new(
+                    serde_default.span(),
+                    "Cannot support `serde_default` alongside `secret` confik attribute",
+                ));
+            }
+            (Some(serde_default), None) => {
+                let serde_default = serde_default.to_token_stream().to_string();
+                quote!(#[serde(default = #serde_default)])
+            }
+            (None, _) => quote!(#[serde(default)]),
+        };
+
         let ident = ident
             .as_ref()
             .map(|ident| quote_spanned!(ident.span() => #ident : ));
 
+        let flatten_span = flatten.is_present().then(|| flatten.span());
+        let flatten = flatten_span.map(|span| quote_spanned!(span => #[serde(flatten)]));
+
+        // Attributes which replace the field's deserialization with a parser of their own.
+        let parsers = [
+            (bytesize, "bytesize", "::confik::__deserialize_bytesize"),
+            (iso8601, "iso8601", "::confik::__deserialize_iso8601"),
+            (rfc3339, "rfc3339", "::confik::__deserialize_rfc3339"),
+        ]
+        .into_iter()
+        .filter(|(flag, _, _)| flag.is_present())
+        .map(|(flag, name, path)| (flag.span(), name, path))
+        .collect::<Vec<_>>();
+
+        let deserialize_with = match &parsers[..] {
+            [] => None,
+            [parser] => Some(*parser),
+            [..] => {
+                let names = parsers
+                    .iter()
+                    .map(|(_, name, _)| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let msg = format!("Cannot support more than one of {names} confik attributes");
+                let mut errs = parsers
+                    .iter()
+                    .map(|(span, _, _)| syn::Error::new(*span, &msg));
+                let mut err = errs.next().unwrap();
+                err.extend(errs);
+                return Err(err);
+            }
+        };
+
+        let deserialize_with = match deserialize_with {
+            // Lets the field's builder tell whether the parent's data is for it, e.g. by the tag of a
+            // tagged enum.
+            None => flatten_span
+                .filter(|_| !forward_serde.as_ref().map_or(false, ForwardSerde::deserializes_with))
+                .map(|span| {
+                    quote_spanned!(span => #[serde(deserialize_with = "::confik::__deserialize_flattened")])
+                }),
+            Some((span, name, _))
+                if secret.is_some()
+                    || from.is_some()
+                    || try_from.is_some()
+                    || merge_key.is_some() =>
+            {
+                return Err(syn::Error::new(
+                    span,
+                    format!("Cannot support `{name}` alongside `secret`, `from`, `try_from`, or `merge_key` confik attributes"),
+                ));
+            }
+            Some((span, _, path)) => {
+                Some(quote_spanned!(span => #[serde(deserialize_with = #path)]))
+            }
+        };
+
+        let ty = Self::builder_type(variant, field_index, field_impl)?;
+
+        // If secret then wrap in [`confik::SecretBuilder`]
+        let ty = if secret.is_some() {
+            quote_spanned!(ty.span() => ::confik::SecretBuilder<#ty>)
+        } else {
+            ty
+        };
+
+        let cfg = field_impl.cfg();
+        let serde_default = deserialize.then_some(serde_default);
+
+        if no_serde.is_present() {
+            let skip = deserialize.then(|| quote!(#[serde(skip)]));
+
+            return Ok(quote_spanned! { ident.span() =>
+                #cfg
+                #skip
+                #ident #ty
+            });
+        }
+
+        Ok(quote_spanned! { ident.span() =>
+                #cfg
+                #serde_default
+                #flatten
+                #deserialize_with
+                #forward_serde
+                #ident #ty
+        })
+    }
+
+    /// The builder type for the field, before any wrapping in `SecretBuilder`.
+    fn builder_type(
+        variant: Option<&Ident>,
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+    ) -> syn::Result<TokenStream> {
+        let Self {
+            ty,
+            from,
+            try_from,
+            map_err,
+            merge_key,
+            no_serde,
+            ..
+        } = field_impl.as_ref();
+
+        if no_serde.is_present() {
+            return Ok(quote_spanned!(ty.span() => ::confik::NoSerdeBuilder<#ty>));
+        }
+
+        if let (Some(map_err), None) = (map_err, try_from) {
+            return Err(syn::Error::new(
+                map_err.span(),
+                "`map_err` requires the `try_from` confik attribute",
+            ));
+        }
+
         // Builder type based on original field type via [`confik::Configuration`]
         // If `from` is set, then use that type instead.
         let ty = match (from, try_from) {
@@ -403,19 +1330,48 @@ impl FieldImplementer {
             (None, None) => ty,
         };
 
-        let ty = quote_spanned!(ty.span() => <#ty as ::confik::Configuration>::Builder);
+        let ty = match (merge_key, from, try_from) {
+            (Some(_), Some(FieldFrom { ty }), _) | (Some(_), _, Some(FieldTryFrom { ty })) => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "Cannot support `merge_key` alongside `from` or `try_from` confik attributes",
+                ));
+            }
+            (Some(_), None, None) => {
+                let marker = Self::merge_key_marker(variant, field_index, field_impl);
+                quote_spanned!(ty.span() => ::confik::MergeByKeyBuilder<<#ty as ::std::iter::IntoIterator>::Item, #marker>)
+            }
+            (None, _, _) => quote_spanned!(ty.span() => <#ty as ::confik::Configuration>::Builder),
+        };
 
-        // If secret then wrap in [`confik::SecretBuilder`]
-        let ty = if secret.is_present() {
-            quote_spanned!(ty.span() => ::confik::SecretBuilder<#ty>)
+        Ok(ty)
+    }
+
+    /// Define a method on a struct's builder which sets the field's value.
+    fn define_setter(
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+        vis: &Visibility,
+    ) -> syn::Result<TokenStream> {
+        let ty = Self::builder_type(None, field_index, field_impl)?;
+        let ident = FieldIdent::new(&field_impl.ident, field_index);
+        let setter = Self::prefixed_ident(field_index, field_impl, "with_");
+
+        let value = if field_impl.secret.is_some() {
+            quote!(::confik::SecretBuilder::from(value.into()))
         } else {
-            ty
+            quote!(value.into())
         };
 
-        Ok(quote_spanned! { ident.span() =>
-                #[serde(default)]
-                #forward_serde
-                #ident #ty
+        let cfg = field_impl.cfg();
+
+        Ok(quote_spanned! { field_impl.span() =>
+            #cfg
+            #[allow(dead_code)] // Setters are generated for every field, whether they're used or not.
+            #vis fn #setter(mut self, value: impl ::std::convert::Into<#ty>) -> Self {
+                self.#ident = #value;
+                self
+            }
         })
     }
 
@@ -432,9 +1388,11 @@ impl FieldImplementer {
             self.#ident.merge(other.#ident)
         };
 
+        let cfg = field_impl.cfg();
+
         match style {
             Style::Struct => quote_spanned! { field_impl.span() =>
-                #ident: #merge
+                #cfg #ident: #merge
             },
             Style::Tuple => merge,
             Style::Unit => panic!("Trying to call merge on a field in a unit struct"),
@@ -456,15 +1414,28 @@ impl FieldImplementer {
             #us_ident.merge(#other_ident)
         };
 
+        let cfg = field_impl.cfg();
+
         match style {
             Style::Struct => quote_spanned! { field_impl.span() =>
-                #ident: #merge
+                #cfg #ident: #merge
             },
             Style::Tuple => merge,
             Style::Unit => panic!("Trying to call merge on a field in a unit struct"),
         }
     }
 
+    /// The expression giving the field's default, if it has one.
+    fn default_expr(&self) -> Option<TokenStream> {
+        match (&self.default, &self.default_lazy) {
+            (Some(default), _) => Some(default.expr.to_token_stream()),
+            (None, Some(default_lazy)) => Some(quote_spanned! { default_lazy.span() =>
+                ::std::clone::Clone::clone(::std::ops::Deref::deref(#default_lazy))
+            }),
+            (None, None) => None,
+        }
+    }
+
     /// Defines how to try to build the given field, including handling defaults.
     fn impl_try_build(
         field_index: usize,
@@ -488,10 +1459,12 @@ impl FieldImplementer {
             #our_field.try_build()
         };
 
-        // Default if no data is present
-        if let Some(default) = &field_impl.default {
-            let default = &default.expr;
-
+        // Default if no data is present. The default is only evaluated when it's used, so may be
+        // expensive or have side effects. A placeholder default is never used.
+        if let Some(default) = field_impl
+            .default_expr()
+            .filter(|_| !field_impl.default_is_placeholder.is_present())
+        {
             field_build = quote_spanned! {
                 default.span() =>
                     if #our_field.contains_non_secret_data().unwrap_or(true) {
@@ -502,9 +1475,12 @@ impl FieldImplementer {
             };
         }
 
-        let extra_prepend = extra_prepend.map(|extra_prepend| quote!(.prepend(#extra_prepend)));
+        let prepend = match extra_prepend {
+            Some(extra_prepend) => quote!(.prepend(#string).prepend(#extra_prepend)),
+            None => quote!(.prepend(#string)),
+        };
         field_build = quote_spanned! {
-            field_build.span() => #field_build.map_err(|err| err.prepend(#string)#extra_prepend)?
+            field_build.span() => #field_build.map_err(|err| err #prepend)?
         };
 
         // We're going via another type to allow handling the field being a foreign type. Do the conversion.
@@ -513,22 +1489,97 @@ impl FieldImplementer {
                 field_build.span() => #field_build.into()
             }
         } else if field_impl.try_from.is_some() {
+            let map_err = field_impl.map_err.as_ref().map_or_else(
+                || quote!(e),
+                |map_err| quote_spanned!(map_err.span() => (#map_err)(e)),
+            );
+
             field_build = quote_spanned! {
                 field_build.span() => #field_build.try_into().map_err(|e|
-                    ::confik::FailedTryInto::new(e)
+                    ::confik::FailedTryInto::new(#map_err) #prepend
                 )?
             }
         }
 
+        if field_impl.expand_tilde.is_present() {
+            field_build = quote_spanned! {
+                field_impl.expand_tilde.span() => ::confik::__expand_tilde(#field_build)
+            }
+        }
+
+        if let Some(url_scheme) = &field_impl.url_scheme {
+            field_build = quote_spanned! {
+                url_scheme.span() => ::confik::__check_url_scheme(#field_build, #url_scheme)
+                    .map_err(|e| ::confik::FailedTryInto::new(e) #prepend)?
+            }
+        }
+
+        let cfg = field_impl.cfg();
+
         match style {
             Style::Struct => quote_spanned! { field_impl.span() =>
-                #ident: #field_build
+                #cfg #ident: #field_build
             },
             Style::Tuple => field_build,
             Style::Unit => panic!("Trying to call merge on a field in a unit struct"),
         }
     }
 
+    /// Defines an iterator over the paths of secrets in the field, for
+    /// `Configuration::secret_paths`.
+    ///
+    /// `path` is the path to the field, as named in sources, or `None` if it's flattened into the
+    /// root.
+    fn impl_secret_paths(field_impl: &SpannedValue<Self>, path: Option<&str>) -> TokenStream {
+        if field_impl.secret.is_some() {
+            return quote_spanned! { field_impl.span() =>
+                ::std::iter::once(::std::borrow::ToOwned::to_owned(#path))
+            };
+        }
+
+        // Not necessarily `Configuration`, and never read from a source anyway.
+        if field_impl.no_serde.is_present() {
+            return quote_spanned! { field_impl.span() =>
+                ::std::iter::empty::<::std::string::String>()
+            };
+        }
+
+        let ty = match (&field_impl.from, &field_impl.try_from) {
+            (Some(FieldFrom { ty }), _) | (None, Some(FieldTryFrom { ty })) => ty,
+            (None, None) => &field_impl.ty,
+        };
+
+        match path {
+            Some(path) => quote_spanned! { field_impl.span() =>
+                ::confik::__prefix_paths(#path, <#ty as ::confik::Configuration>::secret_paths())
+            },
+            None => quote_spanned! { field_impl.span() =>
+                <#ty as ::confik::Configuration>::secret_paths()
+            },
+        }
+    }
+
+    /// The key the field is read from, following any serde renaming, or `None` if it's flattened
+    /// into its parent.
+    fn key(&self, index: usize, rename_rule: Option<RenameRule>) -> Option<String> {
+        let forward_serde = self.forward_serde.as_ref();
+        if self.flatten.is_present() || forward_serde.map_or(false, ForwardSerde::flatten) {
+            return None;
+        }
+
+        if let Some(rename) = forward_serde.and_then(|serde| serde.deserialize_name("rename")) {
+            return Some(rename);
+        }
+
+        Some(match &self.ident {
+            Some(ident) => {
+                let name = ident.unraw().to_string();
+                rename_rule.map_or(name.clone(), |rule| rule.apply_to_field(&name))
+            }
+            None => index.to_string(),
+        })
+    }
+
     /// Defines how to check that the field does not contain secret data.
     fn impl_contains_non_secret_data(
         field_index: usize,
@@ -545,57 +1596,270 @@ impl FieldImplementer {
 
         let string = ident.to_string();
 
+        let classify = field_impl
+            .secret
+            .as_ref()
+            .and_then(|secret| secret.classification.as_ref())
+            .map(|classification| quote!(.with_classification(#classification)));
+
         quote_spanned! {
             field_impl.span() =>
-            #our_field.contains_non_secret_data().map_err(|err| err.prepend(#string))
+            #our_field.contains_non_secret_data().map_err(|err| err #classify .prepend(#string))
         }
     }
-}
-
-/// List of attributes to be derived.
-#[derive(Debug)]
-struct Derive {
-    items: Vec<Path>,
-}
 
-impl FromMeta for Derive {
-    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
-        let items = items
-            .iter()
-            .map(|item| {
-                if let NestedMeta::Meta(Meta::Path(path)) = item {
-                    Ok(path.clone())
-                } else {
-                    Err(syn::Error::new(
-                        item.span(),
-                        format!("Expected a path to a derivable trait, got {item:?}"),
-                    ))
-                }
-            })
-            .collect::<Result<Vec<_>, syn::Error>>()?;
+    /// Defines how to check that the field contains no environment-only secrets, for
+    /// `ConfigurationBuilder::check_env_only`.
+    fn impl_check_env_only(
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+        us_ident_prefix: Option<&str>,
+    ) -> TokenStream {
+        let ident = FieldIdent::new(&field_impl.ident, field_index);
 
-        Ok(Self { items })
-    }
-}
+        let our_field = if let Some(ident_prefix) = us_ident_prefix {
+            Self::prefixed_ident(field_index, field_impl, ident_prefix).into_token_stream()
+        } else {
+            quote!(self.#ident)
+        };
 
-impl ToTokens for Derive {
-    fn into_token_stream(self) -> TokenStream {
-        self.to_token_stream()
-    }
+        let string = ident.to_string();
 
-    fn to_tokens(&self, tokens: &mut TokenStream) {
-        tokens.extend(self.to_token_stream());
+        if field_impl.env_only.is_present() {
+            let classify = field_impl
+                .secret
+                .as_ref()
+                .and_then(|secret| secret.classification.as_ref())
+                .map(|classification| quote!(.with_classification(#classification)));
+
+            // The field is a `SecretBuilder`, so any data it contains is an error.
+            quote_spanned! { field_impl.span() =>
+                match #our_field.contains_non_secret_data() {
+                    ::std::result::Result::Ok(false) => ::std::result::Result::Ok(()),
+                    _ => ::std::result::Result::Err(
+                        ::confik::UnexpectedSecret::default() #classify .prepend(#string)
+                    ),
+                }
+            }
+        } else {
+            quote_spanned! { field_impl.span() =>
+                #our_field.check_env_only().map_err(|err| err.prepend(#string))
+            }
+        }
     }
 
-    fn to_token_stream(&self) -> TokenStream {
-        let Self { items } = self;
-        quote!( #( #items ),*)
-    }
-}
+    /// Defines how to check that the field contains no data from a kind of source it is restricted
+    /// from, for `ConfigurationBuilder::check_source_kind`.
+    fn impl_check_source_kind(
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+        us_ident_prefix: Option<&str>,
+    ) -> TokenStream {
+        let ident = FieldIdent::new(&field_impl.ident, field_index);
 
-/// Driver for the implementation of `#[derive(Configuration)]`.
-#[derive(Debug, FromDeriveInput)]
-#[darling(attributes(confik))]
+        let our_field = if let Some(ident_prefix) = us_ident_prefix {
+            Self::prefixed_ident(field_index, field_impl, ident_prefix).into_token_stream()
+        } else {
+            quote!(self.#ident)
+        };
+
+        let string = ident.to_string();
+
+        let kinds = if let Some(source_kinds) = &field_impl.source_kinds {
+            source_kinds.kinds.clone()
+        } else {
+            return quote_spanned! { field_impl.span() =>
+                #our_field.check_source_kind(kind).map_err(|err| err.prepend(#string))
+            };
+        };
+
+        // Any data at all in the field is an error if it came from a kind not listed.
+        quote_spanned! { field_impl.span() =>
+            if [ #( ::confik::SourceKind::#kinds ),* ].contains(&kind)
+                || ::std::matches!(
+                    #our_field.contains_non_secret_data(),
+                    ::std::result::Result::Ok(false)
+                )
+            {
+                #our_field.check_source_kind(kind).map_err(|err| err.prepend(#string))
+            } else {
+                ::std::result::Result::Err(::confik::UnexpectedSource::new(kind).prepend(#string))
+            }
+        }
+    }
+
+    /// Defines how to check that the field isn't given by both builders, for
+    /// `ConfigurationBuilder::check_conflicts`.
+    fn impl_check_conflicts(
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+        in_variant: bool,
+    ) -> TokenStream {
+        let ident = FieldIdent::new(&field_impl.ident, field_index);
+
+        // Fields extracted from a variant are already references.
+        let (our_field, their_field) = if in_variant {
+            let us_ident = Self::prefixed_ident(field_index, field_impl, "us");
+            let other_ident = Self::prefixed_ident(field_index, field_impl, "other");
+            (quote!(#us_ident), quote!(#other_ident))
+        } else {
+            (quote!(self.#ident), quote!(&other.#ident))
+        };
+
+        let string = ident.to_string();
+
+        quote_spanned! { field_impl.span() =>
+            #our_field.check_conflicts(#their_field).map_err(|err| err.prepend(#string))
+        }
+    }
+
+    /// Defines how to convert the field's value back into its builder, for `ToBuilder::to_builder`.
+    ///
+    /// `value` is a reference to the field's value.
+    fn impl_to_builder(
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+        style: Style,
+        value: TokenStream,
+    ) -> syn::Result<TokenStream> {
+        let Self {
+            secret,
+            from,
+            try_from,
+            merge_key,
+            no_serde,
+            ..
+        } = field_impl.as_ref();
+
+        // The builders of these are for a different type, which can't be converted back into, or
+        // for `no_serde` a type which may not be `Configuration`.
+        let unsupported = [
+            no_serde.is_present().then(|| (no_serde.span(), "no_serde")),
+            from.as_ref().map(|from| (from.ty.span(), "from")),
+            try_from
+                .as_ref()
+                .map(|try_from| (try_from.ty.span(), "try_from")),
+            merge_key.as_ref().map(|_| (field_impl.span(), "merge_key")),
+        ];
+        if let Some((span, name)) = unsupported.into_iter().flatten().next() {
+            return Err(syn::Error::new(
+                span,
+                format!("Cannot support `{name}` confik attribute alongside `gen_to_builder`"),
+            ));
+        }
+
+        let to_builder = quote_spanned! { field_impl.span() =>
+            ::confik::ToBuilder::to_builder(#value)
+        };
+        let to_builder = if secret.is_some() {
+            quote_spanned!(field_impl.span() => ::confik::SecretBuilder::from(#to_builder))
+        } else {
+            to_builder
+        };
+
+        let ident = FieldIdent::new(&field_impl.ident, field_index);
+        let cfg = field_impl.cfg();
+
+        Ok(match style {
+            Style::Struct => quote_spanned!(field_impl.span() => #cfg #ident: #to_builder),
+            Style::Tuple => to_builder,
+            Style::Unit => panic!("Trying to convert a field in a unit struct"),
+        })
+    }
+
+    /// Defines how to stop the field being set by an `EnvSource`, for
+    /// `ConfigurationBuilder::clear_env_ignored`.
+    fn impl_clear_env_ignored(
+        field_index: usize,
+        field_impl: &SpannedValue<Self>,
+        us_ident_prefix: Option<&str>,
+    ) -> TokenStream {
+        let ident = FieldIdent::new(&field_impl.ident, field_index);
+
+        let our_field = if let Some(ident_prefix) = us_ident_prefix {
+            let ident = Self::prefixed_ident(field_index, field_impl, ident_prefix);
+            quote!((*#ident))
+        } else {
+            quote!(self.#ident)
+        };
+
+        if field_impl.env_ignore.is_present() {
+            quote_spanned! { field_impl.span() =>
+                #our_field = ::std::default::Default::default()
+            }
+        } else {
+            quote_spanned! { field_impl.span() =>
+                #our_field.clear_env_ignored()
+            }
+        }
+    }
+}
+
+/// Handles requesting to forward other attributes to the builder, e.g. `non_exhaustive`.
+#[derive(Debug)]
+struct Forward {
+    items: Vec<NestedMeta>,
+}
+
+impl ToTokens for Forward {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self { items } = self;
+        tokens.extend(quote!(#( #[#items] )*));
+    }
+}
+
+impl FromMeta for Forward {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let items = items.to_vec();
+
+        Ok(Self { items })
+    }
+}
+
+/// List of attributes to be derived.
+#[derive(Debug)]
+struct Derive {
+    items: Vec<Path>,
+}
+
+impl FromMeta for Derive {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let items = items
+            .iter()
+            .map(|item| {
+                if let NestedMeta::Meta(Meta::Path(path)) = item {
+                    Ok(path.clone())
+                } else {
+                    Err(syn::Error::new(
+                        item.span(),
+                        format!("Expected a path to a derivable trait, got {item:?}"),
+                    ))
+                }
+            })
+            .collect::<Result<Vec<_>, syn::Error>>()?;
+
+        Ok(Self { items })
+    }
+}
+
+impl ToTokens for Derive {
+    fn into_token_stream(self) -> TokenStream {
+        self.to_token_stream()
+    }
+
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(self.to_token_stream());
+    }
+
+    fn to_token_stream(&self) -> TokenStream {
+        let Self { items } = self;
+        quote!( #( #items ),*)
+    }
+}
+
+/// Driver for the implementation of `#[derive(Configuration)]`.
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(confik))]
 struct RootImplementer {
     /// The ident/name of the target (the struct/enum the derive was called on).
     ///
@@ -623,14 +1887,131 @@ struct RootImplementer {
     /// `pub`, `pub(crate)`, etc.
     vis: Visibility,
 
+    /// The path to serde for the builder to use, instead of confik's re-export of it.
+    serde_crate: Option<Path>,
+
     /// Optional attributes to forward to serde.
     forward_serde: Option<ForwardSerde>,
 
+    /// Optional attributes to forward to the builder itself, e.g. `non_exhaustive`.
+    forward: Option<Forward>,
+
     /// Derives needed by the builder, e.g. `Hash`.
     derive: Option<Derive>,
+
+    /// Whether to implement `Display`, formatting like `Debug` but with secrets redacted.
+    redact_display: Flag,
+
+    /// Whether to generate a method on the builder for setting each field, e.g. `with_port`.
+    gen_setters: Flag,
+
+    /// Whether to implement `FromStr` for a fieldless enum, using the builder's `Deserialize`.
+    gen_from_str: Flag,
+
+    /// Whether a fieldless enum can also be deserialized from the integer discriminant of a
+    /// variant.
+    from_repr: Flag,
+
+    /// Whether to implement `ToBuilder`, converting the target back into its builder.
+    gen_to_builder: Flag,
+
+    /// Whether the builder should reject keys which don't match any field.
+    deny_unknown_fields: Flag,
+
+    /// Whether to leave the builder without a derived `Deserialize`, so that one can be written by
+    /// hand.
+    no_deserialize: Flag,
+
+    /// Whether the enum is built from exactly one of several sections, one per variant.
+    one_of: Flag,
+
+    /// Whether to warn about secret fields whose type doesn't protect its value in memory.
+    strict_secrets: Flag,
+
+    /// Whether the struct is read from `SCREAMING_SNAKE_CASE` keys, matching environment variables.
+    env_style: Flag,
+
+    /// A previous version of the configuration to build from if the target can't be built.
+    from_builder: Option<SpannedValue<RootFromBuilder>>,
+
+    /// The variant for each section of a `one_of` enum, set by [`Self::into_one_of`].
+    #[darling(skip)]
+    one_of_variants: Option<Vec<Ident>>,
 }
 
 impl RootImplementer {
+    /// Converts a `one_of` enum into a struct with a field for each variant's section, so that its
+    /// builder can be derived as for a struct. Only building differs, see
+    /// [`Self::impl_try_build_one_of`].
+    fn into_one_of(mut self) -> syn::Result<Self> {
+        if !self.one_of.is_present() {
+            return Ok(self);
+        }
+
+        let unsupported = [
+            (&self.redact_display, "redact_display"),
+            (&self.gen_to_builder, "gen_to_builder"),
+        ];
+        if let Some((flag, name)) = unsupported.into_iter().find(|(flag, _)| flag.is_present()) {
+            return Err(syn::Error::new(
+                flag.span(),
+                format!("Cannot support `{name}` confik attribute alongside `one_of`"),
+            ));
+        }
+
+        let data = mem::replace(&mut self.data, ast::Data::Struct(Style::Struct.into()));
+        let ast::Data::Enum(variants) = data else {
+            return Err(syn::Error::new(
+                self.one_of.span(),
+                "`one_of` is only supported for enums",
+            ));
+        };
+
+        let mut one_of_variants = Vec::with_capacity(variants.len());
+        let mut sections = Vec::with_capacity(variants.len());
+
+        for mut variant in variants {
+            if variant.default.is_present() {
+                return Err(syn::Error::new(
+                    variant.default.span(),
+                    "Cannot support `default` confik attribute alongside `one_of`",
+                ));
+            }
+
+            let span = variant.span();
+            let fields = mem::replace(&mut variant.fields, Style::Unit.into());
+            let mut field = match fields.split() {
+                (Style::Tuple, fields) if fields.len() == 1 => fields.into_iter().next().unwrap(),
+                _ => return Err(syn::Error::new(
+                    span,
+                    "`one_of` requires each variant to have a single unnamed field, its section",
+                )),
+            };
+
+            field.ident = Some(format_ident!(
+                "{}",
+                to_snake_case(&variant.ident.unraw().to_string()),
+                span = variant.ident.span(),
+            ));
+
+            // Serde attributes on the variant, e.g. `rename`, now apply to its section's field.
+            if let Some(ForwardSerde { items }) = variant.forward_serde.take() {
+                field
+                    .forward_serde
+                    .get_or_insert_with(|| ForwardSerde { items: Vec::new() })
+                    .items
+                    .extend(items);
+            }
+
+            one_of_variants.push(variant.ident.clone());
+            sections.push(field);
+        }
+
+        self.data = ast::Data::Struct(ast::Fields::new(Style::Struct, sections));
+        self.one_of_variants = Some(one_of_variants);
+        Ok(self)
+    }
+
     /// Check that the type can be instantiated. This currently just checks that the type
     /// is not a variant-less `enum`, e.g.
     ///
@@ -639,16 +2020,84 @@ impl RootImplementer {
     /// ```
     fn check_valid(&self) -> syn::Result<()> {
         if matches!(&self.data, ast::Data::Enum(variants) if variants.is_empty()) {
-            Err(syn::Error::new(
+            return Err(syn::Error::new(
                 self.ident.span(),
                 format!(
                     "Cannot create a builder for a type that cannot be instantiated: {}",
                     self.ident
                 ),
-            ))
-        } else {
-            Ok(())
+            ));
+        }
+
+        if let ast::Data::Enum(variants) = &self.data {
+            let mut defaults = variants
+                .iter()
+                .filter(|variant| variant.default.is_present());
+            if let (Some(_), Some(second)) = (defaults.next(), defaults.next()) {
+                return Err(syn::Error::new(
+                    second.default.span(),
+                    "Cannot support more than one variant with the `default` confik attribute",
+                ));
+            }
+        }
+
+        if let Some(from_builder) = &self.from_builder {
+            if !self.generics.params.is_empty() {
+                return Err(syn::Error::new(
+                    from_builder.span(),
+                    "`from_builder` is only supported for types without generics",
+                ));
+            }
+
+            // These all rely on the builder being the target's own.
+            let unsupported = [
+                self.gen_setters
+                    .is_present()
+                    .then(|| (self.gen_setters.span(), "gen_setters")),
+                self.gen_to_builder
+                    .is_present()
+                    .then(|| (self.gen_to_builder.span(), "gen_to_builder")),
+                self.no_deserialize
+                    .is_present()
+                    .then(|| (self.no_deserialize.span(), "no_deserialize")),
+            ];
+            if let Some((span, name)) = unsupported.into_iter().flatten().next() {
+                return Err(syn::Error::new(
+                    span,
+                    format!("Cannot support `{name}` confik attribute alongside `from_builder`"),
+                ));
+            }
+        }
+
+        if self.env_style.is_present() {
+            self.check_env_style()?;
         }
+
+        if self.from_repr.is_present() {
+            self.check_from_repr()?;
+        }
+
+        if self.no_deserialize.is_present() {
+            let unsupported = [
+                self.forward_serde
+                    .as_ref()
+                    .map(|_| (self.ident.span(), "forward_serde")),
+                self.deny_unknown_fields
+                    .is_present()
+                    .then(|| (self.deny_unknown_fields.span(), "deny_unknown_fields")),
+                self.env_style
+                    .is_present()
+                    .then(|| (self.env_style.span(), "env_style")),
+            ];
+            if let Some((span, name)) = unsupported.into_iter().flatten().next() {
+                return Err(syn::Error::new(
+                    span,
+                    format!("Cannot support `{name}` confik attribute alongside `no_deserialize`"),
+                ));
+            }
+        }
+
+        Ok(())
     }
 
     /// What the builder name would be for the target, even if one doesn't exist.
@@ -658,6 +2107,92 @@ impl RootImplementer {
         format_ident!("{}ConfigBuilder", self.ident)
     }
 
+    /// Checks that `env_style` is on a struct whose field names can be told apart from the `__`
+    /// separating nested environment variable names, and isn't given another renaming.
+    fn check_env_style(&self) -> syn::Result<()> {
+        let ast::Data::Struct(fields) = &self.data else {
+            return Err(syn::Error::new(
+                self.env_style.span(),
+                "`env_style` is only supported for structs",
+            ));
+        };
+
+        if let Some(rename_all) = self
+            .forward_serde
+            .as_ref()
+            .and_then(ForwardSerde::rename_all)
+        {
+            return Err(syn::Error::new(
+                rename_all.span(),
+                "Cannot support `rename_all` alongside `env_style` confik attribute",
+            ));
+        }
+
+        // E.g. `a__b` would be read from `A__B`, the same as field `b` of a field `a`.
+        for ident in fields.iter().filter_map(|field| field.ident.as_ref()) {
+            let name = ident.unraw().to_string();
+            if name.contains("__") || name.starts_with('_') || name.ends_with('_') {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "Field `{name}` would be ambiguous with the `__` separating nested \
+                         environment variable names, so can't be used with `env_style`"
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `from_repr` is on a non-generic enum without fields, whose builder derives a
+    /// `Deserialize` that isn't already wrapped for a tag.
+    fn check_from_repr(&self) -> syn::Result<()> {
+        let msg = "`from_repr` is only supported for enums without fields";
+        let ast::Data::Enum(variants) = &self.data else {
+            return Err(syn::Error::new(self.from_repr.span(), msg));
+        };
+        if let Some(variant) = variants.iter().find(|variant| !variant.fields.is_empty()) {
+            return Err(syn::Error::new(variant.span(), msg));
+        }
+
+        if !self.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                self.from_repr.span(),
+                "`from_repr` is only supported for types without generics",
+            ));
+        }
+
+        let unsupported = [
+            self.no_deserialize
+                .is_present()
+                .then(|| (self.no_deserialize.span(), "no_deserialize")),
+            self.tag().map(|tag| (tag.span(), "tag")),
+        ];
+        if let Some((span, name)) = unsupported.into_iter().flatten().next() {
+            return Err(syn::Error::new(
+                span,
+                format!("Cannot support `{name}` confik attribute alongside `from_repr`"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The name of the builder which also holds the builder of a previous version of the
+    /// configuration, see `#[confik(from_builder(...))]`.
+    fn migrating_builder_name(&self) -> Ident {
+        format_ident!("{}MigratingConfigBuilder", self.ident)
+    }
+
+    /// The path to serde in generated code, see `#[confik(serde_crate = "...")]`.
+    fn serde_path(&self) -> TokenStream {
+        self.serde_crate.as_ref().map_or_else(
+            || quote!(::confik::__exports::__serde),
+            ToTokens::to_token_stream,
+        )
+    }
+
     /// Defines the builder for the target.
     fn define_builder(&self) -> syn::Result<TokenStream> {
         let Self {
@@ -666,12 +2201,18 @@ impl RootImplementer {
             generics,
             vis,
             forward_serde,
+            forward,
             derive: additional_derives,
+            deny_unknown_fields,
             ..
         } = self;
 
         let builder_name = self.builder_name();
 
+        let deny_unknown_fields = self
+            .deny_unknown_fields()?
+            .then(|| quote_spanned!(deny_unknown_fields.span() => #[serde(deny_unknown_fields)]));
+
         let enum_or_struct_token = if data.is_struct() {
             syn::token::Struct {
                 span: target_name.span(),
@@ -684,11 +2225,13 @@ impl RootImplementer {
             .into_token_stream()
         };
 
+        let deserialize = !self.no_deserialize.is_present();
+
         let bracketed_data = match &self.data {
             ast::Data::Enum(variants) => {
                 let variants = variants
                     .iter()
-                    .map(VariantImplementer::define_builder)
+                    .map(|variant| VariantImplementer::define_builder(variant, deserialize))
                     .collect::<Result<Vec<_>, _>>()?;
 
                 quote_spanned! { target_name.span() =>
@@ -705,7 +2248,10 @@ impl RootImplementer {
             ast::Data::Struct(fields) => {
                 let field_vec = fields
                     .iter()
-                    .map(FieldImplementer::define_builder)
+                    .enumerate()
+                    .map(|(index, field)| {
+                        FieldImplementer::define_builder(None, index, field, deserialize)
+                    })
                     .collect::<Result<Vec<_>, _>>()?;
                 ast::Fields::new(fields.style, field_vec).into_token_stream()
             }
@@ -724,16 +2270,94 @@ impl RootImplementer {
 
         let (_impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
+        // The derived implementation is made inherent, to be wrapped by
+        // `impl_deserialize_from_repr`.
+        let remote = self
+            .from_repr
+            .is_present()
+            .then(|| quote_spanned!(self.from_repr.span() => #[serde(remote = "Self")]));
+
+        let serde = self.serde_path();
+        let serde_str = serde.to_string();
+
+        let env_style = self
+            .env_style
+            .is_present()
+            .then(|| quote!(#[serde(rename_all = "SCREAMING_SNAKE_CASE")]));
+
+        let derive_deserialize = deserialize.then(|| {
+            quote! {
+                #[derive(#serde::Deserialize)]
+                #[serde(crate = #serde_str)]
+                #remote
+                #deny_unknown_fields
+                #env_style
+                #forward_serde
+            }
+        });
+
         Ok(quote_spanned! { target_name.span() =>
-            #[derive(::std::default::Default, ::confik::__exports::__serde::Deserialize, #additional_derives )]
-            #[serde(crate = "::confik::__exports::__serde")]
-            #forward_serde
+            #[derive(::std::default::Default, #additional_derives )]
+            #derive_deserialize
+            #forward
             #vis #enum_or_struct_token #builder_name #type_generics #where_clause
                 #bracketed_data
             #terminator
         })
     }
 
+    /// Whether the builder should deny unknown fields, checking that no field is flattened, as serde
+    /// doesn't support the two together.
+    fn deny_unknown_fields(&self) -> syn::Result<bool> {
+        if !self.deny_unknown_fields.is_present() {
+            return Ok(false);
+        }
+
+        let flattened = match &self.data {
+            ast::Data::Enum(variants) => variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter())
+                .find(|field| field.flatten.is_present()),
+            ast::Data::Struct(fields) => fields.iter().find(|field| field.flatten.is_present()),
+        };
+
+        match flattened {
+            Some(field) => {
+                let msg =
+                    "Cannot support both `deny_unknown_fields` and `flatten` confik attributes";
+                let mut err = syn::Error::new(self.deny_unknown_fields.span(), msg);
+                err.combine(syn::Error::new(field.flatten.span(), msg));
+                Err(err)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Defines the types naming the merge keys of any fields with one.
+    fn define_merge_keys(&self) -> TokenStream {
+        let merge_keys = match &self.data {
+            ast::Data::Enum(variants) => variants
+                .iter()
+                .flat_map(|variant| {
+                    variant
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(index, field)| {
+                            FieldImplementer::define_merge_key(Some(&variant.ident), index, field)
+                        })
+                })
+                .collect::<Vec<_>>(),
+            ast::Data::Struct(fields) => fields
+                .iter()
+                .enumerate()
+                .filter_map(|(index, field)| FieldImplementer::define_merge_key(None, index, field))
+                .collect(),
+        };
+
+        quote!(#( #merge_keys )*)
+    }
+
     /// Implement the `ConfigurationBuilder::merge` method for our builder.
     fn impl_merge(&self) -> TokenStream {
         let Self { data, .. } = self;
@@ -773,12 +2397,57 @@ impl RootImplementer {
         }
     }
 
+    /// Warn about each secret field whose type doesn't protect its value in memory, if
+    /// `strict_secrets` is set.
+    ///
+    /// There's no stable way for a derive to emit warnings, so each is the use of a deprecated item.
+    fn impl_strict_secrets(&self) -> Option<TokenStream> {
+        if !self.strict_secrets.is_present() {
+            return None;
+        }
+
+        let fields = match &self.data {
+            ast::Data::Struct(fields) => fields.iter().enumerate().collect::<Vec<_>>(),
+            ast::Data::Enum(variants) => variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter().enumerate())
+                .collect(),
+        };
+
+        let warnings = fields
+            .into_iter()
+            .filter(|(_, field)| field.secret.is_some() && !is_secret_type(&field.ty))
+            .map(|(index, field)| {
+                let ty = &field.ty;
+                let cfg = field.cfg();
+                let note = format!(
+                    "`{}` is a secret, but its type doesn't protect its value in memory. Consider \
+                     `secrecy::SecretString` instead.",
+                    FieldIdent::new(&field.ident, index),
+                );
+
+                quote_spanned! { ty.span() =>
+                    #cfg
+                    const _: () = {
+                        #[deprecated(note = #note)]
+                        struct UnprotectedSecret;
+                        let _ = UnprotectedSecret;
+                    };
+                }
+            });
+
+        Some(quote!(#( #warnings )*))
+    }
+
     /// Implement the `ConfigurationBuilder::try_build` method for our builder.
     fn impl_try_build(&self) -> TokenStream {
         let Self { ident, data, .. } = self;
 
-        let field_build = match data {
-            ast::Data::Struct(fields) => {
+        let field_build = match (data, &self.one_of_variants) {
+            (ast::Data::Struct(fields), Some(variants)) => {
+                Self::impl_try_build_one_of(ident, fields, variants)
+            }
+            (ast::Data::Struct(fields), None) => {
                 let style = fields.style;
                 let fields = fields
                     .iter()
@@ -790,14 +2459,21 @@ impl RootImplementer {
                 let bracketed_fields = ast::Fields::new(style, fields).into_token_stream();
                 quote!(Ok(#ident #bracketed_fields))
             }
-            ast::Data::Enum(variants) => {
+            (ast::Data::Enum(variants), _) => {
+                let undefined = variants
+                    .iter()
+                    .find(|variant| variant.default.is_present())
+                    .map_or_else(
+                        || quote!(Self::ConfigBuilderUndefined => return Err(::confik::Error::MissingValue(<::confik::MissingValue as ::std::default::Default>::default()))),
+                        VariantImplementer::impl_try_build_default,
+                    );
                 let variants = variants
                     .iter()
                     .map(VariantImplementer::impl_try_build)
                     .collect::<Vec<_>>();
                 quote! {
                     Ok(match self {
-                        Self::ConfigBuilderUndefined => return Err(::confik::Error::MissingValue(<::confik::MissingValue as ::std::default::Default>::default())),
+                        #undefined,
                         #( #variants, )*
                     })
                 }
@@ -813,18 +2489,76 @@ impl RootImplementer {
         }
     }
 
-    /// Implement the `ConfigurationBuilder::contains_non_secret_data` method for our builder.
-    fn impl_contains_non_secret_data(&self) -> TokenStream {
-        let field_check = match &self.data {
-            ast::Data::Struct(fields) => {
-                let field_check = fields
-                    .iter()
-                    .enumerate()
-                    .map(|(index, field)| {
-                        FieldImplementer::impl_contains_non_secret_data(index, field, None)
-                    })
+    /// Builds the variant of a `one_of` enum whose section was provided, failing unless exactly one
+    /// was.
+    fn impl_try_build_one_of(
+        ident: &Ident,
+        sections: &ast::Fields<SpannedValue<FieldImplementer>>,
+        variants: &[Ident],
+    ) -> TokenStream {
+        let names = sections
+            .iter()
+            .map(|section| FieldIdent::new(&section.ident, 0).to_string())
+            .collect::<Vec<_>>();
+
+        let present = sections.iter().map(|section| {
+            let field = &section.ident;
+            quote!(self.#field.contains_non_secret_data().unwrap_or(true))
+        });
+
+        let build = sections
+            .iter()
+            .zip(variants)
+            .enumerate()
+            .map(|(index, (section, variant))| {
+                let build =
+                    FieldImplementer::impl_try_build(index, section, Style::Tuple, None, None);
+                quote! {
+                    if present[#index] {
+                        return Ok(#ident::#variant(#build));
+                    }
+                }
+            });
+
+        quote! {
+            let present = [#( #present ),*];
+            let sections: &'static [&'static str] = &[#( #names ),*];
+
+            if present.iter().filter(|present| **present).count() != 1 {
+                let found = sections
+                    .iter()
+                    .zip(present)
+                    .filter_map(|(section, present)| present.then_some(*section))
+                    .collect();
+                return Err(::confik::Error::NotOneOf(::confik::NotOneOf::new(sections, found)));
+            }
+
+            #( #build )*
+
+            ::std::unreachable!("exactly one section is present")
+        }
+    }
+
+    /// Implement the `ConfigurationBuilder::contains_non_secret_data` method for our builder.
+    fn impl_contains_non_secret_data(&self) -> TokenStream {
+        let field_check = match &self.data {
+            ast::Data::Struct(fields) => {
+                let field_check = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        let cfg = field.cfg();
+                        let check =
+                            FieldImplementer::impl_contains_non_secret_data(index, field, None);
+                        quote!(#cfg { contains |= #check?; })
+                    })
                     .collect::<Vec<_>>();
-                quote!(false #( | #field_check? )*)
+                quote! {{
+                    #[allow(unused_mut)]
+                    let mut contains = false;
+                    #( #field_check )*
+                    contains
+                }}
             }
             ast::Data::Enum(variants) => {
                 let variant_check = variants
@@ -845,6 +2579,321 @@ impl RootImplementer {
         }
     }
 
+    /// Implement the `ConfigurationBuilder::clear_env_ignored` method for our builder.
+    fn impl_clear_env_ignored(&self) -> TokenStream {
+        let field_clear = match &self.data {
+            ast::Data::Struct(fields) => {
+                let field_clear = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        let cfg = field.cfg();
+                        let clear = FieldImplementer::impl_clear_env_ignored(index, field, None);
+                        quote!(#cfg { #clear; })
+                    })
+                    .collect::<Vec<_>>();
+                quote!(#( #field_clear )*)
+            }
+            ast::Data::Enum(variants) => {
+                let variant_clear = variants
+                    .iter()
+                    .map(VariantImplementer::impl_clear_env_ignored)
+                    .collect::<Vec<_>>();
+                quote! { match self {
+                    Self::ConfigBuilderUndefined => {}
+                    #( #variant_clear, )*
+                }}
+            }
+        };
+
+        quote! {
+            fn clear_env_ignored(&mut self) {
+                #field_clear
+            }
+        }
+    }
+
+    /// Implement the `ConfigurationBuilder::check_env_only` method for our builder.
+    fn impl_check_env_only(&self) -> TokenStream {
+        let field_check = match &self.data {
+            ast::Data::Struct(fields) => {
+                let field_check = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        let cfg = field.cfg();
+                        let check = FieldImplementer::impl_check_env_only(index, field, None);
+                        quote!(#cfg { #check?; })
+                    })
+                    .collect::<Vec<_>>();
+                quote!(#( #field_check )*)
+            }
+            ast::Data::Enum(variants) => {
+                let variant_check = variants
+                    .iter()
+                    .map(VariantImplementer::impl_check_env_only)
+                    .collect::<Vec<_>>();
+                quote! { match self {
+                    Self::ConfigBuilderUndefined => {}
+                    #( #variant_check, )*
+                }}
+            }
+        };
+
+        quote! {
+            fn check_env_only(&self) -> ::std::result::Result<(), ::confik::UnexpectedSecret> {
+                #field_check
+                ::std::result::Result::Ok(())
+            }
+        }
+    }
+
+    /// Implement the `ConfigurationBuilder::check_source_kind` method for our builder.
+    fn impl_check_source_kind(&self) -> TokenStream {
+        let field_check = match &self.data {
+            ast::Data::Struct(fields) => {
+                let field_check = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        let cfg = field.cfg();
+                        let check = FieldImplementer::impl_check_source_kind(index, field, None);
+                        quote!(#cfg { #check?; })
+                    })
+                    .collect::<Vec<_>>();
+                quote!(#( #field_check )*)
+            }
+            ast::Data::Enum(variants) => {
+                let variant_check = variants
+                    .iter()
+                    .map(VariantImplementer::impl_check_source_kind)
+                    .collect::<Vec<_>>();
+                quote! { match self {
+                    Self::ConfigBuilderUndefined => {}
+                    #( #variant_check, )*
+                }}
+            }
+        };
+
+        quote! {
+            fn check_source_kind(
+                &self,
+                kind: ::confik::SourceKind,
+            ) -> ::std::result::Result<(), ::confik::UnexpectedSource> {
+                #field_check
+                ::std::result::Result::Ok(())
+            }
+        }
+    }
+
+    /// Implement the `ConfigurationBuilder::check_conflicts` method for our builder.
+    fn impl_check_conflicts(&self) -> TokenStream {
+        let conflict_check = match &self.data {
+            ast::Data::Struct(fields) => {
+                let field_check = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        let cfg = field.cfg();
+                        let check = FieldImplementer::impl_check_conflicts(index, field, false);
+                        quote!(#cfg { #check?; })
+                    })
+                    .collect::<Vec<_>>();
+                quote!(#( #field_check )*)
+            }
+            ast::Data::Enum(variants) => {
+                let variant_check = variants
+                    .iter()
+                    .map(VariantImplementer::impl_check_conflicts)
+                    .collect::<Vec<_>>();
+                quote! { match (self, other) {
+                    (Self::ConfigBuilderUndefined, _) | (_, Self::ConfigBuilderUndefined) => {}
+                    #( #variant_check, )*
+                    // Different variants are never merged, so always conflict.
+                    _ => return ::std::result::Result::Err(::confik::Conflict::default()),
+                }}
+            }
+        };
+
+        quote! {
+            fn check_conflicts(
+                &self,
+                other: &Self,
+            ) -> ::std::result::Result<(), ::confik::Conflict> {
+                #conflict_check
+                ::std::result::Result::Ok(())
+            }
+        }
+    }
+
+    /// The tag forwarded to serde for an enum, see [`Self::impl_deserialize_flattened`].
+    fn tag(&self) -> Option<&Expr> {
+        if !self.data.is_enum() {
+            return None;
+        }
+
+        self.forward_serde.as_ref()?.tag()
+    }
+
+    /// Implement `ConfigurationBuilder::__deserialize_flattened` for the builder of a tagged enum,
+    /// such that data without the tag is treated as undefined rather than an error, as it's for the
+    /// rest of the parent.
+    fn impl_deserialize_flattened(&self) -> Option<TokenStream> {
+        if self.no_deserialize.is_present() {
+            return None;
+        }
+
+        let tag = self.tag()?;
+
+        Some(quote! {
+            fn __deserialize_flattened<'de, D: ::confik::__exports::__serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> ::std::result::Result<Self, D::Error> {
+                ::confik::__deserialize_tagged(
+                    deserializer,
+                    #tag,
+                    <Self as ::confik::__exports::__serde::Deserialize>::deserialize,
+                )
+            }
+        })
+    }
+
+    /// Implement `Deserialize` for the builder of a `from_repr` enum, such that an integer is
+    /// matched against the discriminant of each of the target's variants, and anything else is
+    /// deserialized as usual.
+    fn impl_deserialize_from_repr(&self) -> Option<TokenStream> {
+        if !self.from_repr.is_present() {
+            return None;
+        }
+        let ast::Data::Enum(variants) = &self.data else {
+            return None;
+        };
+
+        let target_name = &self.ident;
+        let builder_name = self.builder_name();
+        let serde = self.serde_path();
+        let variants = variants.iter().map(|variant| &variant.ident);
+
+        Some(quote! {
+            impl<'de> #serde::Deserialize<'de> for #builder_name {
+                fn deserialize<D: #serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> ::std::result::Result<Self, D::Error> {
+                    ::confik::__deserialize_from_repr(
+                        deserializer,
+                        |repr| match repr {
+                            #(
+                                repr if repr == #target_name::#variants as ::std::primitive::i128 => {
+                                    ::std::option::Option::Some(Self::#variants)
+                                }
+                            )*
+                            _ => ::std::option::Option::None,
+                        },
+                        Self::deserialize,
+                    )
+                }
+            }
+        })
+    }
+
+    /// Define the builder holding both our builder and that of a previous version of the
+    /// configuration, which is built and converted if ours can't be built, if requested.
+    fn impl_from_builder(&self) -> Option<TokenStream> {
+        let RootFromBuilder { ty, with } = self.from_builder.as_deref()?;
+        let target_name = &self.ident;
+        let builder_name = self.builder_name();
+        let migrating_builder_name = self.migrating_builder_name();
+        let vis = &self.vis;
+        let serde = self.serde_path();
+
+        Some(quote_spanned! { ty.span() =>
+            #[derive(::std::default::Default)]
+            #vis struct #migrating_builder_name {
+                current: #builder_name,
+                previous: <#ty as ::confik::Configuration>::Builder,
+            }
+
+            impl<'de> #serde::Deserialize<'de> for #migrating_builder_name {
+                fn deserialize<D: #serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> ::std::result::Result<Self, D::Error> {
+                    let (current, previous) = ::confik::__deserialize_with_previous(deserializer)?;
+                    Ok(Self { current, previous })
+                }
+            }
+
+            impl ::confik::ConfigurationBuilder for #migrating_builder_name {
+                type Target = #target_name;
+
+                const ENV_STYLE: ::std::primitive::bool =
+                    <#builder_name as ::confik::ConfigurationBuilder>::ENV_STYLE;
+
+                fn merge(self, other: Self) -> Self {
+                    Self {
+                        current: self.current.merge(other.current),
+                        previous: self.previous.merge(other.previous),
+                    }
+                }
+
+                // The previous builder is only used if it has data, and then the error from our own
+                // is still reported if it can't be built either.
+                fn try_build(self) -> ::std::result::Result<Self::Target, ::confik::Error> {
+                    match self.current.try_build() {
+                        Err(err) if self.previous.contains_non_secret_data().unwrap_or(true) => {
+                            self.previous.try_build().map(#with).map_err(|_| err)
+                        }
+                        built => built,
+                    }
+                }
+
+                // Both are checked, so a secret found in either is reported.
+                fn contains_non_secret_data(&self) -> ::std::result::Result<::std::primitive::bool, ::confik::UnexpectedSecret> {
+                    let current = self.current.contains_non_secret_data()?;
+                    let previous = self.previous.contains_non_secret_data()?;
+                    Ok(current || previous)
+                }
+
+                fn clear_env_ignored(&mut self) {
+                    self.current.clear_env_ignored();
+                    self.previous.clear_env_ignored();
+                }
+
+                // Each is provided separately, so sources needn't support buffering their data.
+                fn __provide<S: ::confik::Source>(
+                    source: &S,
+                    provide: ::confik::__Provide,
+                ) -> ::confik::__Provided<Self> {
+                    ::confik::__provide_with_previous(source, provide).map(|provided| {
+                        provided.map(|provided| {
+                            provided.map(|(current, previous)| Self { current, previous })
+                        })
+                    })
+                }
+
+                fn check_env_only(&self) -> ::std::result::Result<(), ::confik::UnexpectedSecret> {
+                    self.current.check_env_only()?;
+                    self.previous.check_env_only()
+                }
+
+                fn check_source_kind(
+                    &self,
+                    kind: ::confik::SourceKind,
+                ) -> ::std::result::Result<(), ::confik::UnexpectedSource> {
+                    self.current.check_source_kind(kind)?;
+                    self.previous.check_source_kind(kind)
+                }
+
+                fn check_conflicts(
+                    &self,
+                    other: &Self,
+                ) -> ::std::result::Result<(), ::confik::Conflict> {
+                    self.current.check_conflicts(&other.current)?;
+                    self.previous.check_conflicts(&other.previous)
+                }
+            }
+        })
+    }
+
     /// Implement `ConfigurationBuilder` for our builder.
     fn impl_builder(&self) -> TokenStream {
         let Self {
@@ -858,6 +2907,17 @@ impl RootImplementer {
         let try_build = self.impl_try_build();
 
         let contains_non_secret_data = self.impl_contains_non_secret_data();
+        let clear_env_ignored = self.impl_clear_env_ignored();
+        let check_env_only = self.impl_check_env_only();
+        let check_source_kind = self.impl_check_source_kind();
+        let check_conflicts = self.impl_check_conflicts();
+        let deserialize_flattened = self.impl_deserialize_flattened();
+
+        let env_style = self.env_style.is_present().then(|| {
+            quote!(
+                const ENV_STYLE: ::std::primitive::bool = true;
+            )
+        });
 
         let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
@@ -865,11 +2925,132 @@ impl RootImplementer {
             impl #impl_generics ::confik::ConfigurationBuilder  for #builder_name #type_generics #where_clause {
                 type Target = #target_name #type_generics;
 
+                #env_style
+
                 #merge
 
                 #try_build
 
                 #contains_non_secret_data
+
+                #clear_env_ignored
+
+                #check_env_only
+
+                #check_source_kind
+
+                #check_conflicts
+
+                #deserialize_flattened
+            }
+        }
+    }
+
+    /// Implement the `Configuration::secret_paths` method for our target.
+    fn impl_secret_paths(&self) -> TokenStream {
+        let forward_serde = self.forward_serde.as_ref();
+
+        // Whether the layout of the data can't be known, so any secret makes the whole value secret,
+        // e.g. when it's deserialized through another type.
+        let mut redact_whole = forward_serde.map_or(false, |serde| {
+            serde.contains("from") || serde.contains("try_from")
+        });
+
+        let fields = match &self.data {
+            ast::Data::Struct(fields) => {
+                let rename_rule = if self.env_style.is_present() {
+                    Some(RenameRule::ScreamingSnake)
+                } else {
+                    forward_serde.and_then(|serde| serde.rename_rule("rename_all"))
+                };
+
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| (field, field.key(index, rename_rule)))
+                    .collect::<Vec<_>>()
+            }
+            ast::Data::Enum(variants) => {
+                let untagged = forward_serde.map_or(false, |serde| serde.contains("untagged"));
+                let content = forward_serde.and_then(|serde| serde.deserialize_name("content"));
+
+                let mut fields = Vec::new();
+                for variant in variants {
+                    let variant_serde = variant.forward_serde.as_ref();
+                    let variant_key = variant_serde
+                        .and_then(|serde| serde.deserialize_name("rename"))
+                        .unwrap_or_else(|| {
+                            let name = variant.ident.unraw().to_string();
+                            forward_serde
+                                .and_then(|serde| serde.rename_rule("rename_all"))
+                                .map_or(name.clone(), |rule| rule.apply_to_variant(&name))
+                        });
+                    let rename_rule = variant_serde
+                        .and_then(|serde| serde.rename_rule("rename_all"))
+                        .or_else(|| {
+                            forward_serde.and_then(|serde| serde.rename_rule("rename_all_fields"))
+                        });
+
+                    if variant_serde.map_or(false, ForwardSerde::deserializes_with) {
+                        redact_whole = true;
+                    }
+
+                    // The key the variant's data is under, or `None` if it's inline with the enum's,
+                    // as for internally tagged and untagged enums.
+                    let prefix = if untagged {
+                        None
+                    } else if self.tag().is_some() {
+                        content.clone()
+                    } else {
+                        Some(variant_key)
+                    };
+
+                    // A newtype variant's data is the field's, rather than a sequence of one.
+                    let newtype = variant.fields.style == Style::Tuple && variant.fields.len() == 1;
+
+                    for (index, field) in variant.fields.iter().enumerate() {
+                        let key = if newtype {
+                            None
+                        } else {
+                            field.key(index, rename_rule)
+                        };
+                        let key = match (&prefix, key) {
+                            (Some(prefix), Some(key)) => Some(format!("{prefix}.{key}")),
+                            (Some(prefix), None) => Some(prefix.clone()),
+                            // An inline secret is the enum's whole value.
+                            (None, None) if field.secret.is_some() => Some(String::new()),
+                            (None, key) => key,
+                        };
+                        fields.push((field, key));
+                    }
+                }
+                fields
+            }
+        };
+
+        let field_paths = fields.into_iter().map(|(field, key)| {
+            let cfg = field.cfg();
+            let paths = FieldImplementer::impl_secret_paths(field, key.as_deref());
+            quote!(#cfg { paths.extend(#paths); })
+        });
+
+        let redact_whole = redact_whole.then(|| {
+            quote! {
+                if !paths.is_empty() {
+                    return ::std::vec![::std::string::String::new()];
+                }
+            }
+        });
+
+        quote! {
+            fn secret_paths() -> ::std::vec::Vec<::std::string::String> {
+                ::confik::__list_secret_paths::<Self>(|| {
+                    #[allow(unused_mut)]
+                    let mut paths = ::std::vec::Vec::new();
+                    #( #field_paths )*
+                    #redact_whole
+                    paths
+                })
             }
         }
     }
@@ -881,14 +3062,254 @@ impl RootImplementer {
             generics,
             ..
         } = self;
-        let builder_name = self.builder_name();
+        let builder_name = match &self.from_builder {
+            Some(_) => self.migrating_builder_name(),
+            None => self.builder_name(),
+        };
         let builder = quote!(#builder_name #generics);
 
+        let secret_paths = self.impl_secret_paths();
+
+        // Lets a parent with `redact_display` format this with its secrets redacted too.
+        let fmt_redacted = self.redact_display.is_present().then(|| {
+            quote! {
+                fn __fmt_redacted(
+                    &self,
+                    f: &mut ::std::fmt::Formatter<'_>,
+                ) -> ::std::option::Option<::std::fmt::Result> {
+                    ::std::option::Option::Some(::std::fmt::Display::fmt(self, f))
+                }
+            }
+        });
+
         let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
         quote! {
             impl #impl_generics ::confik::Configuration for #target_name #type_generics  #where_clause {
                 type Builder = #builder;
+
+                #secret_paths
+
+                #fmt_redacted
+            }
+        }
+    }
+
+    /// Implement setters for each field of our builder, if requested.
+    fn impl_setters(&self) -> syn::Result<Option<TokenStream>> {
+        let Self {
+            data,
+            generics,
+            vis,
+            gen_setters,
+            ..
+        } = self;
+
+        if !gen_setters.is_present() {
+            return Ok(None);
+        }
+
+        let ast::Data::Struct(fields) = data else {
+            return Err(syn::Error::new(
+                gen_setters.span(),
+                "`gen_setters` is only supported for structs",
+            ));
+        };
+
+        let setters = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| FieldImplementer::define_setter(index, field, vis))
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let builder_name = self.builder_name();
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+        Ok(Some(quote! {
+            impl #impl_generics #builder_name #type_generics #where_clause {
+                #( #setters )*
+            }
+        }))
+    }
+
+    /// Implement `FromStr` for our target, if requested, parsing variant names in the same way as
+    /// the builder, i.e. following any serde renames.
+    fn impl_from_str(&self) -> syn::Result<Option<TokenStream>> {
+        let Self {
+            ident: target_name,
+            data,
+            generics,
+            gen_from_str,
+            ..
+        } = self;
+
+        if !gen_from_str.is_present() {
+            return Ok(None);
+        }
+
+        let msg = "`gen_from_str` is only supported for enums without fields";
+        let ast::Data::Enum(variants) = data else {
+            return Err(syn::Error::new(gen_from_str.span(), msg));
+        };
+        if let Some(variant) = variants.iter().find(|variant| !variant.fields.is_empty()) {
+            return Err(syn::Error::new(variant.span(), msg));
+        }
+
+        let builder_name = self.builder_name();
+        let serde = self.serde_path();
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+        Ok(Some(quote! {
+            impl #impl_generics ::std::str::FromStr for #target_name #type_generics #where_clause {
+                type Err = #serde::de::value::Error;
+
+                fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                    use #serde::de::{Error as _, IntoDeserializer as _};
+
+                    let builder: #builder_name #type_generics =
+                        #serde::Deserialize::deserialize(s.into_deserializer())?;
+                    ::confik::ConfigurationBuilder::try_build(builder).map_err(Self::Err::custom)
+                }
+            }
+        }))
+    }
+
+    /// Implement `ToBuilder` for our target, if requested, setting every field of the builder.
+    fn impl_to_builder(&self) -> syn::Result<Option<TokenStream>> {
+        let Self {
+            ident: target_name,
+            data,
+            generics,
+            gen_to_builder,
+            ..
+        } = self;
+
+        if !gen_to_builder.is_present() {
+            return Ok(None);
+        }
+
+        let builder_name = self.builder_name();
+
+        let to_builder = match data {
+            ast::Data::Struct(fields) if fields.is_empty() => quote!(#builder_name {}),
+            ast::Data::Struct(fields) => {
+                let style = fields.style;
+                let fields = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| {
+                        let ident = FieldIdent::new(&field.ident, index);
+                        FieldImplementer::impl_to_builder(index, field, style, quote!(&self.#ident))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+                let bracketed_fields = ast::Fields::new(style, fields).into_token_stream();
+                quote!(#builder_name #bracketed_fields)
+            }
+            ast::Data::Enum(variants) => {
+                let variants = variants
+                    .iter()
+                    .map(|variant| VariantImplementer::impl_to_builder(variant, &builder_name))
+                    .collect::<syn::Result<Vec<_>>>()?;
+                quote! {
+                    match self {
+                        #( #variants, )*
+                    }
+                }
+            }
+        };
+
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+        Ok(Some(quote! {
+            impl #impl_generics ::confik::ToBuilder for #target_name #type_generics #where_clause {
+                fn to_builder(&self) -> Self::Builder {
+                    #to_builder
+                }
+            }
+        }))
+    }
+
+    /// Implement `Display` for our target, if requested, which formats like `Debug` but with any
+    /// secrets redacted.
+    fn impl_redact_display(&self) -> Option<TokenStream> {
+        let Self {
+            ident: target_name,
+            generics,
+            data,
+            redact_display,
+            ..
+        } = self;
+
+        if !redact_display.is_present() {
+            return None;
+        }
+
+        let redacted_debug = match data {
+            ast::Data::Struct(fields) => {
+                let extract_us_fields = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| FieldImplementer::extract_for_match(index, field, "us"))
+                    .collect::<Vec<_>>();
+                let bracketed_extract_us_fields =
+                    ast::Fields::new(fields.style, extract_us_fields).into_token_stream();
+
+                let redacted_debug =
+                    FieldImplementer::impl_redacted_debug(target_name, fields, "us");
+
+                quote! {
+                    let Self #bracketed_extract_us_fields = self;
+                    #redacted_debug
+                }
+            }
+            ast::Data::Enum(variants) => {
+                let variants = variants
+                    .iter()
+                    .map(VariantImplementer::impl_redact_display)
+                    .collect::<Vec<_>>();
+
+                quote! {
+                    match self {
+                        #( #variants, )*
+                    }
+                }
+            }
+        };
+
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+        Some(quote! {
+            impl #impl_generics ::std::fmt::Display for #target_name #type_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    #redacted_debug
+                }
+            }
+        })
+    }
+
+    /// Implement `TryFrom<ConfigBuilder>` for our target, as a blanket implementation is not
+    /// possible.
+    fn impl_try_from_config_builder(&self) -> TokenStream {
+        let Self {
+            ident: target_name,
+            generics,
+            ..
+        } = self;
+
+        let mut try_from_generics = generics.clone();
+        try_from_generics
+            .params
+            .insert(0, syn::parse_quote!('__confik_sources));
+        let (impl_generics, _, _) = try_from_generics.split_for_impl();
+        let (_, type_generics, where_clause) = generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics ::std::convert::TryFrom<::confik::ConfigBuilder<'__confik_sources, Self>> for #target_name #type_generics #where_clause {
+                type Error = ::confik::Error;
+
+                fn try_from(mut builder: ::confik::ConfigBuilder<'__confik_sources, Self>) -> ::std::result::Result<Self, Self::Error> {
+                    builder.try_build()
+                }
             }
         }
     }
@@ -897,9 +3318,19 @@ impl RootImplementer {
 fn derive_macro_builder_inner(target_struct: &DeriveInput) -> syn::Result<proc_macro::TokenStream> {
     let implementer = RootImplementer::from_derive_input(target_struct)?;
     implementer.check_valid()?;
+    let implementer = implementer.into_one_of()?;
     let builder_struct = implementer.define_builder()?;
+    let merge_keys = implementer.define_merge_keys();
     let builder_impl = implementer.impl_builder();
     let target_impl = implementer.impl_target();
+    let try_from_impl = implementer.impl_try_from_config_builder();
+    let redact_display_impl = implementer.impl_redact_display();
+    let setters_impl = implementer.impl_setters()?;
+    let from_str_impl = implementer.impl_from_str()?;
+    let to_builder_impl = implementer.impl_to_builder()?;
+    let deserialize_from_repr_impl = implementer.impl_deserialize_from_repr();
+    let from_builder_impl = implementer.impl_from_builder();
+    let strict_secrets_warnings = implementer.impl_strict_secrets();
 
     let overall_lint_overrides = quote! {
         #[doc(hidden)] // crate docs should cover builders' uses.
@@ -921,19 +3352,128 @@ fn derive_macro_builder_inner(target_struct: &DeriveInput) -> syn::Result<proc_m
         )]
     };
 
+    // Optional impls, which must not leave the lint overrides dangling when absent.
+    let redact_display_impl = redact_display_impl.map(|redact_display_impl| {
+        quote! {
+            #impl_lint_overrides
+            #redact_display_impl
+        }
+    });
+    let setters_impl = setters_impl.map(|setters_impl| {
+        quote! {
+            #impl_lint_overrides
+            #setters_impl
+        }
+    });
+    let from_str_impl = from_str_impl.map(|from_str_impl| {
+        quote! {
+            #impl_lint_overrides
+            #from_str_impl
+        }
+    });
+    let to_builder_impl = to_builder_impl.map(|to_builder_impl| {
+        quote! {
+            #impl_lint_overrides
+            #to_builder_impl
+        }
+    });
+    let deserialize_from_repr_impl = deserialize_from_repr_impl.map(|deserialize_from_repr_impl| {
+        quote! {
+            #impl_lint_overrides
+            #deserialize_from_repr_impl
+        }
+    });
+    let from_builder_impl = from_builder_impl.map(|from_builder_impl| {
+        quote! {
+            #struct_lint_overrides
+            #impl_lint_overrides
+            #from_builder_impl
+        }
+    });
+
     let full_derive = quote! {
         #overall_lint_overrides
         const _: () = {
             #impl_lint_overrides
             #target_impl
 
+            #impl_lint_overrides
+            #try_from_impl
+
+            #redact_display_impl
+
+            #from_str_impl
+
+            #to_builder_impl
+
+            #merge_keys
+
             #struct_lint_overrides
             #builder_struct
 
             #impl_lint_overrides
             #builder_impl
+
+
+            #deserialize_from_repr_impl
+
+            #from_builder_impl
+
+            #setters_impl
+
+            #strict_secrets_warnings
         };
     };
 
     Ok(full_derive.into())
 }
+
+/// Whether `ty` is a type from `secrecy`, which zeroes its value on drop and redacts it from
+/// `Debug`, or an `Option` of one. Only the name is checked, as the path may be aliased.
+fn is_secret_type(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else {
+        return false;
+    };
+    let Some(segment) = ty.path.segments.last() else {
+        return false;
+    };
+
+    match segment.ident.to_string().as_str() {
+        "SecretBox" | "SecretSlice" | "SecretString" | "Secret" => true,
+        "Option" => match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args) => matches!(
+                args.args.first(),
+                Some(syn::GenericArgument::Type(inner)) if is_secret_type(inner)
+            ),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Converts a variant name, e.g. `MyStore` or `HTTPServer`, to a field name, e.g. `my_store` or
+/// `http_server`.
+fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (index, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            let prev = chars[index - 1];
+            let next_is_lower = chars
+                .get(index + 1)
+                .map_or(false, |next| next.is_lowercase());
+
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
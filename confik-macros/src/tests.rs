@@ -23,7 +23,68 @@ fn secret_attribute_parsing() {
             .expect("Didn't parse as struct")
             .fields[0]
             .secret
-            .is_present(),
+            .is_some(),
         "Failed to read secret, state: {implementer:?}"
     );
 }
+
+#[test]
+fn secret_classification_parsing() {
+    let input = r#"
+    #[derive(Configuration)]
+    struct Config {
+        #[confik(secret = "credential")]
+        field: String,
+    }
+    "#;
+
+    let parsed = parse_str(input).expect("Failed to parse input as rust code");
+    let implementer = RootImplementer::from_derive_input(&parsed)
+        .expect("Failed to read derive input into `RootImplementer`");
+    let secret = implementer
+        .data
+        .as_ref()
+        .take_struct()
+        .expect("Didn't parse as struct")
+        .fields[0]
+        .secret
+        .as_ref()
+        .expect("Failed to read secret");
+    assert_eq!(secret.classification.as_deref(), Some("credential"));
+}
+
+#[test]
+fn rename_rules() {
+    let rules = [
+        // As with serde, fields are assumed to already be `snake_case`.
+        ("lowercase", "api_key", "api_key"),
+        ("UPPERCASE", "api_key", "API_KEY"),
+        ("PascalCase", "api_key", "ApiKey"),
+        ("camelCase", "api_key", "apiKey"),
+        ("snake_case", "api_key", "api_key"),
+        ("SCREAMING_SNAKE_CASE", "api_key", "API_KEY"),
+        ("kebab-case", "api_key", "api-key"),
+        ("SCREAMING-KEBAB-CASE", "api_key", "API-KEY"),
+    ];
+    for (rule, field, expected) in rules {
+        let rule = RenameRule::from_name(rule).unwrap();
+        assert_eq!(rule.apply_to_field(field), expected, "{rule:?}");
+    }
+
+    let variants = [
+        ("lowercase", "ApiKey", "apikey"),
+        ("UPPERCASE", "ApiKey", "APIKEY"),
+        ("PascalCase", "ApiKey", "ApiKey"),
+        ("camelCase", "ApiKey", "apiKey"),
+        ("snake_case", "ApiKey", "api_key"),
+        ("SCREAMING_SNAKE_CASE", "ApiKey", "API_KEY"),
+        ("kebab-case", "ApiKey", "api-key"),
+        ("SCREAMING-KEBAB-CASE", "ApiKey", "API-KEY"),
+    ];
+    for (rule, variant, expected) in variants {
+        let rule = RenameRule::from_name(rule).unwrap();
+        assert_eq!(rule.apply_to_variant(variant), expected, "{rule:?}");
+    }
+
+    assert_eq!(RenameRule::from_name("Title Case"), None);
+}
@@ -28,6 +28,23 @@ fn compile_macros() {
     t.pass("tests/trybuild/22-dataless-types.rs");
     t.pass("tests/trybuild/23-where-clause.rs");
     t.pass("tests/trybuild/24-field-try-from.rs");
+    t.pass("tests/trybuild/25-classified-secret.rs");
+    t.pass("tests/trybuild/26-tuple-default.rs");
+    t.pass("tests/trybuild/27-merge-key.rs");
+    t.pass("tests/trybuild/28-redact-display.rs");
+    t.pass("tests/trybuild/29-gen-setters.rs");
+    t.pass("tests/trybuild/30-env-ignore.rs");
+    t.pass("tests/trybuild/31-gen-from-str.rs");
+    t.pass("tests/trybuild/32-try-from-map-err.rs");
+    t.pass("tests/trybuild/33-deny-unknown-fields.rs");
+    t.pass("tests/trybuild/34-forward-non-exhaustive.rs");
+    t.pass("tests/trybuild/35-gen-to-builder.rs");
+    t.pass("tests/trybuild/36-cfg-fields.rs");
+    t.pass("tests/trybuild/37-serde-crate.rs");
+    t.pass("tests/trybuild/38-no-deserialize.rs");
+    t.pass("tests/trybuild/39-no-serde-field.rs");
+    t.pass("tests/trybuild/40-default-const-path.rs");
+    t.pass("tests/trybuild/41-explicit-discriminants.rs");
     t.pass("tests/trybuild/pass-enum-untagged.rs");
 
     t.compile_fail("tests/trybuild/fail-default-parse.rs");
@@ -41,4 +58,25 @@ fn compile_macros() {
     t.compile_fail("tests/trybuild/fail-default-not-expression.rs");
     t.compile_fail("tests/trybuild/fail-from-and-try-from.rs");
     t.compile_fail("tests/trybuild/fail-try-from-not-implemented.rs");
+    t.compile_fail("tests/trybuild/fail-deny-unknown-fields-flatten.rs");
+    t.compile_fail("tests/trybuild/fail-gen-to-builder-from.rs");
+    t.compile_fail("tests/trybuild/fail-strict-secrets.rs");
+    t.compile_fail("tests/trybuild/fail-multiple-default-variants.rs");
+    t.compile_fail("tests/trybuild/fail-default-is-placeholder-not-secret.rs");
+    t.compile_fail("tests/trybuild/fail-cfg-unnamed-field.rs");
+    t.compile_fail("tests/trybuild/fail-no-deserialize-serde-attr.rs");
+    t.compile_fail("tests/trybuild/fail-no-serde-secret.rs");
+    t.compile_fail("tests/trybuild/fail-env-style-field-name.rs");
+    t.compile_fail("tests/trybuild/fail-log-hash-not-secret.rs");
+    t.compile_fail("tests/trybuild/fail-from-repr-fields.rs");
+    t.compile_fail("tests/trybuild/fail-secret-flatten.rs");
+}
+
+// `Configuration`'s hint for types which don't implement it is only shown from 1.78
+#[rustversion::since(1.78)]
+#[test]
+fn field_not_configuration() {
+    let t = trybuild::TestCases::new();
+
+    t.compile_fail("tests/trybuild/fail-field-not-configuration.rs");
 }
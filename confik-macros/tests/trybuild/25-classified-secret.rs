@@ -0,0 +1,21 @@
+//! Check that a secret can be given a classification, which is reported in the error.
+
+use confik::{ConfigBuilder, Error, TomlSource};
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+struct Config {
+    #[confik(secret = "credential")]
+    param: String,
+}
+
+fn main() {
+    let error = ConfigBuilder::<Config>::default()
+        .override_with(TomlSource::new(r#"param = "Hello World""#))
+        .try_build()
+        .expect_err("Can't build secret from Toml");
+    assert_matches::assert_matches!(
+        error,
+        Error::UnexpectedSecret(secret, _) if secret.classification() == Some("credential")
+            && secret.to_string() == "Found credential secret at path `param`"
+    );
+}
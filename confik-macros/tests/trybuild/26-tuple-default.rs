@@ -0,0 +1,37 @@
+//! Check that defaults can be used on unnamed (tuple struct) fields.
+
+use confik::{ConfigBuilder, TomlSource};
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+struct Pair(usize, #[confik(default = 1usize)] usize);
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+struct Defaults(#[confik(default = 1usize)] usize, #[confik(default)] usize);
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+struct Config {
+    pair: Pair,
+}
+
+fn main() {
+    let config = ConfigBuilder::<Config>::default()
+        .override_with(TomlSource::new("pair = [5, 6]"))
+        .try_build()
+        .expect("Failed to build when fully configured");
+    assert_eq!(config, Config { pair: Pair(5, 6) });
+
+    let config = ConfigBuilder::<Config>::default()
+        .override_with(TomlSource::new("pair = [5]"))
+        .try_build()
+        .expect("Failed to build with defaulted field");
+    assert_eq!(config, Config { pair: Pair(5, 1) });
+
+    ConfigBuilder::<Config>::default()
+        .try_build()
+        .expect_err("Non-defaulted field must be provided");
+
+    let defaults = ConfigBuilder::<Defaults>::default()
+        .try_build()
+        .expect("Failed to build with defaults");
+    assert_eq!(defaults, Defaults(1, 0));
+}
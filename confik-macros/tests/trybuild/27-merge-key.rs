@@ -0,0 +1,65 @@
+//! Check that `merge_key` can be used on named, unnamed, and enum variant fields.
+
+use confik::{ConfigBuilder, TomlSource};
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+struct Item {
+    id: usize,
+    value: usize,
+}
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+struct Named {
+    #[confik(merge_key = "id")]
+    items: Vec<Item>,
+}
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+struct Unnamed(#[confik(merge_key = "id")] Vec<Item>);
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+enum Choice {
+    Named {
+        #[confik(merge_key = "id")]
+        items: Vec<Item>,
+    },
+    Unnamed(#[confik(merge_key = "id")] Vec<Item>),
+}
+
+#[derive(confik::Configuration, Debug, PartialEq)]
+struct Config {
+    named: Named,
+    unnamed: Unnamed,
+    choice: Choice,
+}
+
+fn main() {
+    let config = ConfigBuilder::<Config>::default()
+        .override_with(TomlSource::new(
+            r#"
+            named = { items = [{ id = 1, value = 1 }, { id = 2, value = 2 }] }
+            unnamed = [{ id = 1, value = 1 }]
+            choice = { Unnamed = [{ id = 1, value = 1 }] }
+            "#,
+        ))
+        .override_with(TomlSource::new(
+            r#"
+            named = { items = [{ id = 2, value = 3 }] }
+            unnamed = [{ id = 2, value = 2 }]
+            choice = { Unnamed = [{ id = 1, value = 2 }] }
+            "#,
+        ))
+        .try_build()
+        .expect("Failed to merge by key");
+
+    assert_eq!(
+        config,
+        Config {
+            named: Named {
+                items: vec![Item { id: 1, value: 1 }, Item { id: 2, value: 3 }],
+            },
+            unnamed: Unnamed(vec![Item { id: 1, value: 1 }, Item { id: 2, value: 2 }]),
+            choice: Choice::Unnamed(vec![Item { id: 1, value: 2 }]),
+        }
+    );
+}
@@ -0,0 +1,43 @@
+//! Check that `redact_display` formats all kinds of fields, redacting secrets.
+
+#[derive(confik::Configuration, Debug)]
+#[confik(redact_display)]
+struct Unnamed(usize, #[confik(secret)] String);
+
+#[derive(confik::Configuration, Debug)]
+#[confik(redact_display)]
+struct Unit;
+
+#[derive(confik::Configuration, Debug)]
+#[confik(redact_display)]
+enum Auth {
+    None,
+    Token(#[confik(secret)] String),
+    Basic {
+        user: String,
+        #[confik(secret)]
+        password: String,
+    },
+}
+
+fn main() {
+    assert_eq!(
+        Unnamed(1, "hunter2".to_owned()).to_string(),
+        r#"Unnamed(1, "[redacted]")"#
+    );
+    assert_eq!(Unit.to_string(), "Unit");
+
+    assert_eq!(Auth::None.to_string(), "None");
+    assert_eq!(
+        Auth::Token("hunter2".to_owned()).to_string(),
+        r#"Token("[redacted]")"#
+    );
+    assert_eq!(
+        Auth::Basic {
+            user: "user".to_owned(),
+            password: "hunter2".to_owned(),
+        }
+        .to_string(),
+        r#"Basic { user: "user", password: "[redacted]" }"#
+    );
+}
@@ -0,0 +1,51 @@
+//! Check that `gen_setters` generates a setter on the builder for each field.
+
+use confik::{Configuration, ConfigurationBuilder};
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(gen_setters)]
+struct Inner {
+    value: usize,
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(gen_setters)]
+struct Config {
+    host: String,
+    port: u16,
+    #[confik(secret)]
+    password: String,
+    inner: Inner,
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(gen_setters)]
+struct Pair(usize, #[confik(default)] usize);
+
+fn main() {
+    let inner = <Inner as Configuration>::Builder::default().with_value(5usize);
+
+    let config = <Config as Configuration>::Builder::default()
+        .with_host("localhost".to_owned())
+        .with_port(8080u16)
+        .with_password("hunter2".to_owned())
+        .with_inner(inner)
+        .try_build()
+        .expect("All fields are set");
+
+    assert_eq!(
+        config,
+        Config {
+            host: "localhost".to_owned(),
+            port: 8080,
+            password: "hunter2".to_owned(),
+            inner: Inner { value: 5 },
+        }
+    );
+
+    let pair = <Pair as Configuration>::Builder::default()
+        .with_0(1usize)
+        .try_build()
+        .expect("Second field is defaulted");
+    assert_eq!(pair, Pair(1, 0));
+}
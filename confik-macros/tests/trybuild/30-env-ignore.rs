@@ -0,0 +1,75 @@
+//! Check that `env_ignore` fields are cleared from the builder, including inside enums, secrets,
+//! and containers.
+
+use std::collections::HashMap;
+
+use confik::{Configuration, ConfigurationBuilder};
+
+#[derive(Configuration, Debug, PartialEq)]
+struct Inner {
+    #[confik(env_ignore, default)]
+    salt: String,
+    rounds: u32,
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+enum Mode {
+    Plain,
+    Hashed(#[confik(env_ignore, default)] String, u32),
+    Nested { inner: Inner },
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+struct Config {
+    #[confik(secret)]
+    inner: Inner,
+    list: Vec<Inner>,
+    map: HashMap<String, Inner>,
+    modes: Vec<Mode>,
+    #[confik(env_ignore, default = 80u16)]
+    port: u16,
+}
+
+fn main() {
+    let toml = r#"
+        port = 8080
+        modes = ["Plain", { Hashed = ["abc", 1] }, { Nested = { inner = { salt = "abc", rounds = 2 } } }]
+
+        [inner]
+        salt = "abc"
+        rounds = 3
+
+        [[list]]
+        salt = "abc"
+        rounds = 4
+
+        [map.a]
+        salt = "abc"
+        rounds = 5
+    "#;
+
+    let mut builder = toml::from_str::<<Config as Configuration>::Builder>(toml).unwrap();
+    builder.clear_env_ignored();
+
+    let config = builder.try_build().expect("Ignored fields are defaulted");
+
+    let inner = |rounds| Inner {
+        salt: String::new(),
+        rounds,
+    };
+
+    assert_eq!(
+        config,
+        Config {
+            inner: inner(3),
+            list: vec![inner(4)],
+            map: HashMap::from([("a".to_owned(), inner(5))]),
+            modes: vec![
+                Mode::Plain,
+                Mode::Hashed(String::new(), 1),
+                Mode::Nested { inner: inner(2) },
+            ],
+            port: 80,
+        }
+    );
+}
@@ -0,0 +1,33 @@
+//! Check that `gen_from_str` parses variants in the same way as the builder, following serde
+//! renames.
+
+use confik::{Configuration, TomlSource};
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(gen_from_str, forward_serde(rename_all = "lowercase"))]
+enum DatabaseKind {
+    Mysql,
+    Postgres,
+    #[confik(forward_serde(rename = "sqlite3"))]
+    Sqlite,
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+struct Config {
+    database: DatabaseKind,
+}
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(r#"database = "postgres""#))
+        .try_build()
+        .expect("Valid variant");
+    assert_eq!(config.database, DatabaseKind::Postgres);
+
+    assert_eq!("postgres".parse(), Ok(DatabaseKind::Postgres));
+    assert_eq!("mysql".parse(), Ok(DatabaseKind::Mysql));
+    assert_eq!("sqlite3".parse(), Ok(DatabaseKind::Sqlite));
+
+    let err = "Postgres".parse::<DatabaseKind>().unwrap_err();
+    assert!(err.to_string().contains("unknown variant `Postgres`"), "{err}");
+}
@@ -0,0 +1,65 @@
+//! Check that `map_err` transforms the error from a `try_from` conversion
+use std::error::Error as _;
+
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, Configuration, PartialEq, Eq)]
+struct Config {
+    #[confik(try_from = A, map_err = to_port_error)]
+    port: u16,
+}
+
+#[derive(Debug, Default, serde::Deserialize, confik::Configuration)]
+struct A(usize);
+
+#[derive(Debug)]
+struct E;
+
+impl std::fmt::Display for E {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("out of range")
+    }
+}
+
+impl std::error::Error for E {}
+
+impl TryFrom<A> for u16 {
+    type Error = E;
+
+    fn try_from(a: A) -> Result<Self, Self::Error> {
+        a.0.try_into().map_err(|_| E)
+    }
+}
+
+#[derive(Debug)]
+struct PortError(E);
+
+impl std::fmt::Display for PortError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "port is {}", self.0)
+    }
+}
+
+impl std::error::Error for PortError {}
+
+fn to_port_error(err: E) -> PortError {
+    PortError(err)
+}
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("port = 80"))
+        .try_build()
+        .expect("Failed to build with an in-range port");
+    assert_eq!(Config { port: 80 }, config);
+
+    let err = Config::builder()
+        .override_with(TomlSource::new("port = 100000"))
+        .try_build()
+        .expect_err("Built with an out-of-range port");
+    assert!(matches!(err, confik::Error::TryInto(_)));
+    assert_eq!(
+        err.source().expect("Missing error source").to_string(),
+        "port is out of range"
+    );
+}
@@ -0,0 +1,25 @@
+//! Check that `deny_unknown_fields` rejects keys which don't match any field
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, Configuration, PartialEq, Eq)]
+#[confik(deny_unknown_fields)]
+struct Config {
+    port: u16,
+}
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("port = 80"))
+        .try_build()
+        .expect("Failed to build with only known keys");
+    assert_eq!(Config { port: 80 }, config);
+
+    let err = Config::builder()
+        .override_with(TomlSource::new("port = 80\nprot = 81"))
+        .try_build()
+        .expect_err("Built with an unknown key");
+    assert!(
+        format!("{err:?}").contains("unknown field `prot`"),
+        "Unexpected error: {err:?}"
+    );
+}
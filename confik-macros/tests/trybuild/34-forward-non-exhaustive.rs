@@ -0,0 +1,36 @@
+//! Check that attributes can be forwarded to the builder, e.g. so that it's `#[non_exhaustive]`
+//! alongside a `#[non_exhaustive]` target.
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, Configuration, PartialEq, Eq)]
+#[confik(forward(non_exhaustive))]
+#[non_exhaustive]
+struct Config {
+    name: String,
+    mode: Mode,
+}
+
+#[derive(Debug, Configuration, PartialEq, Eq)]
+#[confik(forward(non_exhaustive, doc = "The builder for `Mode`."))]
+#[non_exhaustive]
+enum Mode {
+    Plain,
+    Hashed { rounds: u32 },
+}
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(
+            "name = \"app\"\n[mode.Hashed]\nrounds = 12",
+        ))
+        .try_build()
+        .expect("Failed to build a non-exhaustive config");
+
+    assert_eq!(
+        config,
+        Config {
+            name: String::from("app"),
+            mode: Mode::Hashed { rounds: 12 },
+        }
+    );
+}
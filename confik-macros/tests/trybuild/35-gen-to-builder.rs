@@ -0,0 +1,43 @@
+//! Check that `gen_to_builder` supports each shape of type, including secrets.
+
+use confik::{Configuration, ConfigurationBuilder, ToBuilder};
+
+#[derive(Configuration, Debug, Clone, PartialEq)]
+#[confik(gen_to_builder)]
+struct Unit;
+
+#[derive(Configuration, Debug, Clone, PartialEq)]
+#[confik(gen_to_builder)]
+struct Tuple(u16, #[confik(secret)] String);
+
+#[derive(Configuration, Debug, Clone, PartialEq)]
+#[confik(gen_to_builder)]
+enum Backend {
+    Memory,
+    Disk { path: String },
+    Remote(Tuple),
+}
+
+#[derive(Configuration, Debug, Clone, PartialEq)]
+#[confik(gen_to_builder)]
+struct Config {
+    unit: Unit,
+    backends: Vec<Backend>,
+    port: Option<u8>,
+}
+
+fn main() {
+    let config = Config {
+        unit: Unit,
+        backends: vec![
+            Backend::Memory,
+            Backend::Disk {
+                path: "/data".to_owned(),
+            },
+            Backend::Remote(Tuple(443, "hunter2".to_owned())),
+        ],
+        port: None,
+    };
+
+    assert_eq!(config.to_builder().try_build().unwrap(), config);
+}
@@ -0,0 +1,68 @@
+//! Check that `cfg` attributes on fields gate the builder's fields in the same way, whether they're
+//! enabled (`all()`) or disabled (`any()`).
+
+use confik::{Configuration, TomlSource};
+
+#[derive(Configuration, Debug, Clone, PartialEq)]
+#[confik(gen_setters, gen_to_builder, redact_display)]
+struct Config {
+    host: String,
+    #[cfg(all())]
+    #[confik(secret, default = "hunter2")]
+    password: String,
+    #[cfg(any())]
+    removed: NotConfiguration,
+    #[cfg(any())]
+    #[confik(merge_key = "name")]
+    plugins: Vec<NotConfiguration>,
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+enum Backend {
+    Disk {
+        path: String,
+        #[cfg(any())]
+        removed: NotConfiguration,
+        #[cfg(all())]
+        #[confik(default = 4)]
+        threads: u8,
+    },
+}
+
+/// Fails to compile if anything is generated for the disabled fields.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+struct NotConfiguration;
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("host = \"localhost\""))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            host: "localhost".to_owned(),
+            password: "hunter2".to_owned(),
+        }
+    );
+    assert_eq!(
+        config.to_string(),
+        r#"Config { host: "localhost", password: "[redacted]" }"#
+    );
+    assert_eq!(Config::secret_paths(), ["password"]);
+
+    let backend = Backend::builder()
+        .override_with(TomlSource::new("[Disk]\npath = \"/data\""))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(
+        backend,
+        Backend::Disk {
+            path: "/data".to_owned(),
+            threads: 4,
+        }
+    );
+}
@@ -0,0 +1,52 @@
+//! Check that `serde_crate` replaces the path to serde used by the builder, e.g. for a vendored or
+//! renamed serde.
+
+use confik::{Configuration, TomlSource};
+
+mod vendor {
+    pub use ::serde as vendored_serde;
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(serde_crate = "crate::vendor::vendored_serde", gen_from_str)]
+enum Level {
+    Debug,
+    Info,
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(
+    serde_crate = "crate::vendor::vendored_serde",
+    forward_serde(tag = "type")
+)]
+enum Output {
+    Stdout,
+    File { path: String },
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(serde_crate = "crate::vendor::vendored_serde")]
+struct Config {
+    level: Level,
+    output: Output,
+}
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(
+            "level = \"Info\"\n[output]\ntype = \"File\"\npath = \"/var/log/app.log\"",
+        ))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            level: Level::Info,
+            output: Output::File {
+                path: "/var/log/app.log".to_owned(),
+            },
+        }
+    );
+    assert_eq!("Debug".parse(), Ok(Level::Debug));
+}
@@ -0,0 +1,49 @@
+//! Check that `no_deserialize` leaves the builder without a `Deserialize` implementation, so that
+//! one can be written by hand.
+
+use confik::{Configuration, TomlSource};
+use serde::{Deserialize, Deserializer};
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(no_deserialize)]
+pub struct Address {
+    host: String,
+    port: u16,
+}
+
+/// Accepts a `host:port` string instead of a table.
+impl<'de> Deserialize<'de> for <Address as Configuration>::Builder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        let (host, port) = address
+            .split_once(':')
+            .ok_or_else(|| serde::de::Error::custom("expected `host:port`"))?;
+
+        Ok(Self {
+            host: Some(host.to_owned()),
+            port: Some(port.parse().map_err(serde::de::Error::custom)?),
+        })
+    }
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+struct Config {
+    address: Address,
+}
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("address = \"localhost:8080\""))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            address: Address {
+                host: "localhost".to_owned(),
+                port: 8080,
+            },
+        }
+    );
+}
@@ -0,0 +1,42 @@
+//! Check that a `no_serde` field's type needn't implement `Deserialize`, and that it's built from
+//! its default or a setter, ignoring any sources.
+
+use std::sync::Arc;
+
+use confik::{Configuration, ConfigurationBuilder, TomlSource};
+
+/// Only ever constructed in code.
+#[derive(Debug, Clone, PartialEq)]
+struct Handler {
+    name: &'static str,
+}
+
+#[derive(Configuration, Debug)]
+#[confik(gen_setters)]
+struct Config {
+    port: u16,
+
+    #[confik(no_serde, default = Arc::new(Handler { name: "default" }))]
+    handler: Arc<Handler>,
+
+    #[confik(no_serde, default)]
+    callback: Option<fn(u16) -> u16>,
+}
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("port = 80\nhandler = \"ignored\""))
+        .try_build()
+        .unwrap();
+    assert_eq!(config.port, 80);
+    assert_eq!(config.handler.name, "default");
+
+    let config = <Config as Configuration>::Builder::default()
+        .with_port(8080u16)
+        .with_handler(Arc::new(Handler { name: "custom" }))
+        .with_callback(Some((|port| port + 1) as fn(u16) -> u16))
+        .try_build()
+        .unwrap();
+    assert_eq!(config.handler.name, "custom");
+    assert_eq!((config.callback.unwrap())(config.port), 8081);
+}
@@ -0,0 +1,58 @@
+//! Check that defaults can refer to `const`s and `static`s by path, converted with `Into` when
+//! their type differs from the field's.
+
+use confik::{Configuration, TomlSource};
+
+mod defaults {
+    pub const PORT: u16 = 8080;
+    pub const HOST: &str = "localhost";
+    pub static WORKERS: u8 = 4;
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+#[serde(transparent)]
+struct Port(u16);
+
+impl From<u16> for Port {
+    fn from(port: u16) -> Self {
+        Self(port)
+    }
+}
+
+impl Configuration for Port {
+    type Builder = Option<Self>;
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+struct Config {
+    #[confik(default = crate::defaults::PORT)]
+    port: Port,
+
+    #[confik(default = defaults::HOST)]
+    host: String,
+
+    #[confik(default = self::defaults::WORKERS)]
+    workers: usize,
+
+    #[confik(default = defaults::PORT)]
+    admin_port: Option<u16>,
+}
+
+fn main() {
+    let config = Config::builder().try_build().expect("Failed to build with defaults");
+    assert_eq!(
+        config,
+        Config {
+            port: Port(8080),
+            host: "localhost".to_owned(),
+            workers: 4,
+            admin_port: Some(8080),
+        }
+    );
+
+    let config = Config::builder()
+        .override_with(TomlSource::new("port = 80"))
+        .try_build()
+        .expect("Failed to build when configured");
+    assert_eq!(config.port, Port(80));
+}
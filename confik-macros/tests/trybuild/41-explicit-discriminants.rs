@@ -0,0 +1,33 @@
+//! Check that enums with explicit discriminants can be derived, including when the builder's
+//! undefined variant would collide if it was numbered after the last one.
+
+use confik::{Configuration, TomlSource};
+
+#[derive(Configuration, Debug, PartialEq)]
+#[repr(i32)]
+enum Priority {
+    High = 1,
+    Low = 0,
+}
+
+#[derive(Configuration, Debug, PartialEq)]
+#[confik(from_repr)]
+enum Level {
+    Minus = -1,
+    Zero,
+}
+
+#[derive(Configuration, Debug)]
+struct Config {
+    priority: Priority,
+    level: Level,
+}
+
+fn main() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("priority = \"Low\"\nlevel = -1"))
+        .try_build()
+        .expect("Failed to build");
+    assert_eq!(config.priority, Priority::Low);
+    assert_eq!(config.level, Level::Minus);
+}
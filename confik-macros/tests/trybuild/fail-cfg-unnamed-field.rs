@@ -0,0 +1,6 @@
+use confik::Configuration;
+
+#[derive(Configuration)]
+struct Config(#[cfg(all())] u8, String);
+
+fn main() {}
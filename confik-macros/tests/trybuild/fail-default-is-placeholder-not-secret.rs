@@ -0,0 +1,7 @@
+#[derive(confik::Configuration)]
+struct Config {
+    #[confik(default = "hunter2", default_is_placeholder)]
+    _password: String,
+}
+
+fn main() {}
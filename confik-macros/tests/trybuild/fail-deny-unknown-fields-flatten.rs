@@ -0,0 +1,14 @@
+//! Check that `deny_unknown_fields` can't be used alongside `flatten`
+use std::collections::HashMap;
+
+use confik::Configuration;
+
+#[derive(Debug, Configuration)]
+#[confik(deny_unknown_fields)]
+struct Config {
+    host: String,
+    #[confik(flatten)]
+    extra: HashMap<String, String>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use confik::Configuration;
+
+#[derive(Configuration)]
+#[confik(env_style)]
+struct Config {
+    database__host: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use confik::Configuration;
+
+struct Level(u8);
+
+fn field<T: Configuration>() {}
+
+fn main() {
+    field::<Level>();
+}
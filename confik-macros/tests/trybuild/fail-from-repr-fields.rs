@@ -0,0 +1,10 @@
+use confik::Configuration;
+
+#[derive(Configuration)]
+#[confik(from_repr)]
+enum Backend {
+    Memory,
+    File(String),
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+use confik::Configuration;
+
+#[derive(Configuration)]
+#[confik(gen_to_builder)]
+struct Config {
+    #[confik(from = String)]
+    path: PathBuf,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use confik::Configuration;
+
+#[derive(Configuration)]
+#[confik(redact_display)]
+struct Config {
+    #[confik(log_hash)]
+    api_key: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[derive(confik::Configuration)]
+enum Level {
+    #[confik(default)]
+    Debug,
+    #[confik(default)]
+    Info,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use confik::Configuration;
+
+#[derive(Configuration)]
+#[confik(no_deserialize)]
+struct Config {
+    #[confik(forward_serde(rename = "other"))]
+    field: String,
+}
+
+#[derive(Configuration)]
+#[confik(no_deserialize, deny_unknown_fields)]
+struct Strict {
+    field: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use confik::Configuration;
+
+#[derive(Configuration)]
+struct Config {
+    #[confik(no_serde, secret)]
+    token: String,
+}
+
+fn main() {}
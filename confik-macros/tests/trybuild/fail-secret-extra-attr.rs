@@ -1,6 +1,6 @@
 #[derive(confik::Configuration)]
 struct Config {
-    #[confik(secret = "foo")]
+    #[confik(secret = 5)]
     _param: String,
 }
 
@@ -0,0 +1,14 @@
+use confik::Configuration;
+
+#[derive(Configuration)]
+struct Database {
+    password: String,
+}
+
+#[derive(Configuration)]
+struct Config {
+    #[confik(secret, flatten)]
+    database: Database,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#![deny(deprecated)]
+
+use confik::Configuration;
+
+#[derive(Configuration)]
+#[confik(strict_secrets)]
+#[allow(dead_code)]
+struct Config {
+    #[confik(secret)]
+    api_key: String,
+    name: String,
+}
+
+fn main() {}
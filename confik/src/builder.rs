@@ -11,14 +11,26 @@
 
 use std::{marker::PhantomData, mem};
 
-use confik::sources::DefaultSource;
-
+#[cfg(feature = "json")]
+use crate::dump::{self, Format};
 use crate::{
-    build_from_sources,
-    sources::{DynSource, Source},
-    Configuration, Error,
+    build_from_sources, build_from_sources_lenient, build_from_sources_timed,
+    sources::{BuilderSource, DefaultSource, DynSource, Source},
+    BuildTimings, Configuration, ConfigurationBuilder, Error, ToBuilder,
 };
 
+/// Which sources take precedence when more than one provides the same value, see
+/// [`ConfigBuilder::precedence`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Precedence {
+    /// Sources added later override those added earlier. This is the default.
+    #[default]
+    LastWins,
+
+    /// Sources added earlier override those added later.
+    FirstWins,
+}
+
 /// Used to accumulate ordered sources from which its `Target` is to be built.
 ///
 /// An instance of this can be created via [`Configuration::builder`] or
@@ -67,9 +79,42 @@ use crate::{
 /// assert_eq!(config.param, "Hello World");
 /// # }
 /// ```
+///
+/// Using [`TryFrom`], which is implemented for all types using the derive macro:
+///
+/// ```
+/// # #[cfg(feature = "toml")]
+/// # {
+/// use confik::{Configuration, TomlSource};
+///
+/// #[derive(Debug, PartialEq, Configuration)]
+/// struct MyConfigType {
+///     param: String,
+/// }
+///
+/// fn load() -> Result<MyConfigType, confik::Error> {
+///     let mut builder = MyConfigType::builder();
+///     builder.override_with(TomlSource::new(r#"param = "Hello World""#));
+///
+///     let config = MyConfigType::try_from(builder)?;
+///     Ok(config)
+/// }
+///
+/// assert_eq!(load().unwrap().param, "Hello World");
+/// # }
+/// ```
 pub struct ConfigBuilder<'a, Target: Configuration> {
     sources: Vec<Box<dyn DynSource<Target::Builder> + 'a>>,
 
+    /// Falls back to these values, with the lowest priority, see [`ConfigBuilder::with_base`].
+    base: Option<Target::Builder>,
+
+    /// Which of the sources take precedence.
+    precedence: Precedence,
+
+    /// Whether two sources providing the same value is an error.
+    forbid_conflicts: bool,
+
     /// Use the generic parameter
     _phantom: PhantomData<fn() -> Target>,
 }
@@ -77,7 +122,8 @@ pub struct ConfigBuilder<'a, Target: Configuration> {
 impl<'a, Target: Configuration> ConfigBuilder<'a, Target> {
     /// Add a single [`Source`] to the list of sources.
     ///
-    /// The source is added at the end of the list, overriding existing sources.
+    /// The source is added at the end of the list, overriding existing sources, unless
+    /// [`precedence`](Self::precedence) is [`Precedence::FirstWins`].
     ///
     /// ```
     /// # #[cfg(feature = "toml")]
@@ -102,18 +148,538 @@ impl<'a, Target: Configuration> ConfigBuilder<'a, Target> {
         self
     }
 
+    /// Add an [`EnvSource`](crate::EnvSource) reading all environment variables, overriding
+    /// existing sources.
+    ///
+    /// Shorthand for `override_with(EnvSource::new())`. See
+    /// [`override_with_env_prefixed`](Self::override_with_env_prefixed) for an example.
+    #[cfg(feature = "env")]
+    pub fn override_with_env(&mut self) -> &mut Self {
+        self.override_with(crate::EnvSource::new())
+    }
+
+    /// Add an [`EnvSource`](crate::EnvSource) reading environment variables starting with
+    /// `prefix`, overriding existing sources.
+    ///
+    /// Shorthand for `override_with(EnvSource::new().with_prefix(prefix))`. Adding the environment
+    /// last, after any files, is the recommended way to let deployments override file-based
+    /// configuration.
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, TomlSource};
+    ///
+    /// #[derive(Debug, PartialEq, Configuration)]
+    /// struct MyConfigType {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let config = temp_env::with_var("APP_PORT", Some("8080"), || {
+    ///     MyConfigType::builder()
+    ///         .override_with(TomlSource::new("host = \"localhost\"\nport = 80"))
+    ///         .override_with_env_prefixed("APP_")
+    ///         .try_build()
+    ///         .expect("Failed to build")
+    /// });
+    ///
+    /// assert_eq!(config.host, "localhost");
+    /// assert_eq!(config.port, 8080);
+    /// # }
+    /// ```
+    #[cfg(feature = "env")]
+    pub fn override_with_env_prefixed(&mut self, prefix: &'a str) -> &mut Self {
+        self.override_with(crate::EnvSource::new().with_prefix(prefix))
+    }
+
+    /// Add an already-built partial builder as a source, e.g. for defaults set in code or in tests.
+    ///
+    /// As with [`override_with`](Self::override_with), it overrides existing sources. The builder
+    /// is allowed to contain secrets.
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, FileSource};
+    ///
+    /// #[derive(Debug, PartialEq, Configuration)]
+    /// #[confik(gen_setters)]
+    /// struct MyConfigType {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("app.toml");
+    /// std::fs::write(&path, "port = 8080").unwrap();
+    ///
+    /// let defaults = <MyConfigType as Configuration>::Builder::default()
+    ///     .with_host("localhost".to_owned())
+    ///     .with_port(80u16);
+    ///
+    /// let config = MyConfigType::builder()
+    ///     .override_with_builder(defaults)
+    ///     .override_with(FileSource::new(&path))
+    ///     .try_build()
+    ///     .expect("Failed to build");
+    ///
+    /// assert_eq!(config.host, "localhost");
+    /// assert_eq!(config.port, 8080);
+    /// # }
+    /// ```
+    pub fn override_with_builder(&mut self, builder: Target::Builder) -> &mut Self
+    where
+        Target::Builder: 'a,
+    {
+        self.sources.push(Box::new(BuilderSource::new(builder)));
+        self
+    }
+
+    /// Falls back to the values of an already-built `base` for anything not provided by the other
+    /// sources, e.g. for layering per-request overrides over a shared configuration.
+    ///
+    /// The base has the lowest priority, regardless of when this is called. It's converted back
+    /// into a builder, so, as with [`merge_into`](Self::merge_into), isn't checked for secrets, or
+    /// for conflicts by [`forbid_conflicts`](Self::forbid_conflicts). Requires `Target` to implement
+    /// [`ToBuilder`], e.g. with `#[confik(gen_to_builder)]`.
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, TomlSource};
+    ///
+    /// #[derive(Debug, Configuration)]
+    /// #[confik(gen_to_builder)]
+    /// struct MyConfigType {
+    ///     timeout_secs: u64,
+    ///     retries: u8,
+    /// }
+    ///
+    /// let base = MyConfigType::builder()
+    ///     .override_with(TomlSource::new("timeout_secs = 30\nretries = 3"))
+    ///     .try_build()
+    ///     .unwrap();
+    ///
+    /// let request = MyConfigType::builder()
+    ///     .override_with(TomlSource::new("timeout_secs = 5"))
+    ///     .with_base(&base)
+    ///     .try_build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.timeout_secs, 5);
+    /// assert_eq!(request.retries, 3);
+    /// # }
+    /// ```
+    pub fn with_base(&mut self, base: &Target) -> &mut Self
+    where
+        Target: ToBuilder,
+    {
+        self.base = Some(base.to_builder());
+        self
+    }
+
+    /// Returns a label for each of the provided sources, in priority order (i.e., the source
+    /// consulted first, which was added last unless [`precedence`](Self::precedence) is changed, is
+    /// listed first).
+    ///
+    /// Labels are the [`Debug`] representation of each source and are intended for logging.
+    ///
+    /// ```
+    /// use confik::{Configuration, FileSource};
+    ///
+    /// #[derive(Configuration)]
+    /// struct MyConfigType {
+    ///     param: String,
+    /// }
+    ///
+    /// let mut builder = MyConfigType::builder();
+    /// builder
+    ///     .override_with(FileSource::new("/etc/app.toml"))
+    ///     .override_with(FileSource::new("app.toml"));
+    ///
+    /// assert_eq!(
+    ///     builder.source_labels(),
+    ///     [
+    ///         r#"FileSource { path: "app.toml", allow_secrets: false }"#,
+    ///         r#"FileSource { path: "/etc/app.toml", allow_secrets: false }"#,
+    ///     ],
+    /// );
+    /// ```
+    pub fn source_labels(&self) -> Vec<String> {
+        self.sources_by_priority()
+            .map(|source| format!("{source:?}"))
+            .collect()
+    }
+
+    /// Clears all of the provided sources, allowing the builder to be reused.
+    ///
+    /// Note that [`try_build`](Self::try_build) already consumes the sources, so this is mainly
+    /// useful after inspecting the sources with non-consuming methods, such as
+    /// [`source_labels`](Self::source_labels).
+    ///
+    /// ```
+    /// use confik::{Configuration, FileSource};
+    ///
+    /// #[derive(Configuration)]
+    /// struct MyConfigType {
+    ///     param: String,
+    /// }
+    ///
+    /// let mut builder = MyConfigType::builder();
+    /// builder.override_with(FileSource::new("app.toml"));
+    /// assert_eq!(builder.source_labels().len(), 1);
+    ///
+    /// builder.reset();
+    /// assert!(builder.source_labels().is_empty());
+    /// ```
+    pub fn reset(&mut self) -> &mut Self {
+        self.sources.clear();
+        self.base = None;
+        self
+    }
+
+    /// Sets which sources take precedence when more than one provides the same value.
+    ///
+    /// By default, [`Precedence::LastWins`], each call to [`override_with`](Self::override_with)
+    /// overrides the sources added before it, so the usual pattern is to add defaults first and the
+    /// environment last. With [`Precedence::FirstWins`] this is reversed, so sources are added from
+    /// highest to lowest priority instead, as some other libraries expect. This applies to all of
+    /// the sources, whenever they were added, other than a [base](Self::with_base), which always has
+    /// the lowest priority.
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, Precedence, TomlSource};
+    ///
+    /// #[derive(Debug, Configuration)]
+    /// struct MyConfigType {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let config = MyConfigType::builder()
+    ///     .precedence(Precedence::FirstWins)
+    ///     .override_with(TomlSource::new("port = 8080"))
+    ///     .override_with(TomlSource::new("host = \"localhost\"\nport = 80"))
+    ///     .try_build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.host, "localhost");
+    /// assert_eq!(config.port, 8080);
+    /// # }
+    /// ```
+    pub fn precedence(&mut self, precedence: Precedence) -> &mut Self {
+        self.precedence = precedence;
+        self
+    }
+
+    /// Makes [`try_build`](Self::try_build) fail if more than one source provides a value for the
+    /// same field, instead of taking the value from the highest priority source.
+    ///
+    /// This is useful when sources are expected to configure disjoint parts of the configuration,
+    /// e.g. a file per component, where an overlap is likely a mistake.
+    ///
+    /// Containers, such as [`Vec`], are replaced whole when merged, so conflict if given by more than
+    /// one source. Maps and lists merged by key, see `#[confik(merge_key = ...)]`, only conflict on
+    /// the values of matching keys. Builders implemented by hand, rather than by the derive macro,
+    /// never conflict unless they implement
+    /// [`ConfigurationBuilder::check_conflicts`](crate::ConfigurationBuilder::check_conflicts).
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, Error, TomlSource};
+    ///
+    /// #[derive(Debug, Configuration)]
+    /// struct MyConfigType {
+    ///     host: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// let config = MyConfigType::builder()
+    ///     .override_with(TomlSource::new("host = \"localhost\""))
+    ///     .override_with(TomlSource::new("port = 80"))
+    ///     .forbid_conflicts()
+    ///     .try_build()
+    ///     .unwrap();
+    /// assert_eq!(config.port, 80);
+    ///
+    /// let err = MyConfigType::builder()
+    ///     .override_with(TomlSource::new("host = \"localhost\"\nport = 80"))
+    ///     .override_with(TomlSource::new("port = 8080"))
+    ///     .forbid_conflicts()
+    ///     .try_build()
+    ///     .unwrap_err();
+    /// assert!(matches!(err, Error::Conflict(..)));
+    /// # }
+    /// ```
+    pub fn forbid_conflicts(&mut self) -> &mut Self {
+        self.forbid_conflicts = true;
+        self
+    }
+
     /// Attempt to build from the provided sources.
     ///
+    /// When the `tracing` feature is enabled, each source being provided and merged is recorded in a
+    /// `debug` level span, which can help to find why a value isn't taking effect.
+    ///
     /// # Errors
     ///
     /// Returns an error if a required value is missing, a secret value was provided in a non-secret
     /// source, or an error is returned from a source (e.g., invalid TOML). See [`Error`] for more
     /// details.
     pub fn try_build(&mut self) -> Result<Target, Error> {
-        if self.sources.is_empty() {
-            build_from_sources([Box::new(DefaultSource) as Box<dyn DynSource<_>>])
+        build_from_sources(self.take_sources(), self.forbid_conflicts, self.base.take())
+    }
+
+    /// Attempt to build from the provided sources, as [`try_build`](Self::try_build), also returning
+    /// how long each source, merging, and building took, e.g. to diagnose slow startup.
+    ///
+    /// Timings are returned even if building fails, covering the steps that ran.
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, TomlSource};
+    ///
+    /// #[derive(Debug, PartialEq, Configuration)]
+    /// struct MyConfigType {
+    ///     param: usize,
+    /// }
+    ///
+    /// let mut builder = MyConfigType::builder();
+    /// builder.override_with(TomlSource::new("param = 1"));
+    ///
+    /// let labels = builder.source_labels();
+    /// let (config, timings) = builder.try_build_timed();
+    ///
+    /// assert_eq!(config.unwrap(), MyConfigType { param: 1 });
+    ///
+    /// for (source, duration) in labels.iter().zip(timings.sources()) {
+    ///     println!("{source} took {duration:?}");
+    /// }
+    /// # }
+    /// ```
+    pub fn try_build_timed(&mut self) -> (Result<Target, Error>, BuildTimings) {
+        build_from_sources_timed(
+            self.take_sources(),
+            self.forbid_conflicts,
+            self.base.take(),
+            true,
+        )
+    }
+
+    /// Attempt to build from the provided sources, falling back to the values of `existing` for
+    /// anything they don't provide, then replace `existing` with the result, e.g. to apply a reload
+    /// onto a running service's configuration.
+    ///
+    /// Fields no source provides keep their current values, rather than being reset to their
+    /// defaults, so values set at runtime survive. `existing` is only converted back into a builder,
+    /// so isn't checked for secrets, or for conflicts by [`forbid_conflicts`](Self::forbid_conflicts).
+    /// Requires `Target` to implement [`ToBuilder`], e.g. with `#[confik(gen_to_builder)]`.
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, TomlSource};
+    ///
+    /// #[derive(Debug, Configuration)]
+    /// #[confik(gen_to_builder)]
+    /// struct MyConfigType {
+    ///     log_level: String,
+    ///     workers: usize,
+    /// }
+    ///
+    /// let mut config = MyConfigType::builder()
+    ///     .override_with(TomlSource::new("log_level = \"info\"\nworkers = 4"))
+    ///     .try_build()
+    ///     .unwrap();
+    ///
+    /// // Tuned at runtime.
+    /// config.workers = 16;
+    ///
+    /// MyConfigType::builder()
+    ///     .override_with(TomlSource::new("log_level = \"debug\""))
+    ///     .merge_into(&mut config)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.log_level, "debug");
+    /// assert_eq!(config.workers, 16);
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if building fails, see [`try_build`](Self::try_build), in which case
+    /// `existing` is left unchanged.
+    pub fn merge_into(&mut self, existing: &mut Target) -> Result<(), Error>
+    where
+        Target: ToBuilder,
+    {
+        let fallback = match self.base.take() {
+            Some(base) => Target::Builder::merge(existing.to_builder(), base),
+            None => existing.to_builder(),
+        };
+        *existing = build_from_sources(self.take_sources(), self.forbid_conflicts, Some(fallback))?;
+        Ok(())
+    }
+
+    /// Attempt to build from the provided sources, then print the result in the given [`Format`],
+    /// e.g. for operators to check the configuration a service is actually running with.
+    ///
+    /// Values at the target's [`secret_paths`](Configuration::secret_paths) are replaced with
+    /// `"[redacted]"`, so `Target`'s [`Serialize`](serde::Serialize) implementation should use the
+    /// same field names as its builder, e.g. by giving it the same serde `rename_all` as is
+    /// forwarded to the builder. Missing optional secrets are still printed as missing.
+    ///
+    /// Requires the `json` feature.
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, Format, TomlSource};
+    ///
+    /// #[derive(serde::Serialize, Configuration)]
+    /// struct MyConfigType {
+    ///     host: String,
+    ///     #[confik(secret)]
+    ///     password: String,
+    /// }
+    ///
+    /// let dump = MyConfigType::builder()
+    ///     .override_with(TomlSource::new("host = \"db\"\npassword = \"hunter2\"").allow_secrets())
+    ///     .dump_effective(Format::Toml)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dump, "host = \"db\"\npassword = \"[redacted]\"\n");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if building fails, see [`try_build`](Self::try_build), or if the built
+    /// configuration can't be represented in the chosen format.
+    #[cfg(feature = "json")]
+    pub fn dump_effective(&mut self, format: Format) -> Result<String, Error>
+    where
+        Target: serde::Serialize,
+    {
+        let target = self.try_build()?;
+
+        let mut value =
+            serde_json::to_value(&target).map_err(|err| Error::Serialize(Box::new(err)))?;
+        dump::redact(&mut value, &Target::secret_paths());
+
+        match format {
+            Format::Json => {
+                serde_json::to_string_pretty(&value).map_err(|err| Error::Serialize(Box::new(err)))
+            }
+            #[cfg(feature = "toml")]
+            Format::Toml => {
+                dump::strip_nulls(&mut value);
+                toml::to_string(&value).map_err(|err| Error::Serialize(Box::new(err)))
+            }
+        }
+    }
+
+    /// Attempt to build from the provided sources, skipping any sources which fail and collecting
+    /// all of the errors.
+    ///
+    /// Errors are either:
+    /// - Non-fatal: a source failed to provide its data (e.g., invalid TOML), or contained a secret
+    ///   it isn't allowed to. That source is skipped and building continues with the others.
+    /// - Fatal: the target couldn't be built from the remaining sources (e.g., a required value is
+    ///   missing, a conversion failed, or two sources conflict when
+    ///   [`forbid_conflicts`](Self::forbid_conflicts) is set). Only the first such error is
+    ///   reported, and no target is returned, as a partially-populated target can't be represented.
+    ///
+    /// So the target is returned whenever there was no fatal error, alongside any non-fatal errors,
+    /// in source priority order.
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "json", feature = "toml"))]
+    /// # {
+    /// use confik::{Configuration, JsonSource, TomlSource};
+    ///
+    /// #[derive(Debug, PartialEq, Configuration)]
+    /// struct MyConfigType {
+    ///     param: usize,
+    /// }
+    ///
+    /// let (config, errors) = MyConfigType::builder()
+    ///     .override_with(TomlSource::new("param = 1"))
+    ///     .override_with(JsonSource::new("not json"))
+    ///     .try_build_lenient();
+    ///
+    /// assert_eq!(config, Some(MyConfigType { param: 1 }));
+    /// assert_eq!(errors.len(), 1);
+    /// # }
+    /// ```
+    pub fn try_build_lenient(&mut self) -> (Option<Target>, Vec<Error>) {
+        build_from_sources_lenient(self.take_sources(), self.forbid_conflicts, self.base.take())
+    }
+
+    /// Attempt to build from the provided sources, falling back to [`Default::default`] if that
+    /// fails.
+    ///
+    /// The error is logged, at the `error` level, when the `tracing` feature is enabled. Otherwise it
+    /// is discarded, so prefer [`try_build`](Self::try_build) unless silently falling back is
+    /// acceptable.
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use confik::{Configuration, TomlSource};
+    ///
+    /// #[derive(Debug, Default, PartialEq, Configuration)]
+    /// struct MyConfigType {
+    ///     param: usize,
+    /// }
+    ///
+    /// let config = MyConfigType::builder()
+    ///     .override_with(TomlSource::new("param = \"not a number\""))
+    ///     .try_build_or_default();
+    ///
+    /// assert_eq!(config, MyConfigType::default());
+    /// # }
+    /// ```
+    pub fn try_build_or_default(&mut self) -> Target
+    where
+        Target: Default,
+    {
+        self.try_build().unwrap_or_else(|_err| {
+            #[cfg(feature = "tracing")]
+            tracing::error!(error = %_err, "failed to build configuration, using default");
+
+            Target::default()
+        })
+    }
+}
+
+impl<'a, Target: Configuration> ConfigBuilder<'a, Target> {
+    /// The sources, from highest to lowest priority.
+    fn sources_by_priority(&self) -> impl Iterator<Item = &(dyn DynSource<Target::Builder> + 'a)> {
+        let sources: Box<dyn Iterator<Item = _>> = match self.precedence {
+            Precedence::LastWins => Box::new(self.sources.iter().rev()),
+            Precedence::FirstWins => Box::new(self.sources.iter()),
+        };
+
+        sources.map(|source| &**source)
+    }
+
+    /// Takes the sources, from highest to lowest priority, using the defaults if there are none.
+    fn take_sources(&mut self) -> Vec<Box<dyn DynSource<Target::Builder> + 'a>> {
+        let mut sources = mem::take(&mut self.sources);
+        if self.precedence == Precedence::LastWins {
+            sources.reverse();
+        }
+
+        if sources.is_empty() {
+            vec![Box::new(DefaultSource)]
         } else {
-            build_from_sources(mem::take(&mut self.sources).into_iter().rev())
+            sources
         }
     }
 }
@@ -122,6 +688,9 @@ impl<Target: Configuration> Default for ConfigBuilder<'_, Target> {
     fn default() -> Self {
         Self {
             sources: Vec::new(),
+            base: None,
+            precedence: Precedence::default(),
+            forbid_conflicts: false,
             _phantom: PhantomData,
         }
     }
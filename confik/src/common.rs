@@ -1,9 +1,28 @@
 //! Useful configuration types that services will likely otherwise re-implement.
+//!
+//! Besides [`DatabaseConnectionConfig`] and [`HttpListenerConfig`], each type is gated behind its
+//! own feature, which also enables `common`: `common_redis`, `common_smtp` and `common_tls`.
 
 use std::{fmt, str};
 
 use crate::{Configuration, MissingValue};
 
+mod http;
+#[cfg(feature = "common_redis")]
+mod redis;
+#[cfg(feature = "common_smtp")]
+mod smtp;
+#[cfg(feature = "common_tls")]
+mod tls;
+
+pub use self::http::HttpListenerConfig;
+#[cfg(feature = "common_redis")]
+pub use self::redis::RedisConnectionConfig;
+#[cfg(feature = "common_smtp")]
+pub use self::smtp::SmtpConfig;
+#[cfg(feature = "common_tls")]
+pub use self::tls::TlsConfig;
+
 /// The database type, used to determine the connection string format
 #[derive(Debug, Clone, PartialEq, Eq, Configuration)]
 #[confik(forward_serde(rename_all = "lowercase"))]
@@ -0,0 +1,77 @@
+use std::{
+    io, iter,
+    net::{SocketAddr, ToSocketAddrs},
+    time::Duration,
+};
+
+#[cfg(feature = "common_tls")]
+use super::TlsConfig;
+use crate::Configuration;
+
+/// HTTP listener configuration, for the address a server binds to and how it handles connections.
+///
+/// Every field has a default, so an empty source is enough:
+///
+/// - `bind`: the address to listen on, defaulting to `0.0.0.0:8080`.
+/// - `keep_alive`: how long an idle connection is kept open, as an ISO 8601 duration, e.g.
+///   `"PT5S"`. Defaults to 5 seconds.
+/// - `max_connections`: the maximum number of concurrent connections, defaulting to 25,000.
+/// - `tls`: the certificate to serve HTTPS with, or `None` for plain HTTP. Requires the
+///   `common_tls` feature.
+///
+/// It implements [`ToSocketAddrs`], so can be passed straight to e.g. `TcpListener::bind`.
+///
+/// ```
+/// use std::net::ToSocketAddrs as _;
+///
+/// use confik::{common::HttpListenerConfig, Configuration};
+///
+/// let config = HttpListenerConfig::builder().try_build().unwrap();
+/// let addr = config.to_socket_addrs().unwrap().next().unwrap();
+/// assert_eq!(addr, "0.0.0.0:8080".parse().unwrap());
+/// ```
+#[derive(Debug, Clone, Configuration)]
+pub struct HttpListenerConfig {
+    #[confik(default = SocketAddr::from(([0, 0, 0, 0], 8080)))]
+    bind: SocketAddr,
+
+    #[confik(iso8601, default = Duration::from_secs(5))]
+    keep_alive: Duration,
+
+    #[confik(default = 25_000usize)]
+    max_connections: usize,
+
+    #[cfg(feature = "common_tls")]
+    tls: Option<TlsConfig>,
+}
+
+impl HttpListenerConfig {
+    /// The address to listen on.
+    pub fn bind(&self) -> SocketAddr {
+        self.bind
+    }
+
+    /// How long an idle connection is kept open.
+    pub fn keep_alive(&self) -> Duration {
+        self.keep_alive
+    }
+
+    /// The maximum number of concurrent connections.
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// The certificate to serve HTTPS with, if any.
+    #[cfg(feature = "common_tls")]
+    pub fn tls(&self) -> Option<&TlsConfig> {
+        self.tls.as_ref()
+    }
+}
+
+impl ToSocketAddrs for HttpListenerConfig {
+    type Iter = iter::Once<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
+        Ok(iter::once(self.bind))
+    }
+}
@@ -0,0 +1,52 @@
+use std::{fmt, path::PathBuf};
+
+use crate::Configuration;
+
+/// TLS certificate configuration, with a secret `key_path`.
+///
+/// The [`Display`] and [`Debug`] impls both show the certificate path, but with the `key_path`
+/// field value replaced by `[redacted]`. The paths are available from [`cert_path`] and
+/// [`key_path`].
+///
+/// See [`SecretBuilder`](crate::SecretBuilder) for details on secrets. NOTE: The hiding of the
+/// field is manually implemented for this type, and is not automatically handled by
+/// `#[config(secret)]`.
+///
+/// [`Display`]: #impl-Display-for-TlsConfig
+/// [`Debug`]: #impl-Debug-for-TlsConfig
+/// [`cert_path`]: TlsConfig::cert_path
+/// [`key_path`]: TlsConfig::key_path
+#[derive(Clone, Configuration)]
+pub struct TlsConfig {
+    cert_path: PathBuf,
+
+    #[confik(secret)]
+    key_path: PathBuf,
+}
+
+impl TlsConfig {
+    /// The path to the certificate chain.
+    pub fn cert_path(&self) -> &PathBuf {
+        &self.cert_path
+    }
+
+    /// The path to the private key.
+    pub fn key_path(&self) -> &PathBuf {
+        &self.key_path
+    }
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &"[redacted]")
+            .finish()
+    }
+}
+
+impl fmt::Display for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cert: {}, key: [redacted]", self.cert_path.display())
+    }
+}
@@ -0,0 +1,124 @@
+//! Printing a built configuration, with its secrets redacted.
+
+use serde_json::Value;
+
+/// What each secret value is replaced with.
+pub(crate) const REDACTED: &str = "[redacted]";
+
+/// The format to print a configuration in, see
+/// [`ConfigBuilder::dump_effective`](crate::ConfigBuilder::dump_effective).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// Pretty-printed JSON.
+    Json,
+
+    /// TOML. Requires the `toml` feature.
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+/// Replaces the value at each of the dotted `paths` with [`REDACTED`], where a `*` segment matches
+/// every item of an array or entry of an object. Missing and `null` values are left alone, so it's
+/// still visible that an optional secret wasn't given.
+pub(crate) fn redact(value: &mut Value, paths: &[String]) {
+    for path in paths {
+        redact_path(value, &path.split('.').collect::<Vec<_>>());
+    }
+}
+
+fn redact_path(value: &mut Value, segments: &[&str]) {
+    let Some((segment, rest)) = segments.split_first() else {
+        if !value.is_null() {
+            *value = Value::String(REDACTED.to_owned());
+        }
+        return;
+    };
+
+    match (value, *segment) {
+        (Value::Array(items), "*") => {
+            for item in items {
+                redact_path(item, rest);
+            }
+        }
+        (Value::Object(entries), "*") => {
+            for entry in entries.values_mut() {
+                redact_path(entry, rest);
+            }
+        }
+        (Value::Array(items), index) => {
+            if let Some(item) = index
+                .parse()
+                .ok()
+                .and_then(|index: usize| items.get_mut(index))
+            {
+                redact_path(item, rest);
+            }
+        }
+        (Value::Object(entries), key) => {
+            if let Some(entry) = entries.get_mut(key) {
+                redact_path(entry, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Removes `null`s, which TOML can't represent, from objects and arrays.
+#[cfg(feature = "toml")]
+pub(crate) fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Array(items) => {
+            items.retain(|item| !item.is_null());
+            items.iter_mut().for_each(strip_nulls);
+        }
+        Value::Object(entries) => {
+            entries.retain(|_, entry| !entry.is_null());
+            entries.values_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn redacts_nested_and_wildcard_paths() {
+        let mut value = json!({
+            "database": { "host": "db", "password": "hunter2" },
+            "replicas": [{ "password": "a" }, { "password": null }],
+            "tokens": { "ci": "b", "deploy": "c" },
+        });
+
+        redact(
+            &mut value,
+            &[
+                "database.password".to_owned(),
+                "replicas.*.password".to_owned(),
+                "tokens.*".to_owned(),
+                "missing.password".to_owned(),
+            ],
+        );
+
+        assert_eq!(
+            value,
+            json!({
+                "database": { "host": "db", "password": REDACTED },
+                "replicas": [{ "password": REDACTED }, { "password": null }],
+                "tokens": { "ci": REDACTED, "deploy": REDACTED },
+            }),
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn strips_nulls() {
+        let mut value = json!({ "a": null, "b": [1, null], "c": { "d": null } });
+        strip_nulls(&mut value);
+        assert_eq!(value, json!({ "b": [1], "c": {} }));
+    }
+}
@@ -7,7 +7,7 @@ use std::{borrow::Cow, error::Error as StdError};
 
 use thiserror::Error;
 
-use crate::{FailedTryInto, MissingValue, UnexpectedSecret};
+use crate::{Conflict, FailedTryInto, MissingValue, NotOneOf, UnexpectedSecret, UnexpectedSource};
 
 /// Possible error values.
 #[derive(Debug, Error)]
@@ -29,9 +29,35 @@ pub enum Error {
     #[error("Found a secret in source {1} that does not permit secrets")]
     UnexpectedSecret(#[source] UnexpectedSecret, String),
 
+    /// The value contained in the `path` was marked as `#[confik(secret, env_only)]` but was parsed
+    /// from a [`Source`](crate::Source) other than an environment source (see
+    /// [`Source::kind`](crate::Source::kind)), even if that source permits secrets.
+    #[error("Found an environment-only secret in source {1}, which is not an environment source")]
+    EnvOnlySecret(#[source] UnexpectedSecret, String),
+
+    /// The value contained in the `path` was restricted to other kinds of
+    /// [`Source`](crate::Source) with `#[confik(source_kinds(...))]`, but was parsed from a
+    /// source of a different [`kind`](crate::Source::kind), even if that source permits secrets.
+    #[error("Found a value in source {1} that is not permitted to provide it")]
+    UnexpectedSource(#[source] UnexpectedSource, String),
+
+    /// Two sources both contained a value for the `path`, with
+    /// [`ConfigBuilder::forbid_conflicts`](crate::ConfigBuilder::forbid_conflicts) enabled.
+    #[error("Found a value in source {1} which is also provided by source {2}")]
+    Conflict(#[source] Conflict, String, String),
+
     /// The value contained in the `path` was attempted to be converted and that conversion failed.
     #[error(transparent)]
     TryInto(#[from] FailedTryInto),
+
+    /// The `#[confik(one_of)]` enum at the `path` was given none, or more than one, of its sections.
+    #[error(transparent)]
+    NotOneOf(#[from] NotOneOf),
+
+    /// The built configuration couldn't be serialized, in
+    /// [`ConfigBuilder::dump_effective`](crate::ConfigBuilder::dump_effective).
+    #[error("Failed to serialize the configuration")]
+    Serialize(#[source] Box<dyn StdError + Send + Sync>),
 }
 
 impl Error {
@@ -42,12 +68,23 @@ impl Error {
         match self {
             Self::MissingValue(err) => Self::MissingValue(err.prepend(path_segment)),
             Self::TryInto(err) => Self::TryInto(err.prepend(path_segment)),
+            Self::NotOneOf(err) => Self::NotOneOf(err.prepend(path_segment)),
             // This branch will probably never be hit but exists so that the function works the way
             // a caller would expect if there is a use case for it in future.
             Self::UnexpectedSecret(err, source) => {
                 Self::UnexpectedSecret(err.prepend(path_segment), source)
             }
+            Self::EnvOnlySecret(err, source) => {
+                Self::EnvOnlySecret(err.prepend(path_segment), source)
+            }
+            Self::UnexpectedSource(err, source) => {
+                Self::UnexpectedSource(err.prepend(path_segment), source)
+            }
+            Self::Conflict(err, source, other_source) => {
+                Self::Conflict(err.prepend(path_segment), source, other_source)
+            }
             Self::Source(err, source) => Self::Source(err, source),
+            Self::Serialize(err) => Self::Serialize(err),
         }
     }
 }
@@ -0,0 +1,138 @@
+//! Parsing of ISO 8601 durations, e.g. `PT1H30M`, as used by `#[confik(iso8601)]`.
+
+use std::time::Duration;
+
+/// The designators of the date components, in order, with their length in seconds.
+///
+/// Years and months have no fixed length, so are recognised but rejected.
+const DATE_UNITS: &[(char, Option<u64>)] = &[
+    ('Y', None),
+    ('M', None),
+    ('W', Some(7 * 24 * 60 * 60)),
+    ('D', Some(24 * 60 * 60)),
+];
+
+/// The designators of the time components, in order, with their length in seconds.
+const TIME_UNITS: &[(char, Option<u64>)] = &[('H', Some(60 * 60)), ('M', Some(60)), ('S', Some(1))];
+
+/// Parses an ISO 8601 duration, e.g. `P1DT12H` or `PT0.5S`.
+pub(crate) fn parse_duration(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid ISO 8601 duration `{s}`");
+
+    let rest = s.strip_prefix('P').ok_or_else(invalid)?;
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return Err(invalid()),
+        Some((date, time)) => (date, time),
+        None if rest.is_empty() => return Err(invalid()),
+        None => (rest, ""),
+    };
+
+    let date = parse_components(date, DATE_UNITS).ok_or_else(invalid)??;
+    let time = parse_components(time, TIME_UNITS).ok_or_else(invalid)??;
+
+    date.checked_add(time).ok_or_else(invalid)
+}
+
+/// Parses and sums the components of either the date or time part of a duration.
+///
+/// Returns `None` if the part is malformed, or an error if it uses a unit with no fixed length.
+fn parse_components(
+    mut part: &str,
+    units: &[(char, Option<u64>)],
+) -> Option<Result<Duration, String>> {
+    let mut units = units.iter();
+    let mut total = Duration::ZERO;
+
+    while !part.is_empty() {
+        let end = part.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))?;
+        let number = &part[..end];
+        let designator = part[end..].chars().next()?;
+        part = &part[end + designator.len_utf8()..];
+
+        // Searching on from the previous unit ensures the components are in order.
+        let (_, unit_secs) = units.find(|(unit, _)| *unit == designator)?;
+        let Some(unit_secs) = unit_secs else {
+            return Some(Err(String::from("years and months have no fixed duration")));
+        };
+
+        total = total.checked_add(parse_component(number, *unit_secs)?)?;
+    }
+
+    Some(Ok(total))
+}
+
+/// Parses a single, possibly fractional, number of a unit.
+fn parse_component(number: &str, unit_secs: u64) -> Option<Duration> {
+    let (whole, fraction) = match number.split_once(['.', ',']) {
+        Some((_, "")) => return None,
+        Some((whole, fraction)) => (whole, fraction),
+        None => (number, ""),
+    };
+
+    if whole.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let whole = Duration::from_secs(whole.parse::<u64>().ok()?.checked_mul(unit_secs)?);
+    let fraction = if fraction.is_empty() {
+        Duration::ZERO
+    } else {
+        // Always less than a week, so can't overflow.
+        Duration::from_secs_f64(format!("0.{fraction}").parse::<f64>().ok()? * unit_secs as f64)
+    };
+
+    whole.checked_add(fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let cases = [
+            ("PT1H30M", 5400.0),
+            ("PT90M", 5400.0),
+            ("PT0S", 0.0),
+            ("PT0.5S", 0.5),
+            ("PT1,5M", 90.0),
+            ("P1D", 86400.0),
+            ("P1W", 604_800.0),
+            ("P1DT12H", 129_600.0),
+            ("P2W3DT4H5M6S", 1_483_506.0),
+        ];
+
+        for (s, secs) in cases {
+            assert_eq!(
+                parse_duration(s),
+                Ok(Duration::from_secs_f64(secs)),
+                "Parsing `{s}`",
+            );
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        for s in [
+            "", "P", "PT", "P1DT", "1H", "PT1", "PTH", "PT1.S", "PT.5S", "PT-1S", "PT1M1H",
+            "PT1H1H", "P1H", "PT1D", "pt1h", "PT1H ",
+        ] {
+            assert_eq!(
+                parse_duration(s),
+                Err(format!("invalid ISO 8601 duration `{s}`")),
+                "Parsing `{s}`",
+            );
+        }
+    }
+
+    #[test]
+    fn variable_length_units() {
+        for s in ["P1Y", "P1M", "P1Y2M3D", "P1MT1M"] {
+            assert_eq!(
+                parse_duration(s),
+                Err(String::from("years and months have no fixed duration")),
+                "Parsing `{s}`",
+            );
+        }
+    }
+}
@@ -2,7 +2,14 @@
 #![deny(rust_2018_idioms, nonstandard_style, future_incompatible)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
-use std::{borrow::Cow, error::Error as StdError, ops::Not};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    error::Error as StdError,
+    fmt,
+    ops::Not,
+    time::{Duration, Instant},
+};
 
 #[doc(hidden)]
 pub use confik_macros::*;
@@ -27,24 +34,57 @@ extern crate self as confik;
 mod builder;
 #[cfg(feature = "common")]
 pub mod common;
+#[cfg(feature = "json")]
+mod dump;
 mod errors;
+mod iso8601;
+mod map_key;
+mod no_serde;
 mod path;
+pub mod prelude;
+mod probe;
+#[cfg(feature = "json")]
+mod raw_string;
 mod secrets;
 mod sources;
 mod std_impls;
+mod tagged;
 mod third_party;
+mod timings;
 
+#[cfg(feature = "json")]
+pub use self::dump::Format;
+#[cfg(feature = "json")]
+pub use self::raw_string::RawString;
+#[doc(hidden)]
+pub use self::sources::__provide_with_previous;
 #[cfg(feature = "env")]
 pub use self::sources::env_source::EnvSource;
+#[cfg(feature = "include_dir")]
+pub use self::sources::include_dir_source::IncludeDirSource;
+#[cfg(feature = "json")]
+pub use self::sources::json_lines_source::JsonLinesSource;
 #[cfg(feature = "json")]
 pub use self::sources::json_source::JsonSource;
+#[cfg(all(windows, feature = "winreg"))]
+pub use self::sources::registry_source::RegistrySource;
 #[cfg(feature = "toml")]
 pub use self::sources::toml_source::TomlSource;
+#[doc(hidden)]
+pub use self::tagged::{
+    __deserialize_from_repr, __deserialize_tagged, __deserialize_with_previous,
+};
 pub use self::{
-    builder::ConfigBuilder,
+    builder::{ConfigBuilder, Precedence},
     errors::Error,
+    no_serde::NoSerdeBuilder,
     secrets::{SecretBuilder, SecretOption, UnexpectedSecret},
-    sources::{file_source::FileSource, Source},
+    sources::{
+        __Provide, __Provided, file_source::FileSource, optional_source::OptionalSource, Source,
+        SourceKind,
+    },
+    std_impls::{MergeByKeyBuilder, MergeKey},
+    timings::BuildTimings,
 };
 use self::{path::Path, sources::DynSource};
 
@@ -53,6 +93,107 @@ use self::{path::Path, sources::DynSource};
 #[error("Missing value for path `{0}`")]
 pub struct MissingValue(Path);
 
+/// Captures the path of a value provided by a kind of source which isn't permitted to provide it,
+/// see `#[confik(source_kinds(...))]`.
+#[derive(Debug, thiserror::Error)]
+#[error("Found value at path `{0}`, which can't come from {1} sources")]
+pub struct UnexpectedSource(Path, SourceKind);
+
+impl UnexpectedSource {
+    /// Creates a new [`Self`] with a blank path, for a value from a source of the given `kind`.
+    pub fn new(kind: SourceKind) -> Self {
+        Self(Path::new(), kind)
+    }
+
+    /// Prepends a path segment as we return back up the call-stack.
+    #[must_use]
+    pub fn prepend(mut self, path_segment: impl Into<Cow<'static, str>>) -> Self {
+        self.0 .0.push(path_segment.into());
+        self
+    }
+
+    /// The kind of source which provided the value.
+    pub fn kind(&self) -> SourceKind {
+        self.1
+    }
+}
+
+/// Captures the path of a value provided by more than one source, see
+/// [`ConfigBuilder::forbid_conflicts`].
+#[derive(Debug, Default, thiserror::Error)]
+#[error("Found values for path `{0}` in more than one source")]
+pub struct Conflict(Path);
+
+impl Conflict {
+    /// Prepends a path segment as we return back up the call-stack.
+    #[must_use]
+    pub fn prepend(mut self, path_segment: impl Into<Cow<'static, str>>) -> Self {
+        self.0 .0.push(path_segment.into());
+        self
+    }
+}
+
+/// Captures the path of a `#[confik(one_of)]` enum, and which of its sections were found when other
+/// than exactly one was.
+#[derive(Debug)]
+pub struct NotOneOf {
+    path: Path,
+    sections: &'static [&'static str],
+    found: Vec<&'static str>,
+}
+
+impl NotOneOf {
+    /// Creates a new [`Self`] with a blank path, for an enum with the given `sections`, of which
+    /// `found` were provided.
+    pub fn new(sections: &'static [&'static str], found: Vec<&'static str>) -> Self {
+        Self {
+            path: Path::new(),
+            sections,
+            found,
+        }
+    }
+
+    /// Prepends a path segment as we return back up the call-stack.
+    #[must_use]
+    pub fn prepend(mut self, path_segment: impl Into<Cow<'static, str>>) -> Self {
+        self.path.0.push(path_segment.into());
+        self
+    }
+
+    /// The sections which were found, none or more than one.
+    pub fn found(&self) -> &[&'static str] {
+        &self.found
+    }
+}
+
+impl fmt::Display for NotOneOf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Writes `names` as e.g. "`a`, `b` or `c`".
+        fn write_list(f: &mut fmt::Formatter<'_>, names: &[&str], last: &str) -> fmt::Result {
+            for (index, name) in names.iter().enumerate() {
+                match index {
+                    0 => {}
+                    _ if index == names.len() - 1 => write!(f, " {last} ")?,
+                    _ => f.write_str(", ")?,
+                }
+                write!(f, "`{name}`")?;
+            }
+            Ok(())
+        }
+
+        f.write_str("Expected exactly one of ")?;
+        write_list(f, self.sections, "or")?;
+        write!(f, " for path `{}`, but found ", self.path)?;
+        if self.found.is_empty() {
+            f.write_str("none")
+        } else {
+            write_list(f, &self.found, "and")
+        }
+    }
+}
+
+impl StdError for NotOneOf {}
+
 impl MissingValue {
     /// Prepends a path segment as we return back up the call-stack.
     #[must_use]
@@ -82,32 +223,216 @@ impl FailedTryInto {
 }
 
 /// Converts the sources, in order, into [`Configuration::Builder`] and
-/// [`ConfigurationBuilder::merge`]s them, passing any errors back.
-fn build_from_sources<'a, Target, Iter>(sources: Iter) -> Result<Target, Error>
+/// [`ConfigurationBuilder::merge`]s them, passing any errors back. The `fallback` builder, if any,
+/// is merged last, so has the lowest priority, and isn't checked for secrets or conflicts.
+///
+/// When the `tracing` feature is enabled, each step is recorded in `debug` level spans.
+fn build_from_sources<'a, Target, Iter>(
+    sources: Iter,
+    forbid_conflicts: bool,
+    fallback: Option<Target::Builder>,
+) -> Result<Target, Error>
+where
+    Target: Configuration,
+    Iter: IntoIterator<Item = Box<dyn DynSource<Target::Builder> + 'a>>,
+{
+    build_from_sources_timed(sources, forbid_conflicts, fallback, false).0
+}
+
+/// As [`build_from_sources`], but also recording how long each step took if `timed`, see
+/// [`ConfigBuilder::try_build_timed`].
+///
+/// The clock isn't read otherwise, as it isn't available on every platform.
+fn build_from_sources_timed<'a, Target, Iter>(
+    sources: Iter,
+    forbid_conflicts: bool,
+    fallback: Option<Target::Builder>,
+    timed: bool,
+) -> (Result<Target, Error>, BuildTimings)
 where
     Target: Configuration,
     Iter: IntoIterator<Item = Box<dyn DynSource<Target::Builder> + 'a>>,
 {
-    sources
+    #[cfg(feature = "tracing")]
+    let _span =
+        tracing::debug_span!("building", target = std::any::type_name::<Target>()).entered();
+
+    let start = timed.then(Instant::now);
+    let mut timings = BuildTimings::default();
+
+    let res = sources
         .into_iter()
-        // Convert each source to a `Target::Builder`
-        .map::<Result<Target::Builder, Error>, _>(
-            |source: Box<dyn DynSource<Target::Builder> + 'a>| {
-                let debug = || format!("{source:?}");
-                let res = source.provide().map_err(|e| Error::Source(e, debug()))?;
-                if source.allows_secrets().not() {
-                    res.contains_non_secret_data()
-                        .map_err(|e| Error::UnexpectedSecret(e, debug()))?;
-                }
-                Ok(res)
-            },
-        )
-        // Merge the builders
-        .reduce(|first, second| Ok(Target::Builder::merge(first?, second?)))
-        // If there was no data then we're missing values
-        .ok_or_else(|| Error::MissingValue(MissingValue::default()))??
-        .try_build()
-        .map_err(Into::into)
+        // Convert each source to a `Target::Builder`, skipping those without data
+        .filter_map(|source| {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("providing", ?source).entered();
+
+            let provide_start = timed.then(Instant::now);
+            let res = provide_checked(&*source);
+            if let Some(provide_start) = provide_start {
+                timings.sources.push(provide_start.elapsed());
+            }
+
+            #[cfg(feature = "tracing")]
+            match &res {
+                Ok(Some(_)) => tracing::debug!("source provided data"),
+                Ok(None) => tracing::debug!("source has no data, skipping"),
+                Err(err) => tracing::debug!(error = %err, "source failed"),
+            }
+
+            res.map(|builder| builder.map(|builder| (builder, source)))
+                .transpose()
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(|provided| {
+            let merge_start = timed.then(Instant::now);
+
+            if forbid_conflicts {
+                check_conflicts(&provided)?;
+            }
+
+            // Merge the builders
+            let builder = provided
+                .into_iter()
+                .map(|(builder, _)| builder)
+                .chain(fallback)
+                .reduce(|first, second| {
+                    #[cfg(feature = "tracing")]
+                    let _span = tracing::debug_span!("merging").entered();
+
+                    Target::Builder::merge(first, second)
+                })
+                // If every source was skipped then there is no data, as with an empty builder
+                .unwrap_or_default();
+
+            timings.merge = elapsed(merge_start);
+            Ok(builder)
+        })
+        .and_then(|builder| {
+            let build_start = timed.then(Instant::now);
+            #[allow(clippy::useless_conversion)]
+            let res = builder.try_build().map_err(Into::into);
+            timings.build = elapsed(build_start);
+            res
+        });
+
+    #[cfg(feature = "tracing")]
+    match &res {
+        Ok(_) => tracing::debug!("built configuration"),
+        Err(err) => tracing::debug!(error = %err, "failed to build configuration"),
+    }
+
+    timings.total = elapsed(start);
+    (res, timings)
+}
+
+/// The time since `start`, or zero if untimed.
+fn elapsed(start: Option<Instant>) -> Duration {
+    start.map_or(Duration::ZERO, |start| start.elapsed())
+}
+
+/// Checks that no two of the `provided` builders contain a value for the same leaf.
+fn check_conflicts<'a, B: ConfigurationBuilder>(
+    provided: &[(B, Box<dyn DynSource<B> + 'a>)],
+) -> Result<(), Error> {
+    for (index, (builder, source)) in provided.iter().enumerate() {
+        for (other, other_source) in &provided[index + 1..] {
+            builder.check_conflicts(other).map_err(|err| {
+                Error::Conflict(err, format!("{source:?}"), format!("{other_source:?}"))
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// As [`build_from_sources`], but skipping sources which fail and collecting their errors, see
+/// [`ConfigBuilder::try_build_lenient`].
+fn build_from_sources_lenient<'a, Target, Iter>(
+    sources: Iter,
+    forbid_conflicts: bool,
+    fallback: Option<Target::Builder>,
+) -> (Option<Target>, Vec<Error>)
+where
+    Target: Configuration,
+    Iter: IntoIterator<Item = Box<dyn DynSource<Target::Builder> + 'a>>,
+{
+    let mut errors = Vec::new();
+
+    let provided = sources
+        .into_iter()
+        .filter_map(|source| {
+            provide_checked(&*source)
+                .unwrap_or_else(|err| {
+                    errors.push(err);
+                    None
+                })
+                .map(|builder| (builder, source))
+        })
+        .collect::<Vec<_>>();
+
+    if forbid_conflicts {
+        if let Err(err) = check_conflicts(&provided) {
+            errors.push(err);
+            return (None, errors);
+        }
+    }
+
+    let builder = provided
+        .into_iter()
+        .map(|(builder, _)| builder)
+        .chain(fallback)
+        .reduce(Target::Builder::merge)
+        .unwrap_or_default();
+
+    match builder.try_build() {
+        Ok(target) => (Some(target), errors),
+        Err(err) => {
+            errors.push(err);
+            (None, errors)
+        }
+    }
+}
+
+/// Provides a builder from `source`, or `None` if it has no data, checking that it contains no
+/// secrets unless they are allowed.
+fn provide_checked<B: ConfigurationBuilder>(source: &dyn DynSource<B>) -> Result<Option<B>, Error> {
+    let debug = || format!("{source:?}");
+
+    let provided = match source.provide_optional() {
+        Err(err) if source.is_optional() && sources::is_not_found(&*err) => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(error = %err, "optional source not found, skipping");
+
+            None
+        }
+        provided => provided.map_err(|e| Error::Source(e, debug()))?,
+    };
+
+    let Some(res) = provided else {
+        return Ok(None);
+    };
+
+    if source.allows_secrets().not() {
+        match source.provide_secret_free() {
+            Some(secret_free) => secret_free
+                .map_err(|e| Error::Source(e, debug()))?
+                .contains_non_secret_data(),
+            None => res.contains_non_secret_data(),
+        }
+        .map_err(|e| Error::UnexpectedSecret(e, debug()))?;
+    }
+
+    if source.checks_source_kind() {
+        if source.kind() != SourceKind::Env {
+            res.check_env_only()
+                .map_err(|e| Error::EnvOnlySecret(e, debug()))?;
+        }
+        res.check_source_kind(source.kind())
+            .map_err(|e| Error::UnexpectedSource(e, debug()))?;
+    }
+
+    Ok(Some(res))
 }
 
 /// The target to be deserialized from multiple sources.
@@ -116,8 +441,9 @@ where
 /// implementation.
 ///
 /// For types with no contents, e.g. empty structs, or simple enums, this can be implemented very
-/// easily by specifying only the builder type as `Option<Self>`. For anything more complicated,
-/// complete target and builder implementations will be needed.
+/// easily by specifying only the builder type as `Option<Self>`, which requires the type to
+/// implement [`Deserialize`](serde::Deserialize). For anything more complicated, complete target
+/// and builder implementations will be needed.
 ///
 /// # Examples
 ///
@@ -131,6 +457,15 @@ where
 ///     type Builder = Option<Self>;
 /// }
 /// ```
+#[rustversion::attr(
+    since(1.78),
+    diagnostic::on_unimplemented(
+        message = "`{Self}` cannot be used as a confik configuration",
+        label = "`Configuration` is not implemented for `{Self}`",
+        note = "derive `Configuration` for `{Self}`, or for a type implementing `Deserialize`, implement it with `type Builder = Option<Self>;`",
+        note = "alternatively, read the field as another type with `#[confik(from = ...)]` or `#[confik(try_from = ...)]`",
+    )
+)]
 pub trait Configuration: Sized {
     /// The builder that accumulates the deserializations.
     type Builder: ConfigurationBuilder<Target = Self>;
@@ -140,6 +475,478 @@ pub trait Configuration: Sized {
     fn builder<'a>() -> ConfigBuilder<'a, Self> {
         ConfigBuilder::<Self>::default()
     }
+
+    /// Lists the dotted paths of all secret fields, descending into nested types, without loading
+    /// any data.
+    ///
+    /// Paths use the keys that sources give values under, following any forwarded serde renaming,
+    /// e.g. `apiKey` for a field `api_key` with `rename_all = "camelCase"`, and enum
+    /// representation, e.g. no variant segment for an internally tagged enum. Items of containers
+    /// are represented by a `*` segment, e.g. `servers.*.password`. A type which is always secret
+    /// (e.g. `secrecy::SecretString`) contains the empty path, as does a type with secrets whose
+    /// layout can't be known, e.g. one forwarding serde's `from`. Where a type with secrets
+    /// contains itself, e.g. `children: Vec<Node>` in `Node`, that field is listed as secret as a
+    /// whole, e.g. `children.*`, rather than listing infinitely many paths.
+    ///
+    /// This is generated by the derive macro and defaults to containing no secrets.
+    ///
+    /// ```
+    /// use confik::Configuration;
+    ///
+    /// #[derive(Configuration)]
+    /// struct Database {
+    ///     host: String,
+    ///     #[confik(secret)]
+    ///     password: String,
+    /// }
+    ///
+    /// #[derive(Configuration)]
+    /// struct Config {
+    ///     database: Database,
+    ///     replicas: Vec<Database>,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     Config::secret_paths(),
+    ///     ["database.password", "replicas.*.password"],
+    /// );
+    /// ```
+    #[must_use]
+    fn secret_paths() -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Formats `self` with its secrets redacted, if it has `#[confik(redact_display)]`, so that it
+    /// can be nested in another type with `redact_display`.
+    #[doc(hidden)]
+    fn __fmt_redacted(&self, f: &mut fmt::Formatter<'_>) -> Option<fmt::Result> {
+        let _ = f;
+        None
+    }
+
+    /// Panics if any field or variant, descending into nested types, has an empty key, or the same
+    /// key as another at the same level, e.g. due to a `rename`. No source could set all of them.
+    ///
+    /// This can't see through types which only support self-describing formats, e.g. flattened or
+    /// untagged types, so is best effort. It's intended to be called from a test.
+    ///
+    /// ```should_panic
+    /// use confik::Configuration;
+    ///
+    /// #[derive(Configuration)]
+    /// struct Config {
+    ///     #[confik(forward_serde(rename = "port"))]
+    ///     listen_port: u16,
+    ///     port: u16,
+    /// }
+    ///
+    /// Config::assert_addressable();
+    /// ```
+    #[track_caller]
+    fn assert_addressable() {
+        let names = probe::KeyNames::of::<Self::Builder>();
+        let type_name = std::any::type_name::<Self>();
+
+        for (path, keys) in names.keyed() {
+            let location = if path.is_empty() {
+                format!("the root of `{type_name}`")
+            } else {
+                format!("`{}` in `{type_name}`", path.join("."))
+            };
+
+            if keys.contains(&"") {
+                panic!("Found an empty key at {location}");
+            }
+
+            if let Some(key) = keys
+                .iter()
+                .enumerate()
+                .find_map(|(index, key)| keys[..index].contains(key).then_some(key))
+            {
+                panic!("Found more than one field or variant named `{key}` at {location}");
+            }
+        }
+    }
+}
+
+/// Converts a built configuration back into its builder, e.g. to modify and rebuild it, or to use
+/// it as a source with [`ConfigBuilder::override_with_builder`].
+///
+/// Every value is set in the returned builder, so building it again gives an equal value. This is
+/// generated by the derive macro with `#[confik(gen_to_builder)]`, which requires it for the type of
+/// every field.
+///
+/// ```
+/// use confik::{Configuration, ConfigurationBuilder, ToBuilder};
+///
+/// #[derive(Debug, PartialEq, Configuration)]
+/// #[confik(gen_to_builder)]
+/// struct Config {
+///     host: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let config = Config {
+///     host: "localhost".to_owned(),
+///     ports: vec![80, 443],
+/// };
+///
+/// assert_eq!(config.to_builder().try_build().unwrap(), config);
+/// ```
+pub trait ToBuilder: Configuration {
+    /// Creates a builder containing all of the values of `self`.
+    #[must_use]
+    fn to_builder(&self) -> Self::Builder;
+}
+
+/// Whether a type whose secret paths are being listed was found to contain itself, see
+/// [`__list_secret_paths`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Recursion {
+    /// Not yet found to contain itself.
+    NotFound,
+
+    /// Found to contain itself, so its paths will be listed again once it's known whether it
+    /// contains any secrets.
+    Found,
+
+    /// Contains itself, and secrets, so each place it's contained is secret as a whole.
+    Secret,
+
+    /// Contains itself, but no secrets.
+    NotSecret,
+}
+
+thread_local! {
+    /// The types whose secret paths are currently being listed, outermost first.
+    static LISTING_SECRET_PATHS: RefCell<Vec<(&'static str, Recursion)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Lists the secret paths of `T` with `list`, as generated for [`Configuration::secret_paths`],
+/// guarding against recursive types.
+///
+/// A type containing itself, e.g. through a `Vec`, would have infinitely many paths. Instead, where
+/// it contains itself, it's listed as secret as a whole if it contains any secrets.
+#[doc(hidden)]
+pub fn __list_secret_paths<T: Configuration>(list: impl Fn() -> Vec<String>) -> Vec<String> {
+    let name = std::any::type_name::<T>();
+
+    let recursion = LISTING_SECRET_PATHS.with(|listing| {
+        let mut listing = listing.borrow_mut();
+        let (_, recursion) = listing.iter_mut().find(|(listed, _)| *listed == name)?;
+        if *recursion == Recursion::NotFound {
+            *recursion = Recursion::Found;
+        }
+        Some(*recursion)
+    });
+
+    match recursion {
+        Some(Recursion::Secret) => return vec![String::new()],
+        Some(_) => return Vec::new(),
+        None => {}
+    }
+
+    LISTING_SECRET_PATHS.with(|listing| listing.borrow_mut().push((name, Recursion::NotFound)));
+
+    let mut paths = list();
+
+    let found = LISTING_SECRET_PATHS.with(|listing| {
+        let mut listing = listing.borrow_mut();
+        let (_, recursion) = listing.last_mut().expect("pushed above");
+        let found = *recursion == Recursion::Found;
+        if found {
+            *recursion = if paths.is_empty() {
+                Recursion::NotSecret
+            } else {
+                Recursion::Secret
+            };
+        }
+        found
+    });
+
+    if found {
+        paths = list();
+    }
+
+    LISTING_SECRET_PATHS.with(|listing| listing.borrow_mut().pop());
+
+    paths
+}
+
+/// Prefixes each of `paths` with `segment`, as used by [`Configuration::secret_paths`].
+#[doc(hidden)]
+pub fn __prefix_paths(segment: &str, paths: Vec<String>) -> impl Iterator<Item = String> + '_ {
+    paths.into_iter().map(move |path| {
+        if path.is_empty() {
+            segment.to_owned()
+        } else {
+            format!("{segment}.{path}")
+        }
+    })
+}
+
+/// Expands a leading `~` in `path` to the home directory, as used by `#[confik(expand_tilde)]`.
+///
+/// Only a bare `~` or a leading `~/` is expanded; `~user` is left as it is, as is any path when the
+/// home directory can't be found.
+#[cfg(feature = "expand_tilde")]
+#[doc(hidden)]
+pub fn __expand_tilde(path: std::path::PathBuf) -> std::path::PathBuf {
+    let Ok(rest) = path.strip_prefix("~") else {
+        return path;
+    };
+
+    match dirs::home_dir() {
+        Some(home) if rest.as_os_str().is_empty() => home,
+        Some(home) => home.join(rest),
+        None => path,
+    }
+}
+
+/// A secret value which can be shown as a hash by `#[confik(secret, log_hash)]`, see
+/// [`__log_hash`].
+#[cfg(feature = "log_hash")]
+#[doc(hidden)]
+pub trait __LogHash {
+    /// The bytes of the value to hash, or `None` if it's missing.
+    fn bytes(&self) -> Option<&[u8]>;
+}
+
+#[cfg(feature = "log_hash")]
+impl __LogHash for str {
+    fn bytes(&self) -> Option<&[u8]> {
+        Some(self.as_bytes())
+    }
+}
+
+#[cfg(feature = "log_hash")]
+impl __LogHash for String {
+    fn bytes(&self) -> Option<&[u8]> {
+        Some(self.as_bytes())
+    }
+}
+
+#[cfg(feature = "log_hash")]
+impl __LogHash for Box<str> {
+    fn bytes(&self) -> Option<&[u8]> {
+        Some(self.as_bytes())
+    }
+}
+
+#[cfg(feature = "log_hash")]
+impl __LogHash for [u8] {
+    fn bytes(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "log_hash")]
+impl __LogHash for Vec<u8> {
+    fn bytes(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "log_hash")]
+impl<T: __LogHash> __LogHash for Option<T> {
+    fn bytes(&self) -> Option<&[u8]> {
+        self.as_ref().and_then(T::bytes)
+    }
+}
+
+/// Formats a secret `value` as a short hash of its bytes, as used by
+/// `#[confik(secret, log_hash)]`, so it can be correlated across logs without being revealed.
+///
+/// The hash is the first 8 hex digits of the SHA-256 digest, so is stable across runs and builds,
+/// and matches hashing the value by other means. A missing value is shown as `None`.
+#[cfg(feature = "log_hash")]
+#[doc(hidden)]
+pub fn __log_hash<T: __LogHash + ?Sized>(value: &T) -> impl fmt::Debug {
+    use sha2::{Digest as _, Sha256};
+
+    struct LogHash(Option<String>);
+
+    impl fmt::Debug for LogHash {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.0 {
+                Some(hash) => fmt::Debug::fmt(hash, f),
+                None => f.write_str("None"),
+            }
+        }
+    }
+
+    LogHash(value.bytes().map(|bytes| {
+        let digest = Sha256::digest(bytes);
+        let hex = digest[..4]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        format!("[redacted sha256:{hex}]")
+    }))
+}
+
+/// Formats a field for `#[confik(redact_display)]`, with its type's own redaction if it has one.
+/// Otherwise, it's formatted as `[redacted]` if its type may contain secrets, see
+/// [`Configuration::secret_paths`].
+#[doc(hidden)]
+pub fn __redacted_field<T: Configuration + fmt::Debug>(value: &T) -> impl fmt::Debug + '_ {
+    RedactedField {
+        value,
+        fmt_redacted: T::__fmt_redacted,
+        secret_paths: T::secret_paths,
+    }
+}
+
+/// As [`__redacted_field`], for a field built from `C` with `#[confik(from)]` or
+/// `#[confik(try_from)]`, so which may contain secrets if `C` does.
+#[doc(hidden)]
+pub fn __redacted_from_field<C: Configuration, T: fmt::Debug>(value: &T) -> impl fmt::Debug + '_ {
+    RedactedField {
+        value,
+        fmt_redacted: |_, _| None,
+        secret_paths: C::secret_paths,
+    }
+}
+
+/// A field formatted by [`__redacted_field`] or [`__redacted_from_field`].
+struct RedactedField<'a, T> {
+    value: &'a T,
+    fmt_redacted: fn(&T, &mut fmt::Formatter<'_>) -> Option<fmt::Result>,
+    secret_paths: fn() -> Vec<String>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for RedactedField<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(result) = (self.fmt_redacted)(self.value, f) {
+            return result;
+        }
+
+        if (self.secret_paths)().is_empty() {
+            fmt::Debug::fmt(self.value, f)
+        } else {
+            fmt::Debug::fmt("[redacted]", f)
+        }
+    }
+}
+
+/// A `Url` field's scheme differed from the one required by `#[confik(url_scheme)]`.
+#[cfg(feature = "url")]
+#[derive(Debug, thiserror::Error)]
+#[error("Expected a URL with scheme `{expected}`, found `{found}`")]
+struct UnexpectedUrlScheme {
+    expected: &'static str,
+    found: String,
+}
+
+/// A field which may be checked with `#[confik(url_scheme)]`.
+#[cfg(feature = "url")]
+#[doc(hidden)]
+pub trait __UrlField {
+    /// The URL to check, if any.
+    fn url(&self) -> Option<&url::Url>;
+}
+
+#[cfg(feature = "url")]
+impl __UrlField for url::Url {
+    fn url(&self) -> Option<&url::Url> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "url")]
+impl __UrlField for Option<url::Url> {
+    fn url(&self) -> Option<&url::Url> {
+        self.as_ref()
+    }
+}
+
+/// Checks that the URL in `field`, if any, has the `expected` scheme, as used by
+/// `#[confik(url_scheme)]`.
+#[cfg(feature = "url")]
+#[doc(hidden)]
+pub fn __check_url_scheme<T: __UrlField>(
+    field: T,
+    expected: &'static str,
+) -> Result<T, impl StdError + Send + Sync + 'static> {
+    match field.url() {
+        Some(url) if url.scheme() != expected => Err(UnexpectedUrlScheme {
+            expected,
+            found: url.scheme().to_owned(),
+        }),
+        _ => Ok(field),
+    }
+}
+
+/// Deserializes a `#[confik(flatten)]` field's builder, see
+/// [`ConfigurationBuilder::__deserialize_flattened`].
+#[doc(hidden)]
+pub fn __deserialize_flattened<'de, D, B>(deserializer: D) -> Result<B, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    B: ConfigurationBuilder,
+{
+    B::__deserialize_flattened(deserializer)
+}
+
+/// Deserializes an integer from a human-readable byte size, e.g. `"512 MiB"`, as used by
+/// `#[confik(bytesize)]`.
+///
+/// Plain integers are also accepted, as bytes.
+#[cfg(feature = "bytesize")]
+#[doc(hidden)]
+pub fn __deserialize_bytesize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: TryFrom<u64>,
+    T::Error: std::fmt::Display,
+{
+    use serde::{de::Error as _, Deserialize as _};
+
+    let size = bytesize::ByteSize::deserialize(deserializer)?;
+    T::try_from(size.as_u64())
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
+/// Deserializes a [`Duration`](std::time::Duration) from an ISO 8601 duration, e.g. `"PT1H30M"`,
+/// as used by `#[confik(iso8601)]`.
+#[doc(hidden)]
+pub fn __deserialize_iso8601<'de, D>(
+    deserializer: D,
+) -> Result<Option<std::time::Duration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::{de::Error as _, Deserialize as _};
+
+    let duration = Cow::<'de, str>::deserialize(deserializer)?;
+    iso8601::parse_duration(&duration)
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
+/// Deserializes a [`SystemTime`](std::time::SystemTime) from an RFC 3339 timestamp in UTC, e.g.
+/// `"2024-01-01T00:00:00Z"`, as used by `#[confik(rfc3339)]`.
+#[cfg(feature = "rfc3339")]
+#[doc(hidden)]
+pub fn __deserialize_rfc3339<'de, D>(
+    deserializer: D,
+) -> Result<Option<std::time::SystemTime>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::{de::Error as _, Deserialize as _};
+
+    let timestamp = Cow::<'de, str>::deserialize(deserializer)?;
+    humantime::parse_rfc3339(&timestamp)
+        .map(Some)
+        .map_err(|err| {
+            D::Error::custom(format_args!(
+                "invalid RFC 3339 timestamp `{timestamp}`: {err}"
+            ))
+        })
 }
 
 /// A builder for a multi-source config deserialization.
@@ -154,6 +961,10 @@ pub trait ConfigurationBuilder: Default + DeserializeOwned {
     /// The target that will be converted into. See [`Configuration`].
     type Target;
 
+    /// Whether the builder reads `SCREAMING_SNAKE_CASE` keys, as set by `#[confik(env_style)]`, so an
+    /// `EnvSource` matches variable names case-sensitively, nested with `__`.
+    const ENV_STYLE: bool = false;
+
     /// Combines two builders recursively, preferring `self`'s data, if present.
     #[must_use]
     fn merge(self, other: Self) -> Self;
@@ -169,6 +980,70 @@ pub trait ConfigurationBuilder: Default + DeserializeOwned {
     /// [`SecretBuilder`] in which case [`UnexpectedSecret`] is passed, which will then be built
     /// into the path to the secret data.
     fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret>;
+
+    /// Whether the builder holds an explicit `None`, e.g. from a `null` in a JSON source.
+    ///
+    /// An explicit `None` counts as data, so overrides any default, but is never a secret, so a
+    /// [`SecretBuilder`] holding one is allowed in sources that don't allow secrets.
+    ///
+    /// This defaults to `false`, and is only `true` for the builder of an `Option` given `None`.
+    fn is_explicit_none(&self) -> bool {
+        false
+    }
+
+    /// Resets any fields marked with `#[confik(env_ignore)]` to their default, recursively, so that
+    /// they are never set by an `EnvSource`.
+    ///
+    /// This is generated by the derive macro and defaults to doing nothing.
+    fn clear_env_ignored(&mut self) {}
+
+    /// Called recursively on each field, checking that no secrets marked with
+    /// `#[confik(secret, env_only)]` contain data. This is only called when the [`Source::kind`] is
+    /// not [`SourceKind::Env`].
+    ///
+    /// This is generated by the derive macro and defaults to finding no such secrets.
+    fn check_env_only(&self) -> Result<(), UnexpectedSecret> {
+        Ok(())
+    }
+
+    /// Called recursively on each field, checking that no fields restricted with
+    /// `#[confik(source_kinds(...))]` contain data from a source of the given `kind`.
+    ///
+    /// This is generated by the derive macro and defaults to finding no such fields.
+    fn check_source_kind(&self, kind: SourceKind) -> Result<(), UnexpectedSource> {
+        let _ = kind;
+        Ok(())
+    }
+
+    /// Called recursively on each field, checking that `self` and `other` don't both contain a value
+    /// for the same leaf, for [`ConfigBuilder::forbid_conflicts`].
+    ///
+    /// This is generated by the derive macro and defaults to finding no conflicts.
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        let _ = other;
+        Ok(())
+    }
+
+    /// Deserializes this builder when flattened into its parent with `#[confik(flatten)]`, so it's
+    /// given every key the parent didn't recognise.
+    ///
+    /// The builders of tagged enums are treated as undefined, rather than failing, if given data
+    /// without their tag, as their data is then for the parent alone.
+    #[doc(hidden)]
+    fn __deserialize_flattened<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+
+    /// Provides this builder from `source`, with the chosen method of [`Source`].
+    ///
+    /// Builders combining others, as for `#[confik(from_builder(...))]`, provide each of them from
+    /// the source in turn, as not every source's data can be buffered to be deserialized twice.
+    #[doc(hidden)]
+    fn __provide<S: Source>(source: &S, provide: __Provide) -> __Provided<Self> {
+        provide.provide(source)
+    }
 }
 
 /// Implementations for trivial types via `Option`.
@@ -194,4 +1069,12 @@ where
     fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
         Ok(self.is_some())
     }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        if self.is_some() && other.is_some() {
+            Err(Conflict::default())
+        } else {
+            Ok(())
+        }
+    }
 }
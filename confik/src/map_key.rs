@@ -0,0 +1,223 @@
+//! Deserialization of map keys which, for non-string keys, also accepts their string form.
+//!
+//! Some formats, such as TOML, only have string keys, so a key of e.g. `u32` would otherwise fail
+//! to deserialize from them.
+
+use std::{fmt, marker::PhantomData, str::FromStr};
+
+use serde::{
+    de::{self, DeserializeSeed, Unexpected, Visitor},
+    Deserialize, Deserializer,
+};
+
+/// Deserializes a map key of type `K`, parsing primitive keys from strings where necessary.
+pub(crate) struct MapKey<K>(PhantomData<fn() -> K>);
+
+impl<K> MapKey<K> {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<'de, K: Deserialize<'de>> DeserializeSeed<'de> for MapKey<K> {
+    type Value = K;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        K::deserialize(MapKeyDeserializer(deserializer))
+    }
+}
+
+/// Wraps a format's key [`Deserializer`], accepting strings wherever a primitive is requested.
+struct MapKeyDeserializer<D>(D);
+
+/// Defines deserializer methods which accept either the primitive itself or its string form.
+macro_rules! deserialize_parsed {
+    ($($method:ident => $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.0.deserialize_any(Parsed::<$ty, V>(visitor, PhantomData))
+            }
+        )*
+    };
+}
+
+/// Defines deserializer methods which are passed straight through to the wrapped deserializer.
+macro_rules! deserialize_forwarded {
+    ($($method:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.0.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for MapKeyDeserializer<D> {
+    type Error = D::Error;
+
+    deserialize_parsed! {
+        deserialize_bool => bool,
+        deserialize_i8 => i8,
+        deserialize_i16 => i16,
+        deserialize_i32 => i32,
+        deserialize_i64 => i64,
+        deserialize_i128 => i128,
+        deserialize_u8 => u8,
+        deserialize_u16 => u16,
+        deserialize_u32 => u32,
+        deserialize_u64 => u64,
+        deserialize_u128 => u128,
+        deserialize_f32 => f32,
+        deserialize_f64 => f64,
+        deserialize_char => char,
+    }
+
+    deserialize_forwarded! {
+        deserialize_any,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier,
+        deserialize_ignored_any,
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        // Keep hold of the key, so that the inner value is parsed too.
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// A primitive which can be parsed from a string key and then handed to a [`Visitor`].
+trait Primitive: FromStr {
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(self, visitor: V) -> Result<V::Value, E>;
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty => $visit:ident),* $(,)?) => {
+        $(
+            impl Primitive for $ty {
+                fn visit<'de, V: Visitor<'de>, E: de::Error>(self, visitor: V) -> Result<V::Value, E> {
+                    visitor.$visit(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    bool => visit_bool,
+    i8 => visit_i8,
+    i16 => visit_i16,
+    i32 => visit_i32,
+    i64 => visit_i64,
+    i128 => visit_i128,
+    u8 => visit_u8,
+    u16 => visit_u16,
+    u32 => visit_u32,
+    u64 => visit_u64,
+    u128 => visit_u128,
+    f32 => visit_f32,
+    f64 => visit_f64,
+    char => visit_char,
+}
+
+/// Visits a primitive key, parsing it as `T` if it is given as a string.
+struct Parsed<T, V>(V, PhantomData<fn() -> T>);
+
+impl<'de, T: Primitive, V: Visitor<'de>> Visitor<'de> for Parsed<T, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(f)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse::<T>()
+            .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))?
+            .visit(self.0)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        self.0.visit_bool(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.0.visit_i64(v)
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        self.0.visit_i128(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.0.visit_u64(v)
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        self.0.visit_u128(v)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        self.0.visit_f64(v)
+    }
+
+    fn visit_char<E: de::Error>(self, v: char) -> Result<Self::Value, E> {
+        self.0.visit_char(v)
+    }
+}
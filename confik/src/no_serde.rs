@@ -0,0 +1,56 @@
+use serde::{de::IgnoredAny, Deserialize, Deserializer};
+
+use crate::{ConfigurationBuilder, Conflict, Error, MissingValue, UnexpectedSecret};
+
+/// Builder for fields marked with `#[confik(no_serde)]`, which are never read from a source, so
+/// their type needn't implement [`Deserialize`].
+///
+/// The field is only ever set programmatically, e.g. with a setter generated by
+/// `#[confik(gen_setters)]`, and otherwise falls back to its default. Its [`Deserialize`]
+/// implementation ignores any data it's given, although the derive macro skips the field entirely.
+#[derive(Debug, Clone)]
+pub struct NoSerdeBuilder<T>(Option<T>);
+
+impl<T> Default for NoSerdeBuilder<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> From<T> for NoSerdeBuilder<T> {
+    fn from(value: T) -> Self {
+        Self(Some(value))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for NoSerdeBuilder<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        IgnoredAny::deserialize(deserializer)?;
+        Ok(Self::default())
+    }
+}
+
+impl<T> ConfigurationBuilder for NoSerdeBuilder<T> {
+    type Target = T;
+
+    fn merge(self, other: Self) -> Self {
+        Self(self.0.or(other.0))
+    }
+
+    fn try_build(self) -> Result<Self::Target, Error> {
+        self.0
+            .ok_or_else(|| Error::MissingValue(MissingValue::default()))
+    }
+
+    fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
+        Ok(self.0.is_some())
+    }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        if self.0.is_some() && other.0.is_some() {
+            Err(Conflict::default())
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Re-exports of the items needed to define and load most configuration.
+//!
+//! ```
+//! # #[cfg(feature = "toml")]
+//! # {
+//! use confik::prelude::*;
+//!
+//! #[derive(Debug, Configuration)]
+//! struct Config {
+//!     port: u16,
+//! }
+//!
+//! fn load() -> Result<Config, Error> {
+//!     Config::builder()
+//!         .override_with(OptionalSource::new(FileSource::new("/etc/app.toml")))
+//!         .override_with(TomlSource::new("port = 8080"))
+//!         .try_build()
+//! }
+//!
+//! assert_eq!(load().unwrap().port, 8080);
+//! # }
+//! ```
+
+#[cfg(feature = "env")]
+pub use crate::EnvSource;
+#[cfg(feature = "include_dir")]
+pub use crate::IncludeDirSource;
+#[cfg(all(windows, feature = "winreg"))]
+pub use crate::RegistrySource;
+#[cfg(feature = "toml")]
+pub use crate::TomlSource;
+pub use crate::{
+    ConfigBuilder, Configuration, ConfigurationBuilder, Error, FileSource, OptionalSource, Source,
+};
+#[cfg(feature = "json")]
+pub use crate::{JsonLinesSource, JsonSource};
@@ -0,0 +1,462 @@
+//! A data-less [`Deserializer`] used to discover the keys a type expects to be given.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+use serde::{
+    de::{self, value::StrDeserializer, DeserializeSeed, IntoDeserializer as _, Visitor},
+    forward_to_deserialize_any, Deserializer,
+};
+
+/// How deep into nested types the probe will look, guarding against recursive types which aren't
+/// caught as back-edges, see [`KeyNames::of`].
+const MAX_DEPTH: usize = 32;
+
+/// The path segment used for the items of a container, rather than a named field or variant.
+pub(crate) const WILDCARD: &str = "*";
+
+/// The keys expected at a single path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyNode {
+    /// A struct, with the given (serde-renamed) field names.
+    Fields(&'static [&'static str]),
+
+    /// An enum, with the given (serde-renamed) variant names.
+    Variants(&'static [&'static str]),
+
+    /// A sequence or map, whose items are found under [`WILDCARD`].
+    Container,
+}
+
+impl KeyNode {
+    /// Whether this is the same node as `other`, i.e. its keys are the same `static`, as they are
+    /// for two instances of the same type.
+    fn is(self, other: Self) -> bool {
+        match (self, other) {
+            (Self::Fields(keys), Self::Fields(other))
+            | (Self::Variants(keys), Self::Variants(other)) => std::ptr::eq(keys, other),
+            _ => false,
+        }
+    }
+}
+
+/// The keys expected by a type, indexed by the path of field and variant names leading to them.
+#[derive(Debug, Default)]
+pub(crate) struct KeyNames {
+    nodes: HashMap<Vec<&'static str>, KeyNode>,
+
+    /// Paths at which a type contains itself, with the path of the outer instance, whose nodes are
+    /// repeated below them.
+    back_edges: HashMap<Vec<&'static str>, Vec<&'static str>>,
+}
+
+impl KeyNames {
+    /// Discovers the keys expected by `T`'s [`Deserialize`](serde::Deserialize) implementation.
+    ///
+    /// Each nested type is reached by a separate, shallow, deserialization which is driven towards
+    /// it by providing only the keys on its path. This is best effort: types which only support
+    /// self-describing formats (e.g., flattened or untagged types) can't be seen through.
+    ///
+    /// Where a type contains itself, e.g. `children: Vec<Node>` in `Node`, the inner instance isn't
+    /// explored again, as there would be exponentially many paths to explore for a type containing
+    /// itself more than once. It's recorded as a back-edge to the outer instance instead.
+    pub(crate) fn of<T: de::DeserializeOwned>() -> Self {
+        let mut names = Self::default();
+        // The name of the type found at each path, given by serde, to tell apart types whose keys
+        // happen to be the same `static`.
+        let mut type_names = HashMap::new();
+        let mut queue = VecDeque::from([Vec::new()]);
+
+        while let Some(path) = queue.pop_front() {
+            let found = RefCell::new(None);
+            let _ = T::deserialize(Probe {
+                target: &path,
+                found: &found,
+            });
+
+            let Some((type_name, node)) = found.into_inner() else {
+                continue;
+            };
+
+            // Struct variants are unnamed, but the enum containing them is found first anyway.
+            let outer = (0..path.len()).find(|&len| {
+                !type_name.is_empty()
+                    && type_names.get(&path[..len]) == Some(&type_name)
+                    && names
+                        .nodes
+                        .get(&path[..len])
+                        .map_or(false, |outer| outer.is(node))
+            });
+
+            if let Some(len) = outer {
+                names.back_edges.insert(path.clone(), path[..len].to_vec());
+            } else if path.len() < MAX_DEPTH {
+                let children = match node {
+                    KeyNode::Fields(names) | KeyNode::Variants(names) => names,
+                    KeyNode::Container => &[WILDCARD],
+                };
+
+                for child in children {
+                    let mut child_path = path.clone();
+                    child_path.push(*child);
+                    queue.push_back(child_path);
+                }
+            }
+
+            type_names.insert(path.clone(), type_name);
+            names.nodes.insert(path, node);
+        }
+
+        names
+    }
+
+    /// Returns the keys expected at `path`, if known, following any back-edges on the way.
+    #[cfg_attr(not(feature = "env"), allow(dead_code))]
+    pub(crate) fn get(&self, path: &[&'static str]) -> Option<KeyNode> {
+        let mut path = path.to_vec();
+
+        // Each back-edge leads to a shorter path, so this ends.
+        while let Some((len, outer)) =
+            (1..path.len()).find_map(|len| Some((len, self.back_edges.get(&path[..len])?)))
+        {
+            path.splice(..len, outer.iter().copied());
+        }
+
+        self.nodes.get(&path).copied()
+    }
+
+    /// Returns the field or variant names expected at each path which has them, ordered by path.
+    pub(crate) fn keyed(&self) -> Vec<(&[&'static str], &'static [&'static str])> {
+        let mut keyed = self
+            .nodes
+            .iter()
+            .filter_map(|(path, node)| match node {
+                KeyNode::Fields(keys) | KeyNode::Variants(keys) => Some((path.as_slice(), *keys)),
+                KeyNode::Container => None,
+            })
+            .collect::<Vec<_>>();
+        keyed.sort_unstable();
+        keyed
+    }
+}
+
+/// Raised to abandon a probing deserialization, either once the target is found or when it can't be
+/// reached.
+#[derive(Debug)]
+struct Stop;
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("probe stopped")
+    }
+}
+
+impl std::error::Error for Stop {}
+
+impl de::Error for Stop {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Self
+    }
+}
+
+/// Walks towards `target`, recording the name of the type there, and the keys it expects, in
+/// `found`.
+struct Probe<'a> {
+    target: &'a [&'static str],
+    found: &'a RefCell<Option<(&'static str, KeyNode)>>,
+}
+
+impl<'a> Probe<'a> {
+    /// Records `node`, of the type `name`, if this is the target, otherwise returns the next segment
+    /// on the path and a probe for the remainder.
+    fn step(self, name: &'static str, node: KeyNode) -> Result<(&'static str, Self), Stop> {
+        match self.target.split_first() {
+            None => {
+                *self.found.borrow_mut() = Some((name, node));
+                Err(Stop)
+            }
+            Some((segment, rest)) => Ok((
+                segment,
+                Self {
+                    target: rest,
+                    found: self.found,
+                },
+            )),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for Probe<'_> {
+    type Error = Stop;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(Stop)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (field, probe) = self.step(name, KeyNode::Fields(fields))?;
+        visitor.visit_map(Single {
+            key: Some(field),
+            value: Some(probe),
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (variant, probe) = self.step(name, KeyNode::Variants(variants))?;
+        visitor.visit_enum(Variant { variant, probe })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let (_, probe) = self.step("", KeyNode::Container)?;
+        visitor.visit_seq(Single {
+            key: None,
+            value: Some(probe),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let (_, probe) = self.step("", KeyNode::Container)?;
+        visitor.visit_map(Single {
+            key: Some(""),
+            value: Some(probe),
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct tuple_struct identifier ignored_any
+    }
+}
+
+/// A map or sequence containing a single item.
+struct Single<'a> {
+    key: Option<&'static str>,
+    value: Option<Probe<'a>>,
+}
+
+impl<'de> de::MapAccess<'de> for Single<'_> {
+    type Error = Stop;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.key
+            .take()
+            .map(|key| seed.deserialize::<StrDeserializer<'_, Stop>>(key.into_deserializer()))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        seed.deserialize(self.value.take().ok_or(Stop)?)
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for Single<'_> {
+    type Error = Stop;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.value
+            .take()
+            .map(|probe| seed.deserialize(probe))
+            .transpose()
+    }
+}
+
+/// Selects the enum variant on the path to the target.
+struct Variant<'a> {
+    variant: &'static str,
+    probe: Probe<'a>,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Variant<'a> {
+    type Error = Stop;
+    type Variant = Probe<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant =
+            seed.deserialize::<StrDeserializer<'_, Stop>>(self.variant.into_deserializer())?;
+        Ok((variant, self.probe))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Probe<'_> {
+    type Error = Stop;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Err(Stop)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_struct("", fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Configuration;
+
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Inner {
+        #[confik(forward_serde(rename = "renamed"))]
+        value: usize,
+    }
+
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    enum Choice {
+        First { inner: Inner },
+        Second,
+    }
+
+    #[derive(Configuration)]
+    #[confik(forward_serde(rename_all = "camelCase"))]
+    #[allow(dead_code)]
+    struct Root {
+        max_conns: usize,
+        inner: Option<Inner>,
+        list: Vec<Inner>,
+        choice: Choice,
+    }
+
+    #[test]
+    fn nested_names() {
+        let names = KeyNames::of::<<Root as Configuration>::Builder>();
+
+        assert_eq!(
+            names.get(&[]),
+            Some(KeyNode::Fields(&["maxConns", "inner", "list", "choice"]))
+        );
+        assert_eq!(names.get(&["maxConns"]), None);
+        assert_eq!(names.get(&["inner"]), Some(KeyNode::Fields(&["renamed"])));
+        assert_eq!(names.get(&["list"]), Some(KeyNode::Container));
+        assert_eq!(
+            names.get(&["list", WILDCARD]),
+            Some(KeyNode::Fields(&["renamed"]))
+        );
+        assert_eq!(
+            names.get(&["choice", "First", "inner"]),
+            Some(KeyNode::Fields(&["renamed"]))
+        );
+    }
+
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Tree {
+        value: usize,
+        left: Vec<Tree>,
+        right: Vec<Tree>,
+    }
+
+    #[test]
+    fn recursive_names() {
+        let names = KeyNames::of::<<Tree as Configuration>::Builder>();
+        let fields = KeyNode::Fields(&["value", "left", "right"]);
+
+        assert_eq!(names.get(&[]), Some(fields));
+        assert_eq!(names.get(&["left", WILDCARD]), Some(fields));
+        assert_eq!(
+            names.get(&["left", WILDCARD, "right", WILDCARD, "left"]),
+            Some(KeyNode::Container)
+        );
+        assert_eq!(
+            names.get(&["left", WILDCARD, "right", WILDCARD]),
+            Some(fields)
+        );
+        assert_eq!(names.get(&["left", WILDCARD, "other"]), None);
+
+        // The inner instances aren't explored.
+        assert_eq!(names.keyed().len(), 3);
+    }
+
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Outer {
+        inner: Middle,
+    }
+
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Middle {
+        inner: Innermost,
+    }
+
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Innermost {
+        value: usize,
+    }
+
+    #[test]
+    fn same_names_are_not_recursion() {
+        let names = KeyNames::of::<<Outer as Configuration>::Builder>();
+
+        assert_eq!(
+            names.get(&["inner", "inner"]),
+            Some(KeyNode::Fields(&["value"]))
+        );
+    }
+}
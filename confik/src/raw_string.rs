@@ -0,0 +1,99 @@
+//! Capturing a value's text, rather than parsing it into a type.
+
+use std::{fmt, ops::Deref};
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::{Configuration, ToBuilder};
+
+/// The text of a configuration value, for passing opaque configuration, e.g. an embedded script
+/// or sub-config for another tool, through without parsing it into a type.
+///
+/// String values are captured as-is. Sources parse their data eagerly, so the original text of any
+/// other value isn't available. Instead, it is re-serialized as compact JSON, losing the source's
+/// formatting, comments, and object key order.
+///
+/// Values are never merged across sources, the highest priority source's value is used whole.
+///
+/// ```
+/// # #[cfg(feature = "toml")]
+/// # {
+/// use confik::{Configuration, RawString, TomlSource};
+///
+/// #[derive(Configuration)]
+/// struct Config {
+///     script: RawString,
+///     plugin: RawString,
+/// }
+///
+/// let config = Config::builder()
+///     .override_with(TomlSource::new(
+///         "script = \"echo hello\"\nplugin = { name = \"fmt\", args = [1, 2] }",
+///     ))
+///     .try_build()
+///     .unwrap();
+///
+/// assert_eq!(config.script.as_str(), "echo hello");
+/// assert_eq!(config.plugin.as_str(), r#"{"args":[1,2],"name":"fmt"}"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RawString(String);
+
+impl RawString {
+    /// Returns the captured text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the captured text, consuming `self`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for RawString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for RawString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<RawString> for String {
+    fn from(raw: RawString) -> Self {
+        raw.0
+    }
+}
+
+impl fmt::Display for RawString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(text) => Ok(Self(text)),
+            value => Ok(Self(value.to_string())),
+        }
+    }
+}
+
+impl Configuration for RawString {
+    type Builder = Option<Self>;
+}
+
+impl ToBuilder for RawString {
+    fn to_builder(&self) -> Self::Builder {
+        Some(self.clone())
+    }
+}
@@ -1,14 +1,19 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt};
 
 use serde::{de::DeserializeOwned, Deserialize};
 use thiserror::Error;
 
-use crate::{path::Path, Configuration, ConfigurationBuilder, Error, MissingValue};
+use crate::{
+    path::Path, Configuration, ConfigurationBuilder, Conflict, Error, MissingValue, SourceKind,
+    UnexpectedSource,
+};
 
 /// Captures the path of a secret found in a non-secret source.
+///
+/// If the secret was classified, e.g. with `#[confik(secret = "credential")]`, then the
+/// classification is included.
 #[derive(Debug, Default, Error)]
-#[error("Found secret at path `{0}`")]
-pub struct UnexpectedSecret(Path);
+pub struct UnexpectedSecret(Path, Option<Cow<'static, str>>);
 
 impl UnexpectedSecret {
     /// Prepends a path segment as we return back up the call-stack.
@@ -17,6 +22,27 @@ impl UnexpectedSecret {
         self.0 .0.push(path_segment.into());
         self
     }
+
+    /// Sets the classification of the secret, e.g. `pii` or `credential`.
+    #[must_use]
+    pub fn with_classification(mut self, classification: impl Into<Cow<'static, str>>) -> Self {
+        self.1 = Some(classification.into());
+        self
+    }
+
+    /// The classification of the secret, if it was given one.
+    pub fn classification(&self) -> Option<&str> {
+        self.1.as_deref()
+    }
+}
+
+impl fmt::Display for UnexpectedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.1 {
+            Some(classification) => write!(f, "Found {classification} secret at path `{}`", self.0),
+            None => write!(f, "Found secret at path `{}`", self.0),
+        }
+    }
 }
 
 /// Wrapper type for carrying secrets, auto-applied to builders when using the `#[config(secret)]`
@@ -31,6 +57,12 @@ impl UnexpectedSecret {
 #[serde(bound = "T: DeserializeOwned")]
 pub struct SecretBuilder<T: ConfigurationBuilder>(T);
 
+impl<T: ConfigurationBuilder> From<T> for SecretBuilder<T> {
+    fn from(builder: T) -> Self {
+        Self(builder)
+    }
+}
+
 impl<T: ConfigurationBuilder> SecretBuilder<T> {
     #[must_use]
     pub fn merge(self, other: Self) -> Self {
@@ -42,14 +74,34 @@ impl<T: ConfigurationBuilder> SecretBuilder<T> {
     }
 
     pub fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
-        // Stop at the earliest secret, so even if we contain further `SecretBuilder`s, which have
-        // returned an `Err`, reset the path.
-        if self.0.contains_non_secret_data().unwrap_or(true) {
-            Err(UnexpectedSecret::default())
-        } else {
-            Ok(false)
+        match self.0.contains_non_secret_data() {
+            // Keep the path to a further secret we contain, e.g. an entry in a map of secrets, as
+            // it's more precise than our own. It's still reported under our own path, so always
+            // within the outermost secret. The further secret's classification is kept, unless we
+            // have our own.
+            Err(err) => Err(err),
+            // An explicit `None` is data, but not a secret.
+            Ok(true) if self.0.is_explicit_none() => Ok(true),
+            Ok(true) => Err(UnexpectedSecret::default()),
+            Ok(false) => Ok(false),
         }
     }
+
+    pub fn clear_env_ignored(&mut self) {
+        self.0.clear_env_ignored();
+    }
+
+    pub fn check_env_only(&self) -> Result<(), UnexpectedSecret> {
+        self.0.check_env_only()
+    }
+
+    pub fn check_source_kind(&self, kind: SourceKind) -> Result<(), UnexpectedSource> {
+        self.0.check_source_kind(kind)
+    }
+
+    pub fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        self.0.check_conflicts(&other.0)
+    }
 }
 
 /// Builder for trivial types that always contain secrets, regardless of the presence of
@@ -61,6 +113,12 @@ impl<T: ConfigurationBuilder> SecretBuilder<T> {
 #[serde(transparent)]
 pub struct SecretOption<T>(Option<T>);
 
+impl<T> From<Option<T>> for SecretOption<T> {
+    fn from(value: Option<T>) -> Self {
+        Self(value)
+    }
+}
+
 impl<T> Default for SecretOption<T> {
     fn default() -> Self {
         Self(None)
@@ -90,4 +148,12 @@ where
             None => Ok(false),
         }
     }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        if self.0.is_some() && other.0.is_some() {
+            Err(Conflict::default())
+        } else {
+            Ok(())
+        }
+    }
 }
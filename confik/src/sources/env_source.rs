@@ -1,11 +1,23 @@
-use std::error::Error;
+use std::{borrow::Cow, collections::HashMap, error::Error, fmt, io, path::PathBuf};
 
-use crate::{ConfigurationBuilder, Source};
+use serde::{
+    de::{DeserializeOwned, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use thiserror::Error;
+
+use crate::{
+    probe::{KeyNames, KeyNode, WILDCARD},
+    ConfigurationBuilder, Source, SourceKind,
+};
 
 /// A [`Source`] referring to environment variables.
 ///
 /// Uses the [envious](https://docs.rs/envious) crate for interpreting env vars.
 ///
+/// Variable names are matched case-insensitively, unless the builder uses `#[confik(env_style)]`,
+/// in which case they, and any prefix, are matched exactly, with nested names separated by `__`.
+///
 /// # Examples
 ///
 /// ```
@@ -25,10 +37,14 @@ use crate::{ConfigurationBuilder, Source};
 ///
 /// assert_eq!(config.port, 1234);
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct EnvSource<'a> {
     config: envious::Config<'a>,
+    prefixes: Vec<&'a str>,
+    normalize_keys: bool,
+    file_indirection: bool,
     allow_secrets: bool,
+    vars: Option<HashMap<String, String>>,
 }
 
 impl Default for EnvSource<'_> {
@@ -42,7 +58,23 @@ impl<'a> EnvSource<'a> {
     pub fn new() -> Self {
         Self {
             config: envious::Config::new(),
+            prefixes: Vec::new(),
+            normalize_keys: false,
+            file_indirection: false,
             allow_secrets: false,
+            vars: None,
+        }
+    }
+
+    /// Creates a new [`Source`] which reads the given variables instead of the process's
+    /// environment.
+    ///
+    /// Useful for testing, or for env-style configuration that came from elsewhere. All other
+    /// options apply as they would to environment variables.
+    pub fn from_map(vars: HashMap<String, String>) -> Self {
+        Self {
+            vars: Some(vars),
+            ..Self::new()
         }
     }
 
@@ -54,9 +86,21 @@ impl<'a> EnvSource<'a> {
         self
     }
 
+    /// Reads variables matching any of the given prefixes.
+    ///
+    /// The environment is scanned once per prefix and the results are merged, with earlier prefixes
+    /// taking precedence over later ones. Overrides any prefix set with [`with_prefix()`].
+    ///
+    /// [`with_prefix()`]: Self::with_prefix()
+    pub fn with_prefixes(mut self, prefixes: &[&'a str]) -> Self {
+        self.prefixes = prefixes.to_vec();
+        self
+    }
+
     /// Sets the envious separator.
     ///
-    /// See [`envious::Config::with_separator()`].
+    /// See [`envious::Config::with_separator()`]. Builders using `#[confik(env_style)]` always use
+    /// `__`.
     pub fn with_separator(mut self, separator: &'a str) -> Self {
         self.config.with_separator(separator);
         self
@@ -68,6 +112,31 @@ impl<'a> EnvSource<'a> {
         self
     }
 
+    /// Matches variable names to the field and variant names expected by the builder, ignoring case
+    /// and any `_` or `-` characters.
+    ///
+    /// The expected names are taken from the builder's `Deserialize` implementation, so they reflect
+    /// any serde renaming. E.g., `MAX_CONNS` will populate a field named `maxConns` when the struct
+    /// uses `#[confik(forward_serde(rename_all = "camelCase"))]`. Names with an exact
+    /// (case-insensitive) match are preferred.
+    pub fn normalize_keys(mut self) -> Self {
+        self.normalize_keys = true;
+        self
+    }
+
+    /// Reads the value of any variable starting with `@` from the file at the path which follows it,
+    /// e.g., `TOKEN=@/run/secrets/token`.
+    ///
+    /// Only variables matching the builder's fields are read from files, so unrelated variables in
+    /// the environment are left alone. A single trailing newline is removed from the file's
+    /// contents. A literal leading `@` can be given by doubling it, e.g., `GREETING=@@everyone`.
+    /// Values read from files are still provided by this source, so secrets read this way require
+    /// [`allow_secrets()`](Self::allow_secrets).
+    pub fn with_file_indirection(mut self, file_indirection: bool) -> Self {
+        self.file_indirection = file_indirection;
+        self
+    }
+
     /// Allows this source to contain secrets.
     pub fn allow_secrets(mut self) -> Self {
         self.allow_secrets = true;
@@ -80,8 +149,305 @@ impl Source for EnvSource<'_> {
         self.allow_secrets
     }
 
+    fn kind(&self) -> SourceKind {
+        SourceKind::Env
+    }
+
     fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
-        Ok(self.config.build_from_env()?)
+        // The builder's keys are already in the variables' case, so must be matched exactly.
+        let config = if T::ENV_STYLE {
+            let mut config = self.config.clone();
+            config
+                .case_sensitive(true)
+                .with_separator(ENV_STYLE_SEPARATOR);
+            Cow::Owned(config)
+        } else {
+            Cow::Borrowed(&self.config)
+        };
+
+        let mut builder = if self.prefixes.is_empty() {
+            self.build::<T>(&config)?
+        } else {
+            self.prefixes
+                .iter()
+                .map(|prefix| {
+                    let mut config = config.clone().into_owned();
+                    config.with_prefix(*prefix);
+                    self.build::<T>(&config)
+                })
+                .try_fold(T::default(), |acc, builder| {
+                    Ok::<_, Box<dyn Error + Sync + Send>>(acc.merge(builder?))
+                })?
+        };
+
+        builder.clear_env_ignored();
+
+        Ok(builder)
+    }
+}
+
+// Values given to `from_map` may be secrets, so only their names are shown.
+impl fmt::Debug for EnvSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("EnvSource");
+        debug
+            .field("config", &self.config)
+            .field("prefixes", &self.prefixes)
+            .field("normalize_keys", &self.normalize_keys)
+            .field("file_indirection", &self.file_indirection)
+            .field("allow_secrets", &self.allow_secrets);
+
+        if let Some(vars) = &self.vars {
+            let mut names = vars.keys().collect::<Vec<_>>();
+            names.sort();
+            debug.field("var_names", &names);
+        }
+
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl EnvSource<'_> {
+    fn build<T: ConfigurationBuilder>(
+        &self,
+        config: &envious::Config<'_>,
+    ) -> Result<T, Box<dyn Error + Sync + Send>> {
+        if !self.normalize_keys && !self.file_indirection {
+            return Ok(self.read_vars(config)?);
+        }
+
+        // Let envious apply the prefix, separator, and casing rules, then rewrite the variables it
+        // found before handing them back to envious to deserialize the builder.
+        let mut tree = self.read_vars::<KeyTree>(config)?;
+        let names = KeyNames::of::<T>();
+
+        if self.file_indirection {
+            tree.read_files(&names, Some(Vec::new()), String::new())?;
+        }
+
+        let (names, path) = if self.normalize_keys {
+            (names, Some(Vec::new()))
+        } else {
+            (KeyNames::default(), None)
+        };
+
+        let mut vars = Vec::new();
+        tree.flatten(&names, path, String::new(), &mut vars);
+
+        let mut config = envious::Config::new();
+        config
+            .with_separator(NORMALIZED_SEPARATOR)
+            .case_sensitive(true);
+        Ok(config.build_from_iter(vars)?)
+    }
+
+    /// Deserializes `T` from the provided variables, or from the environment if there are none.
+    fn read_vars<T: DeserializeOwned>(
+        &self,
+        config: &envious::Config<'_>,
+    ) -> Result<T, envious::EnvDeserializationError> {
+        match &self.vars {
+            Some(vars) => {
+                config.build_from_iter(vars.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            }
+            None => config.build_from_env(),
+        }
+    }
+}
+
+/// Separator between the names of nested fields for builders using `#[confik(env_style)]`.
+const ENV_STYLE_SEPARATOR: &str = "__";
+
+/// Separator used once keys have been normalized, which can't appear in an environment variable.
+const NORMALIZED_SEPARATOR: &str = "\0";
+
+/// Environment variables, as nested by envious.
+#[derive(Debug)]
+enum KeyTree {
+    Leaf(String),
+    Node(Vec<(String, KeyTree)>),
+}
+
+/// A variable's value could not be read from the file it referred to.
+#[derive(Debug, Error)]
+#[error("Could not read `{key}` from {}", .path.display())]
+struct FileIndirectionError {
+    key: String,
+    path: PathBuf,
+
+    #[source]
+    source: io::Error,
+}
+
+impl KeyTree {
+    /// Replaces values of the form `@path` with the contents of the file at `path`.
+    ///
+    /// Only variables read by the builder are replaced, so that unrelated variables, e.g. when no
+    /// prefix is used, are left alone. `path` is the path of field and variant names to this node,
+    /// or `None` once a key isn't one of those expected. Past where the expected names can't be
+    /// discovered, e.g. into a flattened type, every variable is assumed to be read.
+    ///
+    /// `key` is the dotted path to this node, for use in errors.
+    fn read_files(
+        &mut self,
+        names: &KeyNames,
+        path: Option<Vec<&'static str>>,
+        key: String,
+    ) -> Result<(), FileIndirectionError> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+
+        match self {
+            Self::Leaf(value) => match value.strip_prefix('@') {
+                Some(literal) if literal.starts_with('@') => *value = literal.to_owned(),
+                Some(path) => {
+                    let path = PathBuf::from(path);
+                    let mut contents = std::fs::read_to_string(&path)
+                        .map_err(|source| FileIndirectionError { key, path, source })?;
+
+                    if contents.ends_with('\n') {
+                        contents.pop();
+                        if contents.ends_with('\r') {
+                            contents.pop();
+                        }
+                    }
+
+                    *value = contents;
+                }
+                None => {}
+            },
+
+            Self::Node(entries) => {
+                for (entry_key, entry) in entries {
+                    let entry_path = match names.get(&path) {
+                        Some(KeyNode::Fields(expected) | KeyNode::Variants(expected)) => {
+                            find_name(expected, entry_key).map(|name| {
+                                let mut path = path.clone();
+                                path.push(name);
+                                path
+                            })
+                        }
+                        Some(KeyNode::Container) => {
+                            let mut path = path.clone();
+                            path.push(WILDCARD);
+                            Some(path)
+                        }
+                        None => Some(path.clone()),
+                    };
+
+                    let key = if key.is_empty() {
+                        entry_key.clone()
+                    } else {
+                        format!("{key}.{entry_key}")
+                    };
+                    entry.read_files(names, entry_path, key)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flattens the tree back into variables, renaming keys to the names expected at `path`.
+    ///
+    /// `path` is `None` once a key couldn't be matched, after which keys are left as they are.
+    fn flatten(
+        self,
+        names: &KeyNames,
+        path: Option<Vec<&'static str>>,
+        key: String,
+        vars: &mut Vec<(String, String)>,
+    ) {
+        let node = path.as_deref().and_then(|path| names.get(path));
+
+        match self {
+            Self::Leaf(value) => {
+                let value = match node {
+                    Some(KeyNode::Variants(variants)) => {
+                        find_name(variants, &value).map_or(value, str::to_owned)
+                    }
+                    _ => value,
+                };
+                vars.push((key, value));
+            }
+
+            Self::Node(entries) => {
+                for (entry_key, entry) in entries {
+                    let (entry_key, entry_path) = match node {
+                        Some(KeyNode::Fields(expected) | KeyNode::Variants(expected)) => {
+                            match find_name(expected, &entry_key) {
+                                Some(name) => (name.to_owned(), Some(name)),
+                                None => (entry_key, None),
+                            }
+                        }
+                        Some(KeyNode::Container) => (entry_key, Some(WILDCARD)),
+                        None => (entry_key, None),
+                    };
+
+                    let entry_path = path.clone().zip(entry_path).map(|(mut path, segment)| {
+                        path.push(segment);
+                        path
+                    });
+
+                    let full_key = if key.is_empty() {
+                        entry_key
+                    } else {
+                        format!("{key}{NORMALIZED_SEPARATOR}{entry_key}")
+                    };
+
+                    entry.flatten(names, entry_path, full_key, vars);
+                }
+            }
+        }
+    }
+}
+
+/// Finds the name matching `key`, preferring a case-insensitive match over one that also ignores
+/// `_` and `-`.
+fn find_name(names: &'static [&'static str], key: &str) -> Option<&'static str> {
+    fn normalize(name: &str) -> impl Iterator<Item = char> + '_ {
+        name.chars()
+            .filter(|c| !matches!(c, '_' | '-'))
+            .map(|c| c.to_ascii_lowercase())
+    }
+
+    names
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(key))
+        .or_else(|| names.iter().find(|name| normalize(name).eq(normalize(key))))
+        .copied()
+}
+
+impl<'de> Deserialize<'de> for KeyTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyTreeVisitor;
+
+        impl<'de> Visitor<'de> for KeyTreeVisitor {
+            type Value = KeyTree;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an environment variable or a map of them")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(KeyTree::Leaf(value.to_owned()))
+            }
+
+            fn visit_string<E: serde::de::Error>(self, value: String) -> Result<Self::Value, E> {
+                Ok(KeyTree::Leaf(value))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(KeyTree::Node(entries))
+            }
+        }
+
+        deserializer.deserialize_any(KeyTreeVisitor)
     }
 }
 
@@ -102,6 +468,21 @@ mod tests {
         assert!(source.clone().allow_secrets);
     }
 
+    #[test]
+    fn debug_omits_values() {
+        let source = EnvSource::from_map(HashMap::from([
+            ("PASSWORD".to_owned(), "hunter2".to_owned()),
+            ("PORT".to_owned(), "8080".to_owned()),
+        ]));
+        let debug = format!("{source:?}");
+        assert!(
+            debug.contains(r#"var_names: ["PASSWORD", "PORT"]"#),
+            "{debug}"
+        );
+        assert!(!debug.contains("hunter2"), "{debug}");
+        assert!(!debug.contains("8080"), "{debug}");
+    }
+
     #[test]
     fn separator() {
         let mut config = envious::Config::new();
@@ -116,4 +497,21 @@ mod tests {
 
         assert!(source_debug.contains(&config_debug));
     }
+
+    #[test]
+    fn prefixes() {
+        let source = EnvSource::default().with_prefixes(&["APP_", "SVC_"]);
+        assert_eq!(source.prefixes, ["APP_", "SVC_"]);
+    }
+
+    #[test]
+    fn find_names() {
+        let names = &["maxConns", "max_conns_total", "Mode"];
+
+        assert_eq!(find_name(names, "maxconns"), Some("maxConns"));
+        assert_eq!(find_name(names, "max_conns"), Some("maxConns"));
+        assert_eq!(find_name(names, "MAX_CONNS_TOTAL"), Some("max_conns_total"));
+        assert_eq!(find_name(names, "mode"), Some("Mode"));
+        assert_eq!(find_name(names, "other"), None);
+    }
 }
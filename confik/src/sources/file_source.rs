@@ -1,21 +1,27 @@
-use std::{error::Error, path::PathBuf};
+#[cfg(feature = "json")]
+use std::sync::Arc;
+use std::{
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+};
 
 use cfg_if::cfg_if;
 use thiserror::Error;
 
-use crate::{ConfigurationBuilder, Source};
+use crate::{ConfigurationBuilder, Source, SourceKind};
 
 #[derive(Debug, Error)]
 #[error("Could not parse {}", .path.display())]
-struct FileError {
-    path: PathBuf,
+pub(crate) struct FileError {
+    pub(crate) path: PathBuf,
 
     #[source]
-    kind: FileErrorKind,
+    pub(crate) kind: FileErrorKind,
 }
 
 #[derive(Debug, Error)]
-enum FileErrorKind {
+pub(crate) enum FileErrorKind {
     #[error(transparent)]
     CouldNotReadFile(#[from] std::io::Error),
 
@@ -33,13 +39,47 @@ enum FileErrorKind {
     #[cfg(feature = "json")]
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+
+    #[cfg(feature = "json")]
+    #[error(transparent)]
+    CustomFormat(Box<dyn Error + Sync + Send>),
 }
 
+/// A user-registered handler for a custom file format.
+#[cfg(feature = "json")]
+type FormatHandler =
+    Arc<dyn Fn(&str) -> Result<serde_json::Value, Box<dyn Error + Sync + Send>> + Sync + Send>;
+
 /// A [`Source`] referring to a file path.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct FileSource {
     path: PathBuf,
     allow_secrets: bool,
+    allow_missing: bool,
+
+    #[cfg(feature = "json")]
+    formats: Vec<(String, FormatHandler)>,
+}
+
+impl fmt::Debug for FileSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("FileSource");
+        debug
+            .field("path", &self.path)
+            .field("allow_secrets", &self.allow_secrets);
+
+        if self.allow_missing {
+            debug.field("allow_missing", &self.allow_missing);
+        }
+
+        #[cfg(feature = "json")]
+        if !self.formats.is_empty() {
+            let extensions = self.formats.iter().map(|(ext, _)| ext).collect::<Vec<_>>();
+            debug.field("formats", &extensions);
+        }
+
+        debug.finish()
+    }
 }
 
 impl FileSource {
@@ -50,10 +90,19 @@ impl FileSource {
     /// Supported extensions:
     /// - `toml`
     /// - `json`
+    ///
+    /// Files compressed with gzip (`.gz`, requires the `flate2` feature) or zstd (`.zst`, requires
+    /// the `zstd` feature) are decompressed first, and their format determined by the remaining
+    /// extension, e.g. `config.toml.gz`.
+    ///
+    /// Other extensions can be supported with [`register_format`](Self::register_format).
     pub fn new(path: impl Into<PathBuf>) -> Self {
         Self {
             path: path.into(),
             allow_secrets: false,
+            allow_missing: false,
+            #[cfg(feature = "json")]
+            formats: Vec::new(),
         }
     }
 
@@ -63,33 +112,156 @@ impl FileSource {
         self
     }
 
+    /// Allows the file to not exist, in which case this source is skipped.
+    ///
+    /// Unlike wrapping the source in an [`OptionalSource`](crate::OptionalSource), other errors,
+    /// such as the file containing invalid data, still fail the build. See
+    /// [`Source::is_optional`].
+    pub fn allow_missing(mut self) -> Self {
+        self.allow_missing = true;
+        self
+    }
+
+    /// Registers a handler for files with the given `extension`.
+    ///
+    /// The handler parses the file contents into a [`serde_json::Value`], which is then
+    /// deserialized into the configuration builder. Registered handlers take precedence over the
+    /// built-in formats, so they can also be used to override how `toml` or `json` files are
+    /// read. As with the built-in formats, empty files give an empty builder without calling the
+    /// handler.
+    ///
+    /// ```
+    /// # use confik::FileSource;
+    /// let source = FileSource::new("config.yaml").register_format("yaml", |contents: &str| {
+    ///     // parse `contents` with your YAML library of choice
+    ///     # let _ = contents;
+    ///     Ok::<_, std::io::Error>(serde_json::json!({}))
+    /// });
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn register_format<F, E>(mut self, extension: impl Into<String>, handler: F) -> Self
+    where
+        F: Fn(&str) -> Result<serde_json::Value, E> + Sync + Send + 'static,
+        E: Into<Box<dyn Error + Sync + Send>>,
+    {
+        let handler: FormatHandler =
+            Arc::new(move |contents| handler(contents).map_err(Into::into));
+        self.formats.push((extension.into(), handler));
+        self
+    }
+
     fn deserialize<T: ConfigurationBuilder>(&self) -> Result<T, FileErrorKind> {
-        #[allow(unused_variables)]
-        let contents = std::fs::read_to_string(&self.path)?;
-
-        match self.path.extension().and_then(|ext| ext.to_str()) {
-            Some("toml") => {
-                cfg_if! {
-                    if #[cfg(feature = "toml")] {
-                        Ok(toml::from_str(&contents)?)
-                    } else {
-                        Err(FileErrorKind::MissingFeatureForExtension("toml"))
-                    }
+        let (path, contents) = read_contents(&self.path)?;
+
+        #[cfg(feature = "json")]
+        if let Some(handler) = self.format_handler(path) {
+            if contents.trim().is_empty() {
+                return Ok(T::default());
+            }
+
+            let value = handler(&contents).map_err(FileErrorKind::CustomFormat)?;
+            return Ok(serde_json::from_value(value)?);
+        }
+
+        deserialize_contents(path, &contents)
+    }
+
+    /// Returns the most recently registered handler for the extension of `path`, if any.
+    #[cfg(feature = "json")]
+    fn format_handler(&self, path: &Path) -> Option<&FormatHandler> {
+        let extension = path.extension().and_then(|ext| ext.to_str())?;
+
+        self.formats
+            .iter()
+            .rev()
+            .find(|(ext, _)| ext == extension)
+            .map(|(_, handler)| handler)
+    }
+}
+
+/// Reads the file at `path`, decompressing it if it has a compression extension.
+///
+/// Returns the path with any compression extension peeled off, for determining the format, along
+/// with the (decompressed) contents.
+fn read_contents(path: &Path) -> Result<(&Path, String), FileErrorKind> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => {
+            cfg_if! {
+                if #[cfg(feature = "flate2")] {
+                    use std::io::Read as _;
+
+                    let file = std::fs::File::open(path)?;
+                    let mut contents = String::new();
+                    flate2::read::GzDecoder::new(file).read_to_string(&mut contents)?;
+                    Ok((strip_extension(path), contents))
+                } else {
+                    Err(FileErrorKind::MissingFeatureForExtension("flate2"))
+                }
+            }
+        }
+
+        Some("zst") => {
+            cfg_if! {
+                if #[cfg(feature = "zstd")] {
+                    let file = std::fs::File::open(path)?;
+                    let contents = String::from_utf8(zstd::decode_all(file)?).map_err(|err| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+                    })?;
+                    Ok((strip_extension(path), contents))
+                } else {
+                    Err(FileErrorKind::MissingFeatureForExtension("zstd"))
                 }
             }
+        }
 
-            Some("json") => {
-                cfg_if! {
-                    if #[cfg(feature = "json")] {
-                        Ok(serde_json::from_str(&contents)?)
-                    } else {
-                        Err(FileErrorKind::MissingFeatureForExtension("json"))
+        _ => Ok((path, std::fs::read_to_string(path)?)),
+    }
+}
+
+/// Strips the last extension from `path`, keeping only the file name.
+#[cfg(any(feature = "flate2", feature = "zstd"))]
+fn strip_extension(path: &Path) -> &Path {
+    Path::new(path.file_stem().unwrap_or_default())
+}
+
+/// Deserializes the contents of the file at `path`, determining the format by its extension.
+///
+/// Empty (or whitespace-only) contents give an empty builder, regardless of the format.
+#[allow(unused_variables)]
+pub(crate) fn deserialize_contents<T: ConfigurationBuilder>(
+    path: &Path,
+    contents: &str,
+) -> Result<T, FileErrorKind> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => {
+            cfg_if! {
+                if #[cfg(feature = "toml")] {
+                    if contents.trim().is_empty() {
+                        return Ok(T::default());
                     }
+
+                    Ok(toml::from_str(contents)?)
+                } else {
+                    Err(FileErrorKind::MissingFeatureForExtension("toml"))
                 }
             }
+        }
+
+        Some("json") => {
+            cfg_if! {
+                if #[cfg(feature = "json")] {
+                    if contents.trim().is_empty() {
+                        return Ok(T::default());
+                    }
 
-            _ => Err(FileErrorKind::UnknownExtension),
+                    Ok(serde_json::from_str(contents)?)
+                } else {
+                    Err(FileErrorKind::MissingFeatureForExtension("json"))
+                }
+            }
         }
+
+        _ => Err(FileErrorKind::UnknownExtension),
     }
 }
 
@@ -98,6 +270,14 @@ impl Source for FileSource {
         self.allow_secrets
     }
 
+    fn kind(&self) -> SourceKind {
+        SourceKind::File
+    }
+
+    fn is_optional(&self) -> bool {
+        self.allow_missing
+    }
+
     fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
         self.deserialize().map_err(|err| {
             Box::new(FileError {
@@ -138,6 +318,33 @@ mod tests {
         assert!(source.clone().allow_secrets);
     }
 
+    #[test]
+    fn allow_missing() {
+        let source = FileSource::new("config.toml");
+        assert!(!source.is_optional());
+
+        let source = source.allow_missing();
+        assert!(source.is_optional());
+        assert!(format!("{source:?}").contains("allow_missing: true"));
+    }
+
+    #[test]
+    fn missing_is_not_found() {
+        let source = FileSource::new("non-existent-config.toml");
+        let err = source.provide::<Option<NoopConfig>>().unwrap_err();
+        assert!(crate::sources::is_not_found(&*err));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let cfg_path = dir.path().join("config.cfg");
+        fs::write(&cfg_path, "").unwrap();
+
+        let source = FileSource::new(&cfg_path);
+        let err = source.provide::<Option<NoopConfig>>().unwrap_err();
+        assert!(!crate::sources::is_not_found(&*err));
+
+        dir.close().unwrap();
+    }
+
     #[test]
     fn non_existent() {
         let source = FileSource::new("non-existent-config.toml");
@@ -180,6 +387,11 @@ mod tests {
             "unexpected error message: {err}",
         );
 
+        fs::write(&json_path, "").unwrap();
+        let source = FileSource::new(&json_path);
+        let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
+        assert!(config.is_none());
+
         fs::write(&json_path, "{\"foo\":42}").unwrap();
         let source = FileSource::new(&json_path);
         let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
@@ -188,6 +400,127 @@ mod tests {
         dir.close().unwrap();
     }
 
+    #[cfg(feature = "json")]
+    #[test]
+    fn custom_format() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let cfg_path = dir.path().join("config.kv");
+
+        // a toy `key=value` format
+        let source = FileSource::new(&cfg_path).register_format("kv", |contents: &str| {
+            contents
+                .lines()
+                .map(|line| {
+                    let (key, value) = line.split_once('=').ok_or("expected `key=value`")?;
+                    let value = value.trim().parse::<u64>().map_err(|err| err.to_string())?;
+                    Ok((key.trim().to_owned(), serde_json::Value::from(value)))
+                })
+                .collect::<Result<serde_json::Map<_, _>, String>>()
+                .map(serde_json::Value::Object)
+        });
+        assert!(format!("{source:?}").contains("formats: [\"kv\"]"));
+
+        fs::write(&cfg_path, "foo = 42").unwrap();
+        let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
+        assert_eq!(config.unwrap().foo, 42);
+
+        fs::write(&cfg_path, "").unwrap();
+        let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
+        assert!(config.is_none());
+
+        fs::write(&cfg_path, "foo").unwrap();
+        let err = source.deserialize::<Option<SimpleConfig>>().unwrap_err();
+        assert!(
+            err.to_string().contains("expected `key=value`"),
+            "unexpected error message: {err}",
+        );
+
+        fs::write(&cfg_path, "bar = 1").unwrap();
+        let err = source.deserialize::<Option<SimpleConfig>>().unwrap_err();
+        assert!(
+            err.to_string().contains("missing field"),
+            "unexpected error message: {err}",
+        );
+
+        // handlers are registered per source
+        let source = FileSource::new(&cfg_path);
+        let err = source.deserialize::<Option<NoopConfig>>().unwrap_err();
+        assert!(
+            err.to_string().contains("Unknown file extension"),
+            "unexpected error message: {err}",
+        );
+
+        dir.close().unwrap();
+    }
+
+    #[cfg(all(feature = "flate2", feature = "toml"))]
+    #[test]
+    fn gzip() {
+        use std::io::Write as _;
+
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let gz_path = dir.path().join("config.toml.gz");
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"foo = 42").unwrap();
+        fs::write(&gz_path, encoder.finish().unwrap()).unwrap();
+
+        let source = FileSource::new(&gz_path);
+        let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
+        assert_eq!(config.unwrap().foo, 42);
+
+        fs::write(&gz_path, "foo = 42").unwrap();
+        let source = FileSource::new(&gz_path);
+        source.deserialize::<Option<SimpleConfig>>().unwrap_err();
+
+        dir.close().unwrap();
+    }
+
+    #[cfg(not(feature = "flate2"))]
+    #[test]
+    fn gzip_missing_feature() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let gz_path = dir.path().join("config.toml.gz");
+        fs::write(&gz_path, "").unwrap();
+
+        let source = FileSource::new(&gz_path);
+        let err = source.deserialize::<Option<NoopConfig>>().unwrap_err();
+        assert!(
+            err.to_string().contains("flate2 feature is not enabled"),
+            "unexpected error message: {err}",
+        );
+
+        dir.close().unwrap();
+    }
+
+    #[cfg(all(feature = "zstd", feature = "json"))]
+    #[test]
+    fn zstd() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let zst_path = dir.path().join("config.json.zst");
+        fs::write(
+            &zst_path,
+            zstd::encode_all(&b"{\"foo\":42}"[..], 0).unwrap(),
+        )
+        .unwrap();
+
+        let source = FileSource::new(&zst_path);
+        let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
+        assert_eq!(config.unwrap().foo, 42);
+
+        let source = FileSource::new(dir.path().join("config.kv.zst"));
+        fs::copy(&zst_path, dir.path().join("config.kv.zst")).unwrap();
+        let source = source.register_format("kv", |contents: &str| serde_json::from_str(contents));
+        let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
+        assert_eq!(config.unwrap().foo, 42);
+
+        dir.close().unwrap();
+    }
+
     #[cfg(feature = "toml")]
     #[test]
     fn toml() {
@@ -195,7 +528,7 @@ mod tests {
 
         let toml_path = dir.path().join("config.toml");
 
-        fs::write(&toml_path, "").unwrap();
+        fs::write(&toml_path, "# no fields").unwrap();
         let source = FileSource::new(&toml_path);
         let err = source.deserialize::<Option<SimpleConfig>>().unwrap_err();
         assert!(
@@ -203,6 +536,11 @@ mod tests {
             "unexpected error message: {err}",
         );
 
+        fs::write(&toml_path, " \n").unwrap();
+        let source = FileSource::new(&toml_path);
+        let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
+        assert!(config.is_none());
+
         fs::write(&toml_path, "foo = 42").unwrap();
         let source = FileSource::new(&toml_path);
         let config = source.deserialize::<Option<SimpleConfig>>().unwrap();
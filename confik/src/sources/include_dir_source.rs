@@ -0,0 +1,131 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Formatter},
+    io,
+};
+
+use include_dir::Dir;
+
+use super::file_source::{deserialize_contents, FileError, FileErrorKind};
+use crate::{ConfigurationBuilder, Source, SourceKind};
+
+/// A [`Source`] referring to a directory embedded in the binary using [`include_dir`].
+///
+/// Each file directly inside the directory is deserialized according to its extension, in the same
+/// way as a [`FileSource`](crate::FileSource). Files with other extensions, and any
+/// sub-directories, are ignored. Files are merged in order of their paths, with later files
+/// overriding earlier ones, e.g. `10-overrides.toml` overrides `00-defaults.toml`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "toml", feature = "json"))]
+/// # {
+/// use confik::{Configuration, IncludeDirSource};
+/// use include_dir::{include_dir, Dir};
+///
+/// static DEFAULTS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/include_dir_source/config");
+///
+/// #[derive(Configuration)]
+/// struct Config {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let config = Config::builder()
+///     .override_with(IncludeDirSource::new(&DEFAULTS))
+///     .try_build()
+///     .unwrap();
+///
+/// assert_eq!(config.port, 8080);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct IncludeDirSource<'a> {
+    dir: &'a Dir<'a>,
+    allow_secrets: bool,
+}
+
+impl<'a> IncludeDirSource<'a> {
+    /// Creates a [`Source`] referring to an embedded directory.
+    pub fn new(dir: &'a Dir<'a>) -> Self {
+        Self {
+            dir,
+            allow_secrets: false,
+        }
+    }
+
+    /// Allows this source to contain secrets.
+    pub fn allow_secrets(mut self) -> Self {
+        self.allow_secrets = true;
+        self
+    }
+}
+
+impl Source for IncludeDirSource<'_> {
+    fn allows_secrets(&self) -> bool {
+        self.allow_secrets
+    }
+
+    fn kind(&self) -> SourceKind {
+        SourceKind::File
+    }
+
+    fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
+        let mut files = self.dir.files().collect::<Vec<_>>();
+        files.sort_by_key(|file| file.path());
+
+        files
+            .into_iter()
+            .try_fold(T::default(), |acc, file| {
+                let contents = file.contents_utf8().ok_or_else(|| {
+                    FileErrorKind::CouldNotReadFile(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "file is not valid UTF-8",
+                    ))
+                });
+
+                match contents.and_then(|contents| deserialize_contents::<T>(file.path(), contents))
+                {
+                    Ok(builder) => Ok(builder.merge(acc)),
+                    Err(FileErrorKind::UnknownExtension) => Ok(acc),
+                    Err(kind) => Err(FileError {
+                        path: file.path().to_owned(),
+                        kind,
+                    }),
+                }
+            })
+            .map_err(Into::into)
+    }
+}
+
+impl Debug for IncludeDirSource<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IncludeDirSource")
+            .field("path", &self.dir.path())
+            .field("allow_secrets", &self.allow_secrets)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use include_dir::{include_dir, Dir};
+
+    use super::*;
+
+    static DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/include_dir_source/config");
+
+    #[test]
+    fn defaults() {
+        let source = IncludeDirSource::new(&DIR);
+        assert!(!source.allows_secrets());
+    }
+
+    #[test]
+    fn clone() {
+        let source = IncludeDirSource::new(&DIR).allow_secrets();
+        assert!(source.allows_secrets());
+        assert!(source.clone().allow_secrets);
+    }
+}
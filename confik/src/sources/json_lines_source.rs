@@ -0,0 +1,118 @@
+use std::{borrow::Cow, error::Error, fmt};
+
+use thiserror::Error;
+
+use crate::{ConfigurationBuilder, Source};
+
+/// A [`Source`] containing raw [JSON Lines](https://jsonlines.org) data, i.e. one JSON object per
+/// line.
+///
+/// Each line is merged over the ones before it, as if each were a separate [`JsonSource`], so later
+/// lines take precedence. Blank lines are ignored.
+///
+/// [`JsonSource`]: crate::JsonSource
+///
+/// # Examples
+///
+/// ```
+/// use confik::{Configuration, JsonLinesSource};
+///
+/// #[derive(Configuration)]
+/// struct Config {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let config = Config::builder()
+///     .override_with(JsonLinesSource::new(
+///         "{\"host\": \"localhost\", \"port\": 80}\n{\"port\": 8080}",
+///     ))
+///     .try_build()
+///     .unwrap();
+///
+/// assert_eq!(config.host, "localhost");
+/// assert_eq!(config.port, 8080);
+/// ```
+#[derive(Clone)]
+pub struct JsonLinesSource<'a> {
+    contents: Cow<'a, str>,
+    allow_secrets: bool,
+}
+
+impl<'a> JsonLinesSource<'a> {
+    /// Creates a [`Source`] containing raw JSON Lines data.
+    pub fn new(contents: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            contents: contents.into(),
+            allow_secrets: false,
+        }
+    }
+
+    /// Allows this source to contain secrets.
+    pub fn allow_secrets(mut self) -> Self {
+        self.allow_secrets = true;
+        self
+    }
+}
+
+/// A line of JSON Lines data could not be parsed.
+#[derive(Debug, Error)]
+#[error("Invalid JSON on line {line}")]
+struct JsonLineError {
+    /// The line number, counting from 1.
+    line: usize,
+
+    #[source]
+    source: serde_json::Error,
+}
+
+impl Source for JsonLinesSource<'_> {
+    fn allows_secrets(&self) -> bool {
+        self.allow_secrets
+    }
+
+    fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
+        let builder = self
+            .contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<T>(line).map_err(|source| JsonLineError {
+                    line: index + 1,
+                    source,
+                })
+            })
+            .try_fold(T::default(), |acc, builder| {
+                Ok::<_, JsonLineError>(builder?.merge(acc))
+            })?;
+
+        Ok(builder)
+    }
+}
+
+impl fmt::Debug for JsonLinesSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonLinesSource")
+            .field("allow_secrets", &self.allow_secrets)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let source = JsonLinesSource::new("{}");
+        assert!(!source.allows_secrets());
+    }
+
+    #[test]
+    fn clone() {
+        let source = JsonLinesSource::new("{}").allow_secrets();
+        assert!(source.allows_secrets());
+        assert!(source.clone().allow_secrets);
+    }
+}
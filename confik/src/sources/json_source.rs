@@ -1,19 +1,46 @@
-use std::{borrow::Cow, error::Error, fmt};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt,
+    str::{self, Utf8Error},
+};
 
 use crate::{ConfigurationBuilder, Source};
 
 /// A [`Source`] containing raw JSON data.
+///
+/// Empty (or whitespace-only) data is treated as providing no values, rather than as an error.
 #[derive(Clone)]
 pub struct JsonSource<'a> {
-    contents: Cow<'a, str>,
+    /// The data, or why the bytes it was given aren't valid UTF-8.
+    contents: Result<Cow<'a, str>, Utf8Error>,
     allow_secrets: bool,
 }
 
 impl<'a> JsonSource<'a> {
     /// Creates a [`Source`] containing raw JSON data.
     pub fn new(contents: impl Into<Cow<'a, str>>) -> Self {
+        Self::with_contents(Ok(contents.into()))
+    }
+
+    /// Creates a [`Source`] containing raw JSON data as bytes, e.g. as read from a socket.
+    ///
+    /// JSON must be valid UTF-8. If it's not, building fails with the [`Utf8Error`] as the
+    /// source's error, rather than a JSON parsing error.
+    pub fn from_bytes(contents: impl Into<Cow<'a, [u8]>>) -> Self {
+        let contents = match contents.into() {
+            Cow::Borrowed(contents) => str::from_utf8(contents).map(Cow::Borrowed),
+            Cow::Owned(contents) => String::from_utf8(contents)
+                .map(Cow::Owned)
+                .map_err(|err| err.utf8_error()),
+        };
+
+        Self::with_contents(contents)
+    }
+
+    fn with_contents(contents: Result<Cow<'a, str>, Utf8Error>) -> Self {
         Self {
-            contents: contents.into(),
+            contents,
             allow_secrets: false,
         }
     }
@@ -31,7 +58,13 @@ impl Source for JsonSource<'_> {
     }
 
     fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
-        Ok(serde_json::from_str(&self.contents)?)
+        let contents = self.contents.as_deref().map_err(Clone::clone)?;
+
+        if contents.trim().is_empty() {
+            return Ok(T::default());
+        }
+
+        Ok(serde_json::from_str(contents)?)
     }
 }
 
@@ -1,7 +1,37 @@
-use std::{error::Error, fmt::Debug};
+use std::{
+    cell::Cell,
+    error::Error,
+    fmt::{self, Debug},
+    io, iter,
+};
 
+use self::file_source::FileErrorKind;
 use crate::ConfigurationBuilder;
 
+/// The broad kind of a [`Source`], used to restrict where some data may come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SourceKind {
+    /// Environment variables, e.g. an [`EnvSource`](crate::EnvSource).
+    Env,
+
+    /// Files, e.g. a [`FileSource`](crate::FileSource).
+    File,
+
+    /// Anything else, e.g. configuration given as a string.
+    Other,
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Env => f.write_str("environment"),
+            Self::File => f.write_str("file"),
+            Self::Other => f.write_str("other"),
+        }
+    }
+}
+
 /// A source of configuration data.
 pub trait Source: Debug {
     /// Whether this source is allowed to contain secret data.
@@ -12,13 +42,128 @@ pub trait Source: Debug {
         false
     }
 
+    /// The kind of this source.
+    ///
+    /// Fields restricted with `#[confik(source_kinds(...))]` are only accepted from sources of the
+    /// listed kinds. Defaults to [`SourceKind::Other`].
+    fn kind(&self) -> SourceKind {
+        SourceKind::Other
+    }
+
+    /// Whether this source may be missing, e.g. a file which doesn't have to exist.
+    ///
+    /// An optional source whose [`provide`](Self::provide) fails because its data wasn't found,
+    /// i.e. with an [`io::ErrorKind::NotFound`] error, is skipped, as if it had no data. Any other
+    /// error, such as invalid data, still fails the build. Sources which can tell they have no data
+    /// without failing can instead return `None` from
+    /// [`provide_optional`](Self::provide_optional). Defaults to `false`.
+    fn is_optional(&self) -> bool {
+        false
+    }
+
     /// Attempts to provide a partial configuration object from this source.
     fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>>;
+
+    /// Attempts to provide a partial configuration object from this source, or `None` if it has no
+    /// data, in which case the source is skipped.
+    ///
+    /// Sources which may legitimately be absent, e.g. an optional file, can implement this instead
+    /// of returning an error from [`provide`](Self::provide). Defaults to always providing.
+    fn provide_optional<T: ConfigurationBuilder>(
+        &self,
+    ) -> Result<Option<T>, Box<dyn Error + Sync + Send>> {
+        self.provide().map(Some)
+    }
+
+    /// Attempts to provide a partial configuration object from this source, with the parts which
+    /// are allowed to contain secrets removed.
+    ///
+    /// When [`allows_secrets`](Self::allows_secrets) is `false`, this is what gets checked for
+    /// secrets, if provided. Returns `None` by default, meaning the whole source is checked.
+    fn provide_secret_free<T: ConfigurationBuilder>(
+        &self,
+    ) -> Option<Result<T, Box<dyn Error + Sync + Send>>> {
+        None
+    }
+}
+
+/// Which of a [`Source`]'s methods to provide a builder with, see
+/// `ConfigurationBuilder::__provide`.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum __Provide {
+    /// [`Source::provide`].
+    Provide,
+
+    /// [`Source::provide_optional`].
+    Optional,
+
+    /// [`Source::provide_secret_free`].
+    SecretFree,
+}
+
+/// A builder provided with one of [`__Provide`]'s methods. This is `None` if the source doesn't
+/// provide secret-free data, and `Ok(None)` if it had no data.
+#[doc(hidden)]
+pub type __Provided<T> = Option<Result<Option<T>, Box<dyn Error + Sync + Send>>>;
+
+impl __Provide {
+    /// Provides `T` from `source` with the chosen method.
+    pub fn provide<S: Source, T: ConfigurationBuilder>(self, source: &S) -> __Provided<T> {
+        match self {
+            Self::Provide => Some(source.provide().map(Some)),
+            Self::Optional => Some(source.provide_optional()),
+            Self::SecretFree => source
+                .provide_secret_free()
+                .map(|provided| provided.map(Some)),
+        }
+    }
+}
+
+/// Provides both a builder and the builder of a previous version of the configuration from the same
+/// source, as used by `#[confik(from_builder(...))]`.
+///
+/// Each is provided from the source directly, as not every source's data can be buffered to be
+/// deserialized twice. If the data only fits one of the two, the other is left empty, and if it fits
+/// neither then the error for `B` is returned.
+#[doc(hidden)]
+pub fn __provide_with_previous<S, B, P>(source: &S, provide: __Provide) -> __Provided<(B, P)>
+where
+    S: Source,
+    B: ConfigurationBuilder,
+    P: ConfigurationBuilder,
+{
+    let current = B::__provide(source, provide)?;
+    let previous = P::__provide(source, provide)?;
+
+    Some(match (current, previous) {
+        (Ok(None), Ok(None)) => Ok(None),
+        (Ok(current), previous) => Ok(Some((
+            current.unwrap_or_default(),
+            previous.ok().flatten().unwrap_or_default(),
+        ))),
+        (Err(_), Ok(previous)) => Ok(Some((B::default(), previous.unwrap_or_default()))),
+        (Err(err), Err(_)) => Err(err),
+    })
 }
 
 pub(crate) trait DynSource<T>: Debug {
     fn allows_secrets(&self) -> bool;
+    fn kind(&self) -> SourceKind;
     fn provide(&self) -> Result<T, Box<dyn Error + Sync + Send>>;
+    fn provide_optional(&self) -> Result<Option<T>, Box<dyn Error + Sync + Send>>;
+    fn provide_secret_free(&self) -> Option<Result<T, Box<dyn Error + Sync + Send>>>;
+
+    /// Whether fields restricted with `#[confik(source_kinds(...))]` are checked against
+    /// [`kind`](Self::kind). Only sources of already-built values skip this.
+    fn checks_source_kind(&self) -> bool {
+        true
+    }
+
+    /// See [`Source::is_optional`]. Only user-provided sources may be optional.
+    fn is_optional(&self) -> bool {
+        false
+    }
 }
 
 impl<S, T> DynSource<T> for S
@@ -30,8 +175,27 @@ where
         <S as Source>::allows_secrets(self)
     }
 
+    fn kind(&self) -> SourceKind {
+        <S as Source>::kind(self)
+    }
+
+    fn is_optional(&self) -> bool {
+        <S as Source>::is_optional(self)
+    }
+
     fn provide(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
-        <S as Source>::provide(self)
+        T::__provide(self, __Provide::Provide)
+            .expect("always provided")
+            .map(Option::unwrap_or_default)
+    }
+
+    fn provide_optional(&self) -> Result<Option<T>, Box<dyn Error + Sync + Send>> {
+        T::__provide(self, __Provide::Optional).expect("always provided")
+    }
+
+    fn provide_secret_free(&self) -> Option<Result<T, Box<dyn Error + Sync + Send>>> {
+        T::__provide(self, __Provide::SecretFree)
+            .map(|provided| provided.map(Option::unwrap_or_default))
     }
 }
 
@@ -46,18 +210,96 @@ where
         true
     }
 
+    fn kind(&self) -> SourceKind {
+        SourceKind::Other
+    }
+
     fn provide(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
         Ok(T::default())
     }
+
+    fn provide_optional(&self) -> Result<Option<T>, Box<dyn Error + Sync + Send>> {
+        self.provide().map(Some)
+    }
+
+    fn provide_secret_free(&self) -> Option<Result<T, Box<dyn Error + Sync + Send>>> {
+        None
+    }
+}
+
+/// Provides an already-built builder, once.
+pub(crate) struct BuilderSource<T>(Cell<Option<T>>);
+
+impl<T> BuilderSource<T> {
+    pub(crate) fn new(builder: T) -> Self {
+        Self(Cell::new(Some(builder)))
+    }
+}
+
+impl<T> Debug for BuilderSource<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuilderSource").finish_non_exhaustive()
+    }
+}
+
+impl<T> DynSource<T> for BuilderSource<T>
+where
+    T: ConfigurationBuilder,
+{
+    /// The builder is constructed in code, rather than read from a file or the environment, so
+    /// may contain secrets.
+    fn allows_secrets(&self) -> bool {
+        true
+    }
+
+    fn kind(&self) -> SourceKind {
+        SourceKind::Other
+    }
+
+    fn provide(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
+        Ok(self.0.take().unwrap_or_default())
+    }
+
+    fn provide_optional(&self) -> Result<Option<T>, Box<dyn Error + Sync + Send>> {
+        self.provide().map(Some)
+    }
+
+    fn provide_secret_free(&self) -> Option<Result<T, Box<dyn Error + Sync + Send>>> {
+        None
+    }
+}
+
+/// Whether `err`, or any error it was caused by, is due to a file or other data not being found,
+/// for skipping [optional](Source::is_optional) sources.
+pub(crate) fn is_not_found(err: &(dyn Error + 'static)) -> bool {
+    iter::successors(Some(err), |&err| err.source()).any(|err| {
+        let io_err = match err.downcast_ref::<FileErrorKind>() {
+            Some(FileErrorKind::CouldNotReadFile(io_err)) => Some(io_err),
+            _ => err.downcast_ref::<io::Error>(),
+        };
+
+        io_err.map_or(false, |io_err| io_err.kind() == io::ErrorKind::NotFound)
+    })
 }
 
 pub(crate) mod file_source;
 
+pub(crate) mod optional_source;
+
 #[cfg(feature = "toml")]
 pub(crate) mod toml_source;
 
 #[cfg(feature = "json")]
 pub(crate) mod json_source;
 
+#[cfg(feature = "json")]
+pub(crate) mod json_lines_source;
+
 #[cfg(feature = "env")]
 pub(crate) mod env_source;
+
+#[cfg(feature = "include_dir")]
+pub(crate) mod include_dir_source;
+
+#[cfg(all(windows, feature = "winreg"))]
+pub(crate) mod registry_source;
@@ -0,0 +1,78 @@
+use std::error::Error;
+
+use crate::{ConfigurationBuilder, Source, SourceKind};
+
+/// A [`Source`] which is skipped, rather than failing the build, if the wrapped source can't
+/// provide its data.
+///
+/// E.g., a [`FileSource`](crate::FileSource) for a file which may not exist. Any error is
+/// discarded, so the wrapped source may also be skipped due to invalid data. The error is logged,
+/// at the `warn` level, when the `tracing` feature is enabled.
+///
+/// ```
+/// use confik::{Configuration, FileSource, OptionalSource};
+///
+/// #[derive(Configuration)]
+/// struct Config {
+///     #[confik(default = 8080u16)]
+///     port: u16,
+/// }
+///
+/// let config = Config::builder()
+///     .override_with(OptionalSource::new(FileSource::new("does-not-exist.toml")))
+///     .try_build()
+///     .unwrap();
+///
+/// assert_eq!(config.port, 8080);
+/// ```
+#[derive(Debug, Clone)]
+pub struct OptionalSource<S> {
+    inner: S,
+}
+
+impl<S: Source> OptionalSource<S> {
+    /// Wraps `inner`, skipping it if it fails to provide its data.
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S: Source> Source for OptionalSource<S> {
+    fn allows_secrets(&self) -> bool {
+        self.inner.allows_secrets()
+    }
+
+    fn kind(&self) -> SourceKind {
+        self.inner.kind()
+    }
+
+    fn is_optional(&self) -> bool {
+        true
+    }
+
+    fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
+        Ok(self.provide_optional()?.unwrap_or_default())
+    }
+
+    fn provide_optional<T: ConfigurationBuilder>(
+        &self,
+    ) -> Result<Option<T>, Box<dyn Error + Sync + Send>> {
+        match self.inner.provide_optional() {
+            Ok(builder) => Ok(builder),
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(source = ?self.inner, error = %_err, "skipping configuration source");
+
+                Ok(None)
+            }
+        }
+    }
+
+    fn provide_secret_free<T: ConfigurationBuilder>(
+        &self,
+    ) -> Option<Result<T, Box<dyn Error + Sync + Send>>> {
+        // Only called once the data has been provided, so a failure here can't be skipped, as the
+        // data would then go unchecked for secrets.
+        self.inner.provide_secret_free()
+    }
+}
@@ -0,0 +1,148 @@
+use std::{error::Error, fmt, io};
+
+use serde::de::{value::Error as ValueError, IntoDeserializer as _};
+use thiserror::Error;
+use winreg::{enums::RegType, types::FromRegValue, RegKey, RegValue, HKEY};
+
+use crate::{
+    tagged::{Content, ContentDeserializer},
+    ConfigurationBuilder, Source,
+};
+
+/// A [`Source`] referring to a key in the Windows Registry.
+///
+/// Each value under the key provides the field of the same name, and each subkey provides a nested
+/// structure. String values (`REG_SZ` and `REG_EXPAND_SZ`) are read as strings, `REG_MULTI_SZ` as
+/// lists of strings, and `REG_DWORD` and `REG_QWORD` as integers. Any other type of value fails the
+/// build. Environment variables in `REG_EXPAND_SZ` values are not expanded.
+///
+/// Only available on Windows, with the `winreg` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use confik::{Configuration, RegistrySource};
+/// use winreg::enums::HKEY_LOCAL_MACHINE;
+///
+/// #[derive(Configuration)]
+/// struct Config {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let config = Config::builder()
+///     .override_with(RegistrySource::new(HKEY_LOCAL_MACHINE, r"SOFTWARE\MyService"))
+///     .try_build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct RegistrySource {
+    hive: HKEY,
+    subkey: String,
+    allow_secrets: bool,
+}
+
+impl RegistrySource {
+    /// Creates a [`Source`] reading the values under `subkey` of the predefined key `hive`, e.g.
+    /// [`HKEY_LOCAL_MACHINE`](winreg::enums::HKEY_LOCAL_MACHINE).
+    pub fn new(hive: HKEY, subkey: impl Into<String>) -> Self {
+        Self {
+            hive,
+            subkey: subkey.into(),
+            allow_secrets: false,
+        }
+    }
+
+    /// Allows this source to contain secrets.
+    pub fn allow_secrets(mut self) -> Self {
+        self.allow_secrets = true;
+        self
+    }
+}
+
+/// A registry value has a type which can't be used for configuration.
+#[derive(Debug, Error)]
+#[error("Unsupported type {vtype:?} of registry value `{name}`")]
+struct UnsupportedValueType {
+    name: String,
+    vtype: RegType,
+}
+
+/// Reads `key`'s values and, recursively, its subkeys into a map.
+fn read_key(key: &RegKey) -> Result<Content, Box<dyn Error + Sync + Send>> {
+    let mut entries = Vec::new();
+
+    for value in key.enum_values() {
+        let (name, value) = value?;
+        let content = read_value(&value).ok_or_else(|| UnsupportedValueType {
+            name: name.clone(),
+            vtype: value.vtype.clone(),
+        })??;
+        entries.push((Content::String(name), content));
+    }
+
+    for name in key.enum_keys() {
+        let name = name?;
+        let content = read_key(&key.open_subkey(&name)?)?;
+        entries.push((Content::String(name), content));
+    }
+
+    Ok(Content::Map(entries))
+}
+
+/// Reads a single value, or returns `None` if its type isn't supported.
+fn read_value(value: &RegValue) -> Option<io::Result<Content>> {
+    let content = match value.vtype {
+        RegType::REG_SZ | RegType::REG_EXPAND_SZ => {
+            String::from_reg_value(value).map(Content::String)
+        }
+        RegType::REG_MULTI_SZ => Vec::<String>::from_reg_value(value)
+            .map(|items| Content::Seq(items.into_iter().map(Content::String).collect())),
+        RegType::REG_DWORD => u32::from_reg_value(value).map(|n| Content::U64(n.into())),
+        RegType::REG_QWORD => u64::from_reg_value(value).map(Content::U64),
+        _ => return None,
+    };
+
+    Some(content)
+}
+
+impl Source for RegistrySource {
+    fn allows_secrets(&self) -> bool {
+        self.allow_secrets
+    }
+
+    fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
+        let key = RegKey::predef(self.hive).open_subkey(&self.subkey)?;
+        let deserializer: ContentDeserializer<ValueError> = read_key(&key)?.into_deserializer();
+        Ok(T::deserialize(deserializer)?)
+    }
+}
+
+impl fmt::Debug for RegistrySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegistrySource")
+            .field("subkey", &self.subkey)
+            .field("allow_secrets", &self.allow_secrets)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winreg::enums::HKEY_CURRENT_USER;
+
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let source = RegistrySource::new(HKEY_CURRENT_USER, "Software");
+        assert!(!source.allows_secrets());
+    }
+
+    #[test]
+    fn clone() {
+        let source = RegistrySource::new(HKEY_CURRENT_USER, "Software").allow_secrets();
+        assert!(source.allows_secrets());
+        assert!(source.clone().allow_secrets);
+    }
+}
@@ -2,23 +2,71 @@ use std::{
     borrow::Cow,
     error::Error,
     fmt::{Debug, Formatter},
+    str::{self, Utf8Error},
 };
 
+use toml::{Table, Value};
+
 use crate::{ConfigurationBuilder, Source};
 
 /// A [`Source`] containing raw TOML data.
+///
+/// Empty (or whitespace-only) data is treated as providing no values, rather than as an error.
 #[derive(Clone)]
 pub struct TomlSource<'a> {
-    contents: Cow<'a, str>,
+    /// The data, or why the bytes it was given aren't valid UTF-8.
+    contents: Result<Cow<'a, str>, Utf8Error>,
     allow_secrets: bool,
+    allow_secrets_under: Option<Cow<'a, str>>,
 }
 
 impl<'a> TomlSource<'a> {
     /// A [`Source`] containing raw TOML data.
     pub fn new(contents: impl Into<Cow<'a, str>>) -> Self {
+        Self::with_contents(Ok(contents.into()))
+    }
+
+    /// A [`Source`] containing raw TOML data as bytes, e.g. as read from a socket.
+    ///
+    /// TOML must be valid UTF-8. If it's not, building fails with the [`Utf8Error`] as the
+    /// source's error, rather than a TOML parsing error.
+    ///
+    /// ```
+    /// use confik::{Configuration, TomlSource};
+    ///
+    /// #[derive(Debug, Configuration)]
+    /// struct Config {
+    ///     port: u16,
+    /// }
+    ///
+    /// let config = Config::builder()
+    ///     .override_with(TomlSource::from_bytes(b"port = 8080".as_slice()))
+    ///     .try_build()
+    ///     .unwrap();
+    /// assert_eq!(config.port, 8080);
+    ///
+    /// let err = Config::builder()
+    ///     .override_with(TomlSource::from_bytes(b"port = \xff".as_slice()))
+    ///     .try_build()
+    ///     .unwrap_err();
+    /// assert!(std::error::Error::source(&err).unwrap().is::<std::str::Utf8Error>());
+    /// ```
+    pub fn from_bytes(contents: impl Into<Cow<'a, [u8]>>) -> Self {
+        let contents = match contents.into() {
+            Cow::Borrowed(contents) => str::from_utf8(contents).map(Cow::Borrowed),
+            Cow::Owned(contents) => String::from_utf8(contents)
+                .map(Cow::Owned)
+                .map_err(|err| err.utf8_error()),
+        };
+
+        Self::with_contents(contents)
+    }
+
+    fn with_contents(contents: Result<Cow<'a, str>, Utf8Error>) -> Self {
         Self {
-            contents: contents.into(),
+            contents,
             allow_secrets: false,
+            allow_secrets_under: None,
         }
     }
 
@@ -27,6 +75,42 @@ impl<'a> TomlSource<'a> {
         self.allow_secrets = true;
         self
     }
+
+    /// Allows this source to contain secrets, but only under the table at the given dotted path.
+    ///
+    /// Secrets found anywhere else will cause an [`UnexpectedSecret`](crate::UnexpectedSecret)
+    /// error, as if secrets weren't allowed at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use confik::{Configuration, TomlSource};
+    ///
+    /// #[derive(Debug, Configuration)]
+    /// struct Credentials {
+    ///     #[confik(secret)]
+    ///     password: String,
+    /// }
+    ///
+    /// #[derive(Debug, Configuration)]
+    /// struct Config {
+    ///     credentials: Credentials,
+    /// }
+    ///
+    /// let config = Config::builder()
+    ///     .override_with(
+    ///         TomlSource::new("[credentials]\npassword = \"hunter2\"")
+    ///             .allow_secrets_under("credentials"),
+    ///     )
+    ///     .try_build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.credentials.password, "hunter2");
+    /// ```
+    pub fn allow_secrets_under(mut self, path: impl Into<Cow<'a, str>>) -> Self {
+        self.allow_secrets_under = Some(path.into());
+        self
+    }
 }
 
 impl Source for TomlSource<'_> {
@@ -35,7 +119,47 @@ impl Source for TomlSource<'_> {
     }
 
     fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
-        Ok(toml::from_str(&self.contents)?)
+        let contents = self.contents.as_deref().map_err(Clone::clone)?;
+
+        if contents.trim().is_empty() {
+            return Ok(T::default());
+        }
+
+        Ok(toml::from_str(contents)?)
+    }
+
+    fn provide_secret_free<T: ConfigurationBuilder>(
+        &self,
+    ) -> Option<Result<T, Box<dyn Error + Sync + Send>>> {
+        let path = self.allow_secrets_under.as_deref()?;
+
+        // Nothing to remove, so let the (empty or invalid) provided data be checked.
+        let contents = self.contents.as_deref().ok()?;
+        if contents.trim().is_empty() {
+            return None;
+        }
+
+        let provide = || {
+            let mut table = toml::from_str::<Table>(contents)?;
+            remove_path(&mut table, path);
+            Ok(Value::Table(table).try_into()?)
+        };
+
+        Some(provide())
+    }
+}
+
+/// Removes the value at the given dotted `path`, if present.
+fn remove_path(table: &mut Table, path: &str) {
+    match path.split_once('.') {
+        None => {
+            table.remove(path);
+        }
+        Some((segment, rest)) => {
+            if let Some(Value::Table(table)) = table.get_mut(segment) {
+                remove_path(table, rest);
+            }
+        }
     }
 }
 
@@ -43,6 +167,7 @@ impl Debug for TomlSource<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TomlSource")
             .field("allow_secrets", &self.allow_secrets)
+            .field("allow_secrets_under", &self.allow_secrets_under)
             .finish_non_exhaustive()
     }
 }
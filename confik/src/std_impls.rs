@@ -1,19 +1,27 @@
 //! Implementations of [`Configuration`](crate::Configuration) for standard library types.
 
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ffi::OsString,
-    fmt::Display,
+    fmt::{self, Display},
     hash::{BuildHasher, Hash},
     marker::PhantomData,
+    mem,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
     path::PathBuf,
     time::{Duration, SystemTime},
 };
 
-use serde::{de::DeserializeOwned, Deserialize};
+use serde::{
+    de::{self, DeserializeOwned, IgnoredAny, MapAccess, Visitor},
+    Deserialize,
+};
 
-use crate::{Configuration, ConfigurationBuilder, Error, MissingValue, UnexpectedSecret};
+use crate::{
+    map_key::MapKey, Configuration, ConfigurationBuilder, Conflict, Error, MissingValue,
+    SourceKind, ToBuilder, UnexpectedSecret, UnexpectedSource,
+};
 
 /// Convenience macro for the large number of foreign library types to implement the
 /// [`Configuration`] using an [`Option`] as their [`ConfigurationBuilder`].
@@ -22,6 +30,12 @@ macro_rules! impl_multi_source_via_option {
         impl Configuration for $type {
             type Builder = Option<Self>;
         }
+
+        impl ToBuilder for $type {
+            fn to_builder(&self) -> Self::Builder {
+                Some(self.clone())
+            }
+        }
     };
 
     ($($type:ty),* $(,)?) => {
@@ -89,7 +103,7 @@ impl<Container, Target> From<Container> for UnkeyedContainerBuilder<Container, T
 impl<Container, Target> ConfigurationBuilder for UnkeyedContainerBuilder<Container, Target>
 where
     Self: DeserializeOwned,
-    Container: IntoIterator + 'static,
+    Container: Default + IntoIterator + FromIterator<ItemOf<Container>> + 'static,
     ItemOf<Container>: ConfigurationBuilder,
     Target: Default + FromIterator<TargetOf<ItemOf<Container>>>,
     for<'a> &'a Container: IntoIterator<Item = &'a ItemOf<Container>>,
@@ -133,6 +147,47 @@ where
             Self::_PhantomData(_) => unreachable!("PhantomData is never instantiated"),
         }
     }
+
+    fn clear_env_ignored(&mut self) {
+        if let Self::Some(val) = self {
+            // Items can't be borrowed mutably from all containers (e.g., sets), so rebuild it.
+            *val = mem::take(val)
+                .into_iter()
+                .map(|mut item| {
+                    item.clear_env_ignored();
+                    item
+                })
+                .collect();
+        }
+    }
+
+    fn check_env_only(&self) -> Result<(), UnexpectedSecret> {
+        match self {
+            Self::Some(val) => val.into_iter().enumerate().try_for_each(|(index, item)| {
+                item.check_env_only()
+                    .map_err(|err| err.prepend(index.to_string()))
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_source_kind(&self, kind: SourceKind) -> Result<(), UnexpectedSource> {
+        match self {
+            Self::Some(val) => val.into_iter().enumerate().try_for_each(|(index, item)| {
+                item.check_source_kind(kind)
+                    .map_err(|err| err.prepend(index.to_string()))
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        // Containers are replaced whole, rather than merged, so any two values conflict.
+        match (self, other) {
+            (Self::Some(_), Self::Some(_)) => Err(Conflict::default()),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl<T> Configuration for Vec<T>
@@ -141,6 +196,20 @@ where
     BuilderOf<T>: 'static,
 {
     type Builder = UnkeyedContainerBuilder<Vec<BuilderOf<T>>, Self>;
+
+    fn secret_paths() -> Vec<String> {
+        crate::__prefix_paths("*", T::secret_paths()).collect()
+    }
+}
+
+impl<T> ToBuilder for Vec<T>
+where
+    T: ToBuilder,
+    BuilderOf<T>: 'static,
+{
+    fn to_builder(&self) -> Self::Builder {
+        UnkeyedContainerBuilder::Some(self.iter().map(T::to_builder).collect())
+    }
 }
 
 impl<T> Configuration for BTreeSet<T>
@@ -149,6 +218,20 @@ where
     BuilderOf<T>: Ord + 'static,
 {
     type Builder = UnkeyedContainerBuilder<BTreeSet<BuilderOf<T>>, Self>;
+
+    fn secret_paths() -> Vec<String> {
+        crate::__prefix_paths("*", T::secret_paths()).collect()
+    }
+}
+
+impl<T> ToBuilder for BTreeSet<T>
+where
+    T: ToBuilder + Ord,
+    BuilderOf<T>: Ord + 'static,
+{
+    fn to_builder(&self) -> Self::Builder {
+        UnkeyedContainerBuilder::Some(self.iter().map(T::to_builder).collect())
+    }
 }
 
 impl<T, S> Configuration for HashSet<T, S>
@@ -158,6 +241,285 @@ where
     S: BuildHasher + Default + 'static,
 {
     type Builder = UnkeyedContainerBuilder<HashSet<BuilderOf<T>, S>, Self>;
+
+    fn secret_paths() -> Vec<String> {
+        crate::__prefix_paths("*", T::secret_paths()).collect()
+    }
+}
+
+impl<T, S> ToBuilder for HashSet<T, S>
+where
+    T: ToBuilder + Eq + Hash,
+    BuilderOf<T>: Hash + Eq + 'static,
+    S: BuildHasher + Default + 'static,
+{
+    fn to_builder(&self) -> Self::Builder {
+        UnkeyedContainerBuilder::Some(self.iter().map(T::to_builder).collect())
+    }
+}
+
+/// Names the field which identifies items in a list using `#[confik(merge_key = "...")]`.
+///
+/// This is implemented by the derive macro.
+#[doc(hidden)]
+pub trait MergeKey {
+    /// The (serialized) name of the identifying field.
+    const NAME: &'static str;
+}
+
+/// Builder for a [`Vec`] annotated with `#[confik(merge_key = "...")]`.
+///
+/// Items from different sources which have the same value for the key field are merged, instead of
+/// the whole list being replaced by the higher priority source. Items without the key field are
+/// never merged.
+///
+/// Items are read using `#[serde(flatten)]` in order to find the key, so the same limitations apply.
+/// E.g., non-string values can't be read from a [`EnvSource`](crate::EnvSource).
+pub struct MergeByKeyBuilder<T: Configuration, Key> {
+    items: Option<Vec<(Option<String>, BuilderOf<T>)>>,
+    _key: PhantomData<fn() -> Key>,
+}
+
+impl<T: Configuration, Key> Default for MergeByKeyBuilder<T, Key> {
+    fn default() -> Self {
+        Self {
+            items: None,
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: Configuration, Key: MergeKey> Deserialize<'de> for MergeByKeyBuilder<T, Key> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items = Vec::<KeyedItem<BuilderOf<T>, Key>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|item| (item.key.value, item.builder))
+            .collect();
+
+        Ok(Self {
+            items: Some(items),
+            _key: PhantomData,
+        })
+    }
+}
+
+/// An item of a [`MergeByKeyBuilder`], alongside its key.
+#[derive(Deserialize)]
+#[serde(bound = "B: DeserializeOwned, Key: MergeKey")]
+struct KeyedItem<B, Key> {
+    // Reads the key without consuming it, so must come first.
+    #[serde(flatten)]
+    key: KeyProbe<Key>,
+
+    #[serde(flatten)]
+    builder: B,
+}
+
+/// Reads the value of the [`MergeKey`] field, as a string, ignoring all other fields.
+struct KeyProbe<Key> {
+    value: Option<String>,
+    _key: PhantomData<fn() -> Key>,
+}
+
+impl<'de, Key: MergeKey> Deserialize<'de> for KeyProbe<Key> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyProbeVisitor<Key>(PhantomData<fn() -> Key>);
+
+        impl<'de, Key: MergeKey> Visitor<'de> for KeyProbeVisitor<Key> {
+            type Value = KeyProbe<Key>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a map, optionally containing `{}`", Key::NAME)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut value = None;
+
+                while let Some(key) = map.next_key::<Cow<'de, str>>()? {
+                    if key == Key::NAME {
+                        value = Some(map.next_value::<KeyValue>()?.0);
+                    } else {
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+
+                Ok(KeyProbe {
+                    value,
+                    _key: PhantomData,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(KeyProbeVisitor(PhantomData))
+    }
+}
+
+/// A key value, which may be any primitive, converted to a string for comparison.
+struct KeyValue(String);
+
+impl<'de> Deserialize<'de> for KeyValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyValueVisitor;
+
+        impl Visitor<'_> for KeyValueVisitor {
+            type Value = KeyValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string, number, or boolean")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(KeyValue(v.to_owned()))
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(KeyValue(v.to_string()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(KeyValue(v.to_string()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(KeyValue(v.to_string()))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(KeyValue(v.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(KeyValueVisitor)
+    }
+}
+
+impl<T, Key> ConfigurationBuilder for MergeByKeyBuilder<T, Key>
+where
+    T: Configuration,
+    Key: MergeKey,
+{
+    type Target = Vec<T>;
+
+    fn merge(self, other: Self) -> Self {
+        let (mut us, other) = match (self.items, other.items) {
+            (Some(us), Some(other)) => (us, other),
+            (us, other) => {
+                return Self {
+                    items: us.or(other),
+                    _key: PhantomData,
+                }
+            }
+        };
+
+        // Keep the lower priority ordering, merging in and then appending our items.
+        let mut items = other
+            .into_iter()
+            .map(|(key, other)| {
+                let matching = key.as_ref().and_then(|key| {
+                    us.iter()
+                        .position(|(us_key, _)| us_key.as_ref() == Some(key))
+                });
+
+                match matching {
+                    Some(index) => {
+                        let (_, us) = us.remove(index);
+                        (key, us.merge(other))
+                    }
+                    None => (key, other),
+                }
+            })
+            .collect::<Vec<_>>();
+        items.append(&mut us);
+
+        Self {
+            items: Some(items),
+            _key: PhantomData,
+        }
+    }
+
+    fn try_build(self) -> Result<Self::Target, Error> {
+        self.items
+            .ok_or_else(|| Error::MissingValue(MissingValue::default()))?
+            .into_iter()
+            .enumerate()
+            .map(|(index, (_, builder))| {
+                builder
+                    .try_build()
+                    .map_err(|err| err.prepend(index.to_string()))
+            })
+            .collect()
+    }
+
+    fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
+        match &self.items {
+            None => Ok(false),
+
+            // An explicit empty list is counted as data, as with other containers.
+            Some(items) => items
+                .iter()
+                .enumerate()
+                .find_map(|(index, (_, builder))| {
+                    builder
+                        .contains_non_secret_data()
+                        .map_err(|err| err.prepend(index.to_string()))
+                        .err()
+                })
+                .map_or(Ok(true), Err),
+        }
+    }
+
+    fn clear_env_ignored(&mut self) {
+        for (_, builder) in self.items.iter_mut().flatten() {
+            builder.clear_env_ignored();
+        }
+    }
+
+    fn check_env_only(&self) -> Result<(), UnexpectedSecret> {
+        self.items
+            .iter()
+            .flatten()
+            .enumerate()
+            .try_for_each(|(index, (_, builder))| {
+                builder
+                    .check_env_only()
+                    .map_err(|err| err.prepend(index.to_string()))
+            })
+    }
+
+    fn check_source_kind(&self, kind: SourceKind) -> Result<(), UnexpectedSource> {
+        self.items
+            .iter()
+            .flatten()
+            .enumerate()
+            .try_for_each(|(index, (_, builder))| {
+                builder
+                    .check_source_kind(kind)
+                    .map_err(|err| err.prepend(index.to_string()))
+            })
+    }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        let (Some(us), Some(other)) = (&self.items, &other.items) else {
+            return Ok(());
+        };
+
+        // Only items with matching keys are merged, the rest are kept alongside each other.
+        us.iter().try_for_each(|(key, builder)| {
+            let Some(key) = key else {
+                return Ok(());
+            };
+
+            match other
+                .iter()
+                .find(|(other_key, _)| other_key.as_ref() == Some(key))
+            {
+                Some((_, other)) => builder
+                    .check_conflicts(other)
+                    .map_err(|err| err.prepend(key.clone())),
+                None => Ok(()),
+            }
+        })
+    }
 }
 
 /// Trait governing access to keyed containers
@@ -167,11 +529,14 @@ trait KeyedContainer {
 
     fn insert(&mut self, k: Self::Key, v: Self::Value);
     fn remove(&mut self, k: &Self::Key) -> Option<Self::Value>;
+    fn get(&self, k: &Self::Key) -> Option<&Self::Value>;
 }
 
 /// Builder type for keyed containers, such as [`HashMap`] (as opposed to unkeyed containers like [`Vec`]).
-#[derive(Debug, Default, Deserialize, Hash, PartialEq, PartialOrd, Eq, Ord)]
-#[serde(from = "Container")]
+///
+/// Keys which are primitives, such as integers, may also be given as strings, for formats such as
+/// TOML which only support string keys.
+#[derive(Debug, Default, Hash, PartialEq, PartialOrd, Eq, Ord)]
 pub enum KeyedContainerBuilder<Container, Target> {
     /// No data has been provided yet.
     ///
@@ -194,11 +559,50 @@ impl<Container, Target> From<Container> for KeyedContainerBuilder<Container, Tar
     }
 }
 
+impl<'de, Container, Target> Deserialize<'de> for KeyedContainerBuilder<Container, Target>
+where
+    Container: KeyedContainer + Default,
+    KeyOf<Container>: Deserialize<'de>,
+    ValueOf<Container>: Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ContainerVisitor<Container, Target>(PhantomData<fn() -> (Container, Target)>);
+
+        impl<'de, Container, Target> Visitor<'de> for ContainerVisitor<Container, Target>
+        where
+            Container: KeyedContainer + Default,
+            KeyOf<Container>: Deserialize<'de>,
+            ValueOf<Container>: Deserialize<'de>,
+        {
+            type Value = KeyedContainerBuilder<Container, Target>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut container = Container::default();
+
+                while let Some(key) = map.next_key_seed(MapKey::new())? {
+                    container.insert(key, map.next_value()?);
+                }
+
+                Ok(KeyedContainerBuilder::Some(container))
+            }
+        }
+
+        deserializer.deserialize_map(ContainerVisitor(PhantomData))
+    }
+}
+
 impl<Container, Target> ConfigurationBuilder for KeyedContainerBuilder<Container, Target>
 where
     Self: DeserializeOwned,
-    Container:
-        KeyedContainer + IntoIterator<Item = (KeyOf<Container>, ValueOf<Container>)> + 'static,
+    Container: KeyedContainer
+        + Default
+        + IntoIterator<Item = (KeyOf<Container>, ValueOf<Container>)>
+        + FromIterator<(KeyOf<Container>, ValueOf<Container>)>
+        + 'static,
     KeyOf<Container>: Display,
     ValueOf<Container>: ConfigurationBuilder + 'static,
     Target: Default + FromIterator<(KeyOf<Container>, TargetOf<ValueOf<Container>>)>,
@@ -234,7 +638,12 @@ where
             Self::Unspecified => Err(Error::MissingValue(MissingValue::default())),
             Self::Some(val) => val
                 .into_iter()
-                .map(|(key, value)| Ok((key, value.try_build()?)))
+                .map(|(key, value)| {
+                    let value = value
+                        .try_build()
+                        .map_err(|err| err.prepend(key.to_string()))?;
+                    Ok((key, value))
+                })
                 .collect(),
             Self::_PhantomData(_) => unreachable!("PhantomData is never instantiated"),
         }
@@ -257,6 +666,56 @@ where
             Self::_PhantomData(_) => unreachable!("PhantomData is never instantiated"),
         }
     }
+
+    fn clear_env_ignored(&mut self) {
+        if let Self::Some(val) = self {
+            *val = mem::take(val)
+                .into_iter()
+                .map(|(key, mut value)| {
+                    value.clear_env_ignored();
+                    (key, value)
+                })
+                .collect();
+        }
+    }
+
+    fn check_env_only(&self) -> Result<(), UnexpectedSecret> {
+        match self {
+            Self::Some(val) => val.into_iter().try_for_each(|(key, value)| {
+                value
+                    .check_env_only()
+                    .map_err(|err| err.prepend(key.to_string()))
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_source_kind(&self, kind: SourceKind) -> Result<(), UnexpectedSource> {
+        match self {
+            Self::Some(val) => val.into_iter().try_for_each(|(key, value)| {
+                value
+                    .check_source_kind(kind)
+                    .map_err(|err| err.prepend(key.to_string()))
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        match (self, other) {
+            // Values are merged by key, so only values with the same key can conflict.
+            (Self::Some(us), Self::Some(other)) => {
+                us.into_iter()
+                    .try_for_each(|(key, value)| match other.get(key) {
+                        Some(other) => value
+                            .check_conflicts(other)
+                            .map_err(|err| err.prepend(key.to_string())),
+                        None => Ok(()),
+                    })
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 impl<K, V> KeyedContainer for BTreeMap<K, V>
@@ -273,6 +732,10 @@ where
     fn remove(&mut self, k: &Self::Key) -> Option<Self::Value> {
         self.remove(k)
     }
+
+    fn get(&self, k: &Self::Key) -> Option<&Self::Value> {
+        self.get(k)
+    }
 }
 
 impl<K, V> Configuration for BTreeMap<K, V>
@@ -282,6 +745,25 @@ where
     BuilderOf<V>: 'static,
 {
     type Builder = KeyedContainerBuilder<BTreeMap<K, BuilderOf<V>>, Self>;
+
+    fn secret_paths() -> Vec<String> {
+        crate::__prefix_paths("*", V::secret_paths()).collect()
+    }
+}
+
+impl<K, V> ToBuilder for BTreeMap<K, V>
+where
+    K: Ord + Clone + Display + DeserializeOwned + 'static,
+    V: ToBuilder,
+    BuilderOf<V>: 'static,
+{
+    fn to_builder(&self) -> Self::Builder {
+        KeyedContainerBuilder::Some(
+            self.iter()
+                .map(|(key, value)| (key.clone(), value.to_builder()))
+                .collect(),
+        )
+    }
 }
 
 impl<K, V, S> KeyedContainer for HashMap<K, V, S>
@@ -299,6 +781,10 @@ where
     fn remove(&mut self, k: &Self::Key) -> Option<Self::Value> {
         self.remove(k)
     }
+
+    fn get(&self, k: &Self::Key) -> Option<&Self::Value> {
+        self.get(k)
+    }
 }
 
 impl<K, V, S> Configuration for HashMap<K, V, S>
@@ -309,6 +795,26 @@ where
     S: Default + BuildHasher + 'static,
 {
     type Builder = KeyedContainerBuilder<HashMap<K, BuilderOf<V>, S>, Self>;
+
+    fn secret_paths() -> Vec<String> {
+        crate::__prefix_paths("*", V::secret_paths()).collect()
+    }
+}
+
+impl<K, V, S> ToBuilder for HashMap<K, V, S>
+where
+    K: Hash + Eq + Clone + Display + DeserializeOwned + 'static,
+    V: ToBuilder,
+    BuilderOf<V>: 'static,
+    S: Default + BuildHasher + 'static,
+{
+    fn to_builder(&self) -> Self::Builder {
+        KeyedContainerBuilder::Some(
+            self.iter()
+                .map(|(key, value)| (key.clone(), value.to_builder()))
+                .collect(),
+        )
+    }
 }
 
 impl<T, const N: usize> Configuration for [T; N]
@@ -317,6 +823,20 @@ where
     T: Configuration,
 {
     type Builder = [BuilderOf<T>; N];
+
+    fn secret_paths() -> Vec<String> {
+        crate::__prefix_paths("*", T::secret_paths()).collect()
+    }
+}
+
+impl<T, const N: usize> ToBuilder for [T; N]
+where
+    [BuilderOf<T>; N]: DeserializeOwned + Default,
+    T: ToBuilder,
+{
+    fn to_builder(&self) -> Self::Builder {
+        std::array::from_fn(|index| self[index].to_builder())
+    }
 }
 
 impl<T, const N: usize> ConfigurationBuilder for [T; N]
@@ -355,6 +875,121 @@ where
                 Ok(val.map_err(|err| err.prepend(index.to_string()))? || has_secret)
             })
     }
+
+    fn clear_env_ignored(&mut self) {
+        for val in self {
+            val.clear_env_ignored();
+        }
+    }
+
+    fn check_env_only(&self) -> Result<(), UnexpectedSecret> {
+        self.iter().enumerate().try_for_each(|(index, val)| {
+            val.check_env_only()
+                .map_err(|err| err.prepend(index.to_string()))
+        })
+    }
+
+    fn check_source_kind(&self, kind: SourceKind) -> Result<(), UnexpectedSource> {
+        self.iter().enumerate().try_for_each(|(index, val)| {
+            val.check_source_kind(kind)
+                .map_err(|err| err.prepend(index.to_string()))
+        })
+    }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        self.iter()
+            .zip(other)
+            .enumerate()
+            .try_for_each(|(index, (us, other))| {
+                us.check_conflicts(other)
+                    .map_err(|err| err.prepend(index.to_string()))
+            })
+    }
+}
+
+/// Pairs are read from two item lists, e.g. `["key", "value"]`, so a `Vec<(K, V)>` can hold an
+/// association list, which keeps the order of its entries and any duplicate keys, unlike a map.
+impl<A, B> Configuration for (A, B)
+where
+    A: Configuration,
+    B: Configuration,
+{
+    type Builder = (BuilderOf<A>, BuilderOf<B>);
+
+    fn secret_paths() -> Vec<String> {
+        crate::__prefix_paths("0", A::secret_paths())
+            .chain(crate::__prefix_paths("1", B::secret_paths()))
+            .collect()
+    }
+}
+
+impl<A, B> ToBuilder for (A, B)
+where
+    A: ToBuilder,
+    B: ToBuilder,
+{
+    fn to_builder(&self) -> Self::Builder {
+        (self.0.to_builder(), self.1.to_builder())
+    }
+}
+
+impl<A, B> ConfigurationBuilder for (A, B)
+where
+    A: ConfigurationBuilder,
+    B: ConfigurationBuilder,
+{
+    type Target = (TargetOf<A>, TargetOf<B>);
+
+    fn merge(self, other: Self) -> Self {
+        (self.0.merge(other.0), self.1.merge(other.1))
+    }
+
+    fn try_build(self) -> Result<Self::Target, Error> {
+        Ok((
+            self.0.try_build().map_err(|err| err.prepend("0"))?,
+            self.1.try_build().map_err(|err| err.prepend("1"))?,
+        ))
+    }
+
+    fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
+        let first = self
+            .0
+            .contains_non_secret_data()
+            .map_err(|err| err.prepend("0"))?;
+        let second = self
+            .1
+            .contains_non_secret_data()
+            .map_err(|err| err.prepend("1"))?;
+        Ok(first || second)
+    }
+
+    fn clear_env_ignored(&mut self) {
+        self.0.clear_env_ignored();
+        self.1.clear_env_ignored();
+    }
+
+    fn check_env_only(&self) -> Result<(), UnexpectedSecret> {
+        self.0.check_env_only().map_err(|err| err.prepend("0"))?;
+        self.1.check_env_only().map_err(|err| err.prepend("1"))
+    }
+
+    fn check_source_kind(&self, kind: SourceKind) -> Result<(), UnexpectedSource> {
+        self.0
+            .check_source_kind(kind)
+            .map_err(|err| err.prepend("0"))?;
+        self.1
+            .check_source_kind(kind)
+            .map_err(|err| err.prepend("1"))
+    }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        self.0
+            .check_conflicts(&other.0)
+            .map_err(|err| err.prepend("0"))?;
+        self.1
+            .check_conflicts(&other.1)
+            .map_err(|err| err.prepend("1"))
+    }
 }
 
 /// `PhantomData` does not need a builder, however we cannot use `()` as that would make `T`
@@ -364,6 +999,12 @@ impl<T> Configuration for PhantomData<T> {
     type Builder = Self;
 }
 
+impl<T> ToBuilder for PhantomData<T> {
+    fn to_builder(&self) -> Self::Builder {
+        PhantomData
+    }
+}
+
 /// `PhantomData` does not need a builder, however we cannot use `()` as that would make `T`
 /// unconstrained. Instead just making it use itself as a builder and rely on serde handling it
 /// alright.
@@ -390,6 +1031,22 @@ where
     OptionBuilder<BuilderOf<T>>: DeserializeOwned,
 {
     type Builder = OptionBuilder<BuilderOf<T>>;
+
+    fn secret_paths() -> Vec<String> {
+        T::secret_paths()
+    }
+}
+
+impl<T: ToBuilder> ToBuilder for Option<T>
+where
+    OptionBuilder<BuilderOf<T>>: DeserializeOwned,
+{
+    fn to_builder(&self) -> Self::Builder {
+        match self {
+            Some(val) => OptionBuilder::Some(val.to_builder()),
+            None => OptionBuilder::None,
+        }
+    }
 }
 
 /// Build an `Option<T>` with a custom structure as we want `None` to be an explicit value that will
@@ -458,4 +1115,37 @@ where
             Self::Unspecified => Ok(false),
         }
     }
+
+    fn is_explicit_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    fn clear_env_ignored(&mut self) {
+        if let Self::Some(data) = self {
+            data.clear_env_ignored();
+        }
+    }
+
+    fn check_env_only(&self) -> Result<(), UnexpectedSecret> {
+        match self {
+            Self::Some(data) => data.check_env_only(),
+            Self::None | Self::Unspecified => Ok(()),
+        }
+    }
+
+    fn check_source_kind(&self, kind: SourceKind) -> Result<(), UnexpectedSource> {
+        match self {
+            Self::Some(data) => data.check_source_kind(kind),
+            Self::None | Self::Unspecified => Ok(()),
+        }
+    }
+
+    fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+        match (self, other) {
+            (Self::Some(us), Self::Some(other)) => us.check_conflicts(other),
+            (Self::Unspecified, _) | (_, Self::Unspecified) => Ok(()),
+            // An explicit `None` is a value, so conflicts with any other.
+            _ => Err(Conflict::default()),
+        }
+    }
 }
@@ -0,0 +1,285 @@
+//! Deserializing flattened tagged enums' builders, where a missing tag means no data was given.
+//!
+//! Serde requires the tag of an internally or adjacently tagged enum to be present, but when the
+//! enum is flattened into its parent it's given every key the parent didn't recognise, whether or
+//! not a source provides the enum. The data is buffered so the tag can be looked for before handing
+//! it to the builder's derived implementation.
+//!
+//! The same buffering lets data be deserialized as both a builder and the builder of a previous
+//! version of the configuration, for `#[confik(from_builder(...))]`, and lets an integer be told
+//! apart from a variant's name, for `#[confik(from_repr)]`.
+
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{
+        self,
+        value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer},
+        IntoDeserializer, MapAccess, SeqAccess, Unexpected, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+/// A buffered value from a self-describing format.
+#[derive(Debug, Clone)]
+pub enum Content {
+    Bool(bool),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<Content>),
+    Unit,
+    Seq(Vec<Content>),
+    Map(Vec<(Content, Content)>),
+}
+
+impl Content {
+    /// Whether this is a map without the given key.
+    fn is_map_without(&self, key: &str) -> bool {
+        match self {
+            Self::Map(entries) => !entries.iter().any(
+                |(entry_key, _)| matches!(entry_key, Self::String(entry_key) if entry_key == key),
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Content {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ContentVisitor;
+
+        impl<'de> Visitor<'de> for ContentVisitor {
+            type Value = Content;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("any value")
+            }
+
+            fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+                Ok(Content::Bool(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+                Ok(Content::I64(value))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(Content::U64(value))
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+                Ok(Content::F64(value))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(Content::String(value.to_owned()))
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+                Ok(Content::String(value))
+            }
+
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+                Ok(Content::Bytes(value.to_owned()))
+            }
+
+            fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(Content::Bytes(value))
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E> {
+                Ok(Content::None)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                Content::deserialize(deserializer).map(|content| Content::Some(Box::new(content)))
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(Content::Unit)
+            }
+
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                Content::deserialize(deserializer)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut items = Vec::new();
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(Content::Seq(items))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Content::Map(entries))
+            }
+        }
+
+        deserializer.deserialize_any(ContentVisitor)
+    }
+}
+
+/// Deserializes from buffered [`Content`].
+#[derive(Debug)]
+pub struct ContentDeserializer<E> {
+    content: Content,
+    error: PhantomData<E>,
+}
+
+impl<'de, E: de::Error> IntoDeserializer<'de, E> for Content {
+    type Deserializer = ContentDeserializer<E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        ContentDeserializer {
+            content: self,
+            error: PhantomData,
+        }
+    }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for ContentDeserializer<E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Bool(value) => visitor.visit_bool(value),
+            Content::U64(value) => visitor.visit_u64(value),
+            Content::I64(value) => visitor.visit_i64(value),
+            Content::F64(value) => visitor.visit_f64(value),
+            Content::String(value) => visitor.visit_string(value),
+            Content::Bytes(value) => visitor.visit_byte_buf(value),
+            Content::None => visitor.visit_none(),
+            Content::Some(content) => visitor.visit_some(content.into_deserializer()),
+            Content::Unit => visitor.visit_unit(),
+            Content::Seq(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Content::Map(entries) => {
+                let mut map = MapDeserializer::new(entries.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::None | Content::Unit => visitor.visit_none(),
+            Content::Some(content) => visitor.visit_some(content.into_deserializer()),
+            content => visitor.visit_some(content.into_deserializer()),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        match self.content {
+            Content::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            Content::Map(entries) => visitor.visit_enum(MapAccessDeserializer::new(
+                MapDeserializer::new(entries.into_iter()),
+            )),
+            content => content.into_deserializer().deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Deserializes a tagged enum's builder with `deserialize`, or as the default builder if given a
+/// map without the `tag` key.
+#[doc(hidden)]
+pub fn __deserialize_tagged<'de, D, T>(
+    deserializer: D,
+    tag: &str,
+    deserialize: impl FnOnce(ContentDeserializer<D::Error>) -> Result<T, D::Error>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default,
+{
+    let content = Content::deserialize(deserializer)?;
+
+    if content.is_map_without(tag) {
+        Ok(T::default())
+    } else {
+        deserialize(content.into_deserializer())
+    }
+}
+
+/// Deserializes a `#[confik(from_repr)]` enum's builder from an integer, by looking up the variant
+/// with that discriminant with `from_repr`, or from anything else with `deserialize`.
+#[doc(hidden)]
+pub fn __deserialize_from_repr<'de, D, T>(
+    deserializer: D,
+    from_repr: impl FnOnce(i128) -> Option<T>,
+    deserialize: impl FnOnce(ContentDeserializer<D::Error>) -> Result<T, D::Error>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let expected = &"the discriminant of a variant";
+
+    match Content::deserialize(deserializer)? {
+        Content::U64(value) => from_repr(value.into())
+            .ok_or_else(|| de::Error::invalid_value(Unexpected::Unsigned(value), expected)),
+        Content::I64(value) => from_repr(value.into())
+            .ok_or_else(|| de::Error::invalid_value(Unexpected::Signed(value), expected)),
+        content => deserialize(content.into_deserializer()),
+    }
+}
+
+/// Deserializes the same data as both a builder and the builder of a previous version of the
+/// configuration, as used by `#[confik(from_builder(...))]`. If the data only fits one of the two,
+/// the other is left empty, and if it fits neither then the error for `B` is returned.
+#[doc(hidden)]
+pub fn __deserialize_with_previous<'de, D, B, P>(deserializer: D) -> Result<(B, P), D::Error>
+where
+    D: Deserializer<'de>,
+    B: Deserialize<'de> + Default,
+    P: Deserialize<'de> + Default,
+{
+    let content = Content::deserialize(deserializer)?;
+    let previous = P::deserialize(IntoDeserializer::<D::Error>::into_deserializer(
+        content.clone(),
+    ));
+    let current = B::deserialize(IntoDeserializer::<D::Error>::into_deserializer(content));
+
+    match (current, previous) {
+        (Ok(current), previous) => Ok((current, previous.unwrap_or_default())),
+        (Err(_), Ok(previous)) => Ok((B::default(), previous)),
+        (Err(err), Err(_)) => Err(err),
+    }
+}
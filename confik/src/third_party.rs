@@ -1,11 +1,131 @@
 //! Implementations of [`Configuration`](crate::Configuration) for frequently used types from other
 //! crates.
 
+#[cfg(any(feature = "http", feature = "mime", feature = "regex"))]
+mod from_str {
+    use std::{error::Error as StdError, fmt, marker::PhantomData, str::FromStr};
+
+    use serde::Deserialize;
+
+    use crate::{
+        ConfigurationBuilder, Conflict, Error, FailedTryInto, MissingValue, UnexpectedSecret,
+    };
+
+    /// Builder for types which do not implement [`Deserialize`], but can be parsed from a string
+    /// using their [`FromStr`] implementation.
+    ///
+    /// The string is stored as-is and only parsed during [`try_build`][ConfigurationBuilder::try_build].
+    #[derive(Deserialize)]
+    #[serde(transparent)]
+    pub struct FromStrBuilder<T> {
+        value: Option<String>,
+
+        #[serde(skip)]
+        _target: PhantomData<fn() -> T>,
+    }
+
+    impl<T> Default for FromStrBuilder<T> {
+        fn default() -> Self {
+            Self {
+                value: None,
+                _target: PhantomData,
+            }
+        }
+    }
+
+    impl<T: fmt::Display> FromStrBuilder<T> {
+        /// Creates a builder containing the string form of `value`.
+        pub(super) fn from_value(value: &T) -> Self {
+            Self {
+                value: Some(value.to_string()),
+                _target: PhantomData,
+            }
+        }
+    }
+
+    impl<T> fmt::Debug for FromStrBuilder<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("FromStrBuilder").field(&self.value).finish()
+        }
+    }
+
+    impl<T> ConfigurationBuilder for FromStrBuilder<T>
+    where
+        T: FromStr,
+        T::Err: StdError + Send + Sync + 'static,
+    {
+        type Target = T;
+
+        fn merge(self, other: Self) -> Self {
+            Self {
+                value: self.value.or(other.value),
+                _target: PhantomData,
+            }
+        }
+
+        fn try_build(self) -> Result<Self::Target, Error> {
+            self.value
+                .ok_or_else(|| Error::MissingValue(MissingValue::default()))?
+                .parse()
+                .map_err(|err| Error::TryInto(FailedTryInto::new(err)))
+        }
+
+        fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
+            Ok(self.value.is_some())
+        }
+
+        fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+            if self.value.is_some() && other.value.is_some() {
+                Err(Conflict::default())
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec {
+    use arrayvec::ArrayVec;
+
+    use crate::{std_impls::UnkeyedContainerBuilder, Configuration, ToBuilder};
+
+    /// Sources providing more than `N` items fail to deserialize, raising an
+    /// [`Error::Source`](crate::Error::Source).
+    impl<T, const N: usize> Configuration for ArrayVec<T, N>
+    where
+        T: Configuration,
+        T::Builder: 'static,
+    {
+        type Builder = UnkeyedContainerBuilder<ArrayVec<T::Builder, N>, Self>;
+
+        fn secret_paths() -> Vec<String> {
+            crate::__prefix_paths("*", T::secret_paths()).collect()
+        }
+    }
+
+    impl<T, const N: usize> ToBuilder for ArrayVec<T, N>
+    where
+        T: ToBuilder,
+        T::Builder: 'static,
+    {
+        fn to_builder(&self) -> Self::Builder {
+            UnkeyedContainerBuilder::Some(self.iter().map(T::to_builder).collect())
+        }
+    }
+}
+
 #[cfg(feature = "bytesize")]
 mod bytesize {
     impl crate::Configuration for bytesize::ByteSize {
         type Builder = Option<Self>;
     }
+
+    impl crate::ToBuilder for bytesize::ByteSize {
+        fn to_builder(&self) -> Self::Builder {
+            Some(*self)
+        }
+    }
 }
 
 #[cfg(feature = "camino")]
@@ -13,14 +133,20 @@ mod camino {
     impl crate::Configuration for camino::Utf8PathBuf {
         type Builder = Option<Self>;
     }
+
+    impl crate::ToBuilder for camino::Utf8PathBuf {
+        fn to_builder(&self) -> Self::Builder {
+            Some(self.clone())
+        }
+    }
 }
 
 #[cfg(feature = "chrono")]
 mod chrono {
-    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+    use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone};
     use serde::de::DeserializeOwned;
 
-    use crate::Configuration;
+    use crate::{Configuration, ToBuilder};
 
     impl<T: TimeZone> Configuration for DateTime<T>
     where
@@ -29,18 +155,56 @@ mod chrono {
         type Builder = Option<Self>;
     }
 
+    impl<T: TimeZone> ToBuilder for DateTime<T>
+    where
+        Self: DeserializeOwned,
+    {
+        fn to_builder(&self) -> Self::Builder {
+            Some(self.clone())
+        }
+    }
+
     impl Configuration for NaiveTime {
         type Builder = Option<Self>;
     }
 
+    impl ToBuilder for NaiveTime {
+        fn to_builder(&self) -> Self::Builder {
+            Some(*self)
+        }
+    }
+
     impl Configuration for NaiveDate {
         type Builder = Option<Self>;
     }
 
+    impl ToBuilder for NaiveDate {
+        fn to_builder(&self) -> Self::Builder {
+            Some(*self)
+        }
+    }
+
     impl Configuration for NaiveDateTime {
         type Builder = Option<Self>;
     }
 
+    impl ToBuilder for NaiveDateTime {
+        fn to_builder(&self) -> Self::Builder {
+            Some(*self)
+        }
+    }
+
+    /// Also covers `chrono::Duration`, which is an alias of [`TimeDelta`].
+    impl Configuration for TimeDelta {
+        type Builder = Option<Self>;
+    }
+
+    impl ToBuilder for TimeDelta {
+        fn to_builder(&self) -> Self::Builder {
+            Some(*self)
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use crate::TomlSource;
@@ -94,6 +258,275 @@ mod chrono {
                 NaiveDate::from_ymd_opt(2013, 8, 9).unwrap()
             );
         }
+
+        #[test]
+        fn time_delta_format() {
+            use chrono::{Duration, TimeDelta};
+
+            use crate::Configuration;
+
+            #[derive(Configuration)]
+            struct Config {
+                delta: TimeDelta,
+                duration: Duration,
+            }
+
+            let toml = r#"
+                delta = [90, 500]
+                duration = [-60, 0]
+            "#;
+
+            let config = Config::builder()
+                .override_with(TomlSource::new(toml))
+                .try_build()
+                .unwrap();
+
+            assert_eq!(config.delta, TimeDelta::new(90, 500).unwrap());
+            assert_eq!(config.duration, Duration::minutes(-1));
+        }
+    }
+}
+
+#[cfg(feature = "compact_str")]
+mod compact_str {
+    use compact_str::CompactString;
+
+    use crate::{Configuration, ToBuilder};
+
+    impl Configuration for CompactString {
+        type Builder = Option<Self>;
+    }
+
+    impl ToBuilder for CompactString {
+        fn to_builder(&self) -> Self::Builder {
+            Some(self.clone())
+        }
+    }
+}
+
+#[cfg(feature = "enumflags2")]
+mod enumflags2 {
+    use std::fmt;
+
+    use enumflags2::{BitFlag, BitFlags};
+    use serde::{de::DeserializeOwned, Deserialize};
+
+    use crate::{
+        Configuration, ConfigurationBuilder, Conflict, Error, MissingValue, ToBuilder,
+        UnexpectedSecret,
+    };
+
+    /// Builder for [`BitFlags`], read from a list of flags, e.g. `["read", "write"]`.
+    ///
+    /// Unlike most builders, merging takes the union of the flags from each source.
+    #[derive(Deserialize)]
+    #[serde(from = "Vec<T>", bound = "T: DeserializeOwned")]
+    pub struct BitFlagsBuilder<T: BitFlag>(Option<BitFlags<T>>);
+
+    impl<T: BitFlag> From<Vec<T>> for BitFlagsBuilder<T> {
+        fn from(flags: Vec<T>) -> Self {
+            Self(Some(flags.into_iter().collect()))
+        }
+    }
+
+    impl<T: BitFlag> Default for BitFlagsBuilder<T> {
+        fn default() -> Self {
+            Self(None)
+        }
+    }
+
+    impl<T: BitFlag + fmt::Debug> fmt::Debug for BitFlagsBuilder<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("BitFlagsBuilder").field(&self.0).finish()
+        }
+    }
+
+    impl<T: BitFlag + DeserializeOwned> ConfigurationBuilder for BitFlagsBuilder<T> {
+        type Target = BitFlags<T>;
+
+        fn merge(self, other: Self) -> Self {
+            match (self.0, other.0) {
+                (Some(us), Some(other)) => Self(Some(us | other)),
+                (us, other) => Self(us.or(other)),
+            }
+        }
+
+        fn try_build(self) -> Result<Self::Target, Error> {
+            self.0
+                .ok_or_else(|| Error::MissingValue(MissingValue::default()))
+        }
+
+        fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
+            Ok(self.0.is_some())
+        }
+
+        fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+            if self.0.is_some() && other.0.is_some() {
+                Err(Conflict::default())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl<T: BitFlag + DeserializeOwned> Configuration for BitFlags<T> {
+        type Builder = BitFlagsBuilder<T>;
+    }
+
+    impl<T: BitFlag + DeserializeOwned> ToBuilder for BitFlags<T> {
+        fn to_builder(&self) -> Self::Builder {
+            BitFlagsBuilder(Some(*self))
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+mod http {
+    use http::Uri;
+
+    use super::from_str::FromStrBuilder;
+    use crate::{Configuration, ToBuilder};
+
+    impl Configuration for Uri {
+        type Builder = FromStrBuilder<Self>;
+    }
+
+    impl ToBuilder for Uri {
+        fn to_builder(&self) -> Self::Builder {
+            FromStrBuilder::from_value(self)
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use serde::{Deserialize, Deserializer};
+    use serde_json::Value;
+
+    use crate::{
+        Configuration, ConfigurationBuilder, Conflict, Error, MissingValue, ToBuilder,
+        UnexpectedSecret,
+    };
+
+    /// Builder for [`Value`], capturing whatever data was provided.
+    ///
+    /// Unlike most builders, objects are merged across sources: keys present in only one source are
+    /// all kept, and the values of keys present in both are merged in the same way. Any other
+    /// values, including arrays and `null`, are replaced by the higher priority source.
+    #[derive(Debug, Default)]
+    pub struct JsonValueBuilder(Option<Value>);
+
+    impl<'de> Deserialize<'de> for JsonValueBuilder {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            // Not `Option::deserialize`, which would treat `null` as missing.
+            Value::deserialize(deserializer).map(|value| Self(Some(value)))
+        }
+    }
+
+    /// Merges `other` into `us`, with `us` taking priority.
+    fn merge_values(us: Value, other: Value) -> Value {
+        match (us, other) {
+            (Value::Object(mut us), Value::Object(other)) => {
+                for (key, other) in other {
+                    match us.get_mut(&key) {
+                        Some(value) => *value = merge_values(value.take(), other),
+                        None => {
+                            us.insert(key, other);
+                        }
+                    }
+                }
+                Value::Object(us)
+            }
+            (us, _) => us,
+        }
+    }
+
+    /// Finds a value present in both `us` and `other`, which would be replaced when merging.
+    fn check_value_conflicts(us: &Value, other: &Value) -> Result<(), Conflict> {
+        match (us, other) {
+            (Value::Object(us), Value::Object(other)) => {
+                us.iter().try_for_each(|(key, us)| match other.get(key) {
+                    Some(other) => {
+                        check_value_conflicts(us, other).map_err(|err| err.prepend(key.clone()))
+                    }
+                    None => Ok(()),
+                })
+            }
+            _ => Err(Conflict::default()),
+        }
+    }
+
+    impl ConfigurationBuilder for JsonValueBuilder {
+        type Target = Value;
+
+        fn merge(self, other: Self) -> Self {
+            match (self.0, other.0) {
+                (Some(us), Some(other)) => Self(Some(merge_values(us, other))),
+                (us, other) => Self(us.or(other)),
+            }
+        }
+
+        fn try_build(self) -> Result<Self::Target, Error> {
+            self.0
+                .ok_or_else(|| Error::MissingValue(MissingValue::default()))
+        }
+
+        fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
+            Ok(self.0.is_some())
+        }
+
+        fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+            match (&self.0, &other.0) {
+                (Some(us), Some(other)) => check_value_conflicts(us, other),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    impl Configuration for Value {
+        type Builder = JsonValueBuilder;
+    }
+
+    impl ToBuilder for Value {
+        fn to_builder(&self) -> Self::Builder {
+            JsonValueBuilder(Some(self.clone()))
+        }
+    }
+}
+
+#[cfg(feature = "mime")]
+mod mime {
+    use mime::Mime;
+
+    use super::from_str::FromStrBuilder;
+    use crate::{Configuration, ToBuilder};
+
+    impl Configuration for Mime {
+        type Builder = FromStrBuilder<Self>;
+    }
+
+    impl ToBuilder for Mime {
+        fn to_builder(&self) -> Self::Builder {
+            FromStrBuilder::from_value(self)
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+mod regex {
+    use regex::Regex;
+
+    use super::from_str::FromStrBuilder;
+    use crate::{Configuration, ToBuilder};
+
+    impl Configuration for Regex {
+        type Builder = FromStrBuilder<Self>;
+    }
+
+    impl ToBuilder for Regex {
+        fn to_builder(&self) -> Self::Builder {
+            FromStrBuilder::from_value(self)
+        }
     }
 }
 
@@ -101,32 +534,258 @@ mod chrono {
 mod decimal {
     use rust_decimal::Decimal;
 
-    use crate::Configuration;
+    use crate::{Configuration, ToBuilder};
 
     impl Configuration for Decimal {
         type Builder = Option<Self>;
     }
+
+    impl ToBuilder for Decimal {
+        fn to_builder(&self) -> Self::Builder {
+            Some(*self)
+        }
+    }
+}
+
+#[cfg(feature = "fixed")]
+mod fixed {
+    use fixed::{
+        types::extra::{LeEqU128, LeEqU16, LeEqU32, LeEqU64, LeEqU8},
+        FixedI128, FixedI16, FixedI32, FixedI64, FixedI8, FixedU128, FixedU16, FixedU32, FixedU64,
+        FixedU8,
+    };
+
+    use crate::{Configuration, ToBuilder};
+
+    macro_rules! impl_fixed {
+        ($($fixed:ident: $frac:ident),* $(,)?) => {$(
+            impl<Frac: $frac> Configuration for $fixed<Frac> {
+                type Builder = Option<Self>;
+            }
+
+            impl<Frac: $frac> ToBuilder for $fixed<Frac> {
+                fn to_builder(&self) -> Self::Builder {
+                    Some(*self)
+                }
+            }
+        )*};
+    }
+
+    impl_fixed! {
+        FixedI8: LeEqU8,
+        FixedI16: LeEqU16,
+        FixedI32: LeEqU32,
+        FixedI64: LeEqU64,
+        FixedI128: LeEqU128,
+        FixedU8: LeEqU8,
+        FixedU16: LeEqU16,
+        FixedU32: LeEqU32,
+        FixedU64: LeEqU64,
+        FixedU128: LeEqU128,
+    }
 }
 
 #[cfg(feature = "ipnetwork")]
 mod ipnetwork {
     use ipnetwork::IpNetwork;
 
-    use crate::Configuration;
+    use crate::{Configuration, ToBuilder};
 
     impl Configuration for IpNetwork {
         type Builder = Option<Self>;
     }
+
+    impl ToBuilder for IpNetwork {
+        fn to_builder(&self) -> Self::Builder {
+            Some(*self)
+        }
+    }
 }
 
 #[cfg(feature = "secrecy")]
 mod secrecy {
     use secrecy::SecretString;
 
-    use crate::{Configuration, SecretOption};
+    use crate::{Configuration, SecretOption, ToBuilder};
 
     impl Configuration for SecretString {
         type Builder = SecretOption<Self>;
+
+        fn secret_paths() -> Vec<String> {
+            vec![String::new()]
+        }
+    }
+
+    impl ToBuilder for SecretString {
+        fn to_builder(&self) -> Self::Builder {
+            SecretOption::from(Some(self.clone()))
+        }
+    }
+}
+
+#[cfg(feature = "smol_str")]
+mod smol_str {
+    use smol_str::SmolStr;
+
+    use crate::{Configuration, ToBuilder};
+
+    impl Configuration for SmolStr {
+        type Builder = Option<Self>;
+    }
+
+    impl ToBuilder for SmolStr {
+        fn to_builder(&self) -> Self::Builder {
+            Some(self.clone())
+        }
+    }
+}
+
+#[cfg(feature = "tinyvec")]
+mod tinyvec {
+    use tinyvec::{ArrayVec, TinyVec};
+
+    use crate::{std_impls::UnkeyedContainerBuilder, Configuration, ToBuilder};
+
+    /// Sources providing more than `N` items fail to deserialize, raising an
+    /// [`Error::Source`](crate::Error::Source).
+    impl<T, const N: usize> Configuration for ArrayVec<[T; N]>
+    where
+        T: Configuration + Default,
+        T::Builder: 'static,
+    {
+        type Builder = UnkeyedContainerBuilder<ArrayVec<[T::Builder; N]>, Self>;
+
+        fn secret_paths() -> Vec<String> {
+            crate::__prefix_paths("*", T::secret_paths()).collect()
+        }
+    }
+
+    impl<T, const N: usize> ToBuilder for ArrayVec<[T; N]>
+    where
+        T: ToBuilder + Default,
+        T::Builder: 'static,
+    {
+        fn to_builder(&self) -> Self::Builder {
+            UnkeyedContainerBuilder::Some(self.iter().map(T::to_builder).collect())
+        }
+    }
+
+    /// Items beyond `N` are moved to the heap, as usual, rather than raising an error.
+    impl<T, const N: usize> Configuration for TinyVec<[T; N]>
+    where
+        T: Configuration + Default,
+        T::Builder: 'static,
+    {
+        type Builder = UnkeyedContainerBuilder<TinyVec<[T::Builder; N]>, Self>;
+
+        fn secret_paths() -> Vec<String> {
+            crate::__prefix_paths("*", T::secret_paths()).collect()
+        }
+    }
+
+    impl<T, const N: usize> ToBuilder for TinyVec<[T; N]>
+    where
+        T: ToBuilder + Default,
+        T::Builder: 'static,
+    {
+        fn to_builder(&self) -> Self::Builder {
+            UnkeyedContainerBuilder::Some(self.iter().map(T::to_builder).collect())
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+mod toml {
+    use std::mem;
+
+    use serde::Deserialize;
+    use toml::{Table, Value};
+
+    use crate::{
+        Configuration, ConfigurationBuilder, Conflict, Error, MissingValue, ToBuilder,
+        UnexpectedSecret,
+    };
+
+    /// Builder for [`Value`], capturing whatever data was provided.
+    ///
+    /// Unlike most builders, tables are merged across sources: keys present in only one source are
+    /// all kept, and the values of keys present in both are merged in the same way. Any other
+    /// values, including arrays, are replaced by the higher priority source.
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(transparent)]
+    pub struct TomlValueBuilder(Option<Value>);
+
+    /// Merges `other` into `us`, with `us` taking priority.
+    fn merge_values(us: Value, other: Value) -> Value {
+        match (us, other) {
+            (Value::Table(mut us), Value::Table(other)) => {
+                for (key, other) in other {
+                    match us.get_mut(&key) {
+                        Some(value) => {
+                            let us = mem::replace(value, Value::Table(Table::new()));
+                            *value = merge_values(us, other);
+                        }
+                        None => {
+                            us.insert(key, other);
+                        }
+                    }
+                }
+                Value::Table(us)
+            }
+            (us, _) => us,
+        }
+    }
+
+    /// Finds a value present in both `us` and `other`, which would be replaced when merging.
+    fn check_value_conflicts(us: &Value, other: &Value) -> Result<(), Conflict> {
+        match (us, other) {
+            (Value::Table(us), Value::Table(other)) => {
+                us.iter().try_for_each(|(key, us)| match other.get(key) {
+                    Some(other) => {
+                        check_value_conflicts(us, other).map_err(|err| err.prepend(key.clone()))
+                    }
+                    None => Ok(()),
+                })
+            }
+            _ => Err(Conflict::default()),
+        }
+    }
+
+    impl ConfigurationBuilder for TomlValueBuilder {
+        type Target = Value;
+
+        fn merge(self, other: Self) -> Self {
+            match (self.0, other.0) {
+                (Some(us), Some(other)) => Self(Some(merge_values(us, other))),
+                (us, other) => Self(us.or(other)),
+            }
+        }
+
+        fn try_build(self) -> Result<Self::Target, Error> {
+            self.0
+                .ok_or_else(|| Error::MissingValue(MissingValue::default()))
+        }
+
+        fn contains_non_secret_data(&self) -> Result<bool, UnexpectedSecret> {
+            Ok(self.0.is_some())
+        }
+
+        fn check_conflicts(&self, other: &Self) -> Result<(), Conflict> {
+            match (&self.0, &other.0) {
+                (Some(us), Some(other)) => check_value_conflicts(us, other),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    impl Configuration for Value {
+        type Builder = TomlValueBuilder;
+    }
+
+    impl ToBuilder for Value {
+        fn to_builder(&self) -> Self::Builder {
+            TomlValueBuilder(Some(self.clone()))
+        }
     }
 }
 
@@ -134,31 +793,49 @@ mod secrecy {
 mod url {
     use url::Url;
 
-    use crate::Configuration;
+    use crate::{Configuration, ToBuilder};
 
     impl Configuration for Url {
         type Builder = Option<Self>;
     }
+
+    impl ToBuilder for Url {
+        fn to_builder(&self) -> Self::Builder {
+            Some(self.clone())
+        }
+    }
 }
 
 #[cfg(feature = "uuid")]
 mod uuid {
     use uuid::Uuid;
 
-    use crate::Configuration;
+    use crate::{Configuration, ToBuilder};
 
     impl Configuration for Uuid {
         type Builder = Option<Self>;
     }
+
+    impl ToBuilder for Uuid {
+        fn to_builder(&self) -> Self::Builder {
+            Some(*self)
+        }
+    }
 }
 
 #[cfg(feature = "bigdecimal")]
 mod bigdecimal {
     use bigdecimal::BigDecimal;
 
-    use crate::Configuration;
+    use crate::{Configuration, ToBuilder};
 
     impl Configuration for BigDecimal {
         type Builder = Option<Self>;
     }
+
+    impl ToBuilder for BigDecimal {
+        fn to_builder(&self) -> Self::Builder {
+            Some(self.clone())
+        }
+    }
 }
@@ -0,0 +1,38 @@
+//! Measuring how long each step of building a configuration takes.
+
+use std::time::Duration;
+
+/// How long each step of building a configuration took, see
+/// [`ConfigBuilder::try_build_timed`](crate::ConfigBuilder::try_build_timed).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildTimings {
+    pub(crate) sources: Vec<Duration>,
+    pub(crate) merge: Duration,
+    pub(crate) build: Duration,
+    pub(crate) total: Duration,
+}
+
+impl BuildTimings {
+    /// How long each source took to provide its data, in priority order, i.e. in the same order as
+    /// [`ConfigBuilder::source_labels`](crate::ConfigBuilder::source_labels).
+    ///
+    /// This includes checking the data for secrets. If a source fails, it is the last one listed.
+    pub fn sources(&self) -> &[Duration] {
+        &self.sources
+    }
+
+    /// How long merging the data from every source took, including checking for conflicts.
+    pub fn merge(&self) -> Duration {
+        self.merge
+    }
+
+    /// How long building the target from the merged data took.
+    pub fn build(&self) -> Duration {
+        self.build
+    }
+
+    /// How long building took overall.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+}
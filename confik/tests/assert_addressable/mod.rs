@@ -0,0 +1,87 @@
+// Colliding keys also make some of the generated `Deserialize` implementation unreachable.
+#![allow(unreachable_patterns)]
+
+use confik::Configuration;
+
+#[derive(Configuration)]
+#[allow(dead_code)]
+struct Database {
+    host: String,
+    #[confik(forward_serde(rename = "host"))]
+    hostname: String,
+}
+
+#[test]
+fn addressable() {
+    #[derive(Configuration)]
+    #[confik(forward_serde(rename_all = "camelCase"))]
+    #[allow(dead_code)]
+    struct Config {
+        max_conns: usize,
+        #[confik(forward_serde(rename = "max_conns"))]
+        legacy_max_conns: Option<usize>,
+        servers: Vec<String>,
+    }
+
+    Config::assert_addressable();
+}
+
+#[test]
+#[should_panic(expected = "Found more than one field or variant named `port` at the root of")]
+fn rename_collision() {
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Config {
+        #[confik(forward_serde(rename = "port"))]
+        listen_port: u16,
+        port: u16,
+    }
+
+    Config::assert_addressable();
+}
+
+#[test]
+#[should_panic(expected = "Found more than one field or variant named `host` at `primary`")]
+fn nested_collision() {
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Config {
+        primary: Database,
+    }
+
+    Config::assert_addressable();
+}
+
+#[test]
+#[should_panic(
+    expected = "Found more than one field or variant named `host` at `backend.Remote.database`"
+)]
+fn collision_in_variant() {
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    enum Backend {
+        Local,
+        Remote { database: Database },
+    }
+
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Config {
+        backend: Backend,
+    }
+
+    Config::assert_addressable();
+}
+
+#[test]
+#[should_panic(expected = "Found an empty key at the root of")]
+fn empty_key() {
+    #[derive(Configuration)]
+    #[allow(dead_code)]
+    struct Config {
+        #[confik(forward_serde(rename = ""))]
+        port: u16,
+    }
+
+    Config::assert_addressable();
+}
@@ -0,0 +1,65 @@
+use std::{error::Error, thread, time::Duration};
+
+use confik::{Configuration, ConfigurationBuilder, Source, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    host: String,
+    port: u16,
+}
+
+/// A source which takes a while to provide, like a file on a slow network mount.
+#[derive(Debug)]
+struct SlowSource(Duration);
+
+impl Source for SlowSource {
+    fn provide<T: ConfigurationBuilder>(&self) -> Result<T, Box<dyn Error + Sync + Send>> {
+        thread::sleep(self.0);
+        Ok(T::default())
+    }
+}
+
+#[test]
+fn timings_are_recorded_per_source() {
+    let mut builder = Config::builder();
+    builder
+        .override_with(TomlSource::new("host = \"localhost\""))
+        .override_with(SlowSource(Duration::from_millis(20)))
+        .override_with(TomlSource::new("port = 8080"));
+
+    // Timings are in the same order as the labels.
+    assert_eq!(builder.source_labels()[1], "SlowSource(20ms)");
+
+    let (config, timings) = builder.try_build_timed();
+
+    assert_eq!(
+        config.unwrap(),
+        Config {
+            host: "localhost".to_owned(),
+            port: 8080,
+        }
+    );
+
+    assert_eq!(timings.sources().len(), 3);
+
+    let slow = timings.sources()[1];
+    assert!(slow >= Duration::from_millis(20), "{slow:?}");
+
+    let steps = timings.sources().iter().sum::<Duration>() + timings.merge() + timings.build();
+    assert!(timings.total() >= steps, "{timings:?}");
+}
+
+#[test]
+fn timings_are_recorded_on_failure() {
+    let (config, timings) = Config::builder()
+        .override_with(SlowSource(Duration::ZERO))
+        .override_with(TomlSource::new("port = \"not a number\""))
+        .try_build_timed();
+
+    config.unwrap_err();
+
+    // The failing source is the last one tried, and nothing is merged or built
+    assert_eq!(timings.sources().len(), 1);
+    assert_eq!(timings.merge(), Duration::ZERO);
+    assert_eq!(timings.build(), Duration::ZERO);
+}
@@ -0,0 +1,123 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use confik::{Configuration, TomlSource};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+use crate::AbsentSource;
+
+/// Records the names of spans and the messages of events, in the order they happen.
+#[derive(Debug, Default)]
+struct Recorder {
+    next_id: AtomicU64,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("span: {}", span.metadata().name()));
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        struct Message(String);
+
+        impl Visit for Message {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                if field.name() == "message" {
+                    self.0 = format!("{value:?}");
+                }
+            }
+        }
+
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("event: {}", message.0));
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[derive(Debug, Configuration)]
+struct Config {
+    #[allow(dead_code)]
+    port: u16,
+}
+
+#[test]
+fn records_each_step() {
+    let recorder = Recorder::default();
+    let log = Arc::clone(&recorder.log);
+
+    tracing::subscriber::with_default(recorder, || {
+        Config::builder()
+            .override_with(TomlSource::new("port = 80"))
+            .override_with(AbsentSource)
+            .override_with(TomlSource::new("port = 81"))
+            .try_build()
+            .expect("Failed to build config");
+    });
+
+    // Sources are provided from highest to lowest priority.
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "span: building",
+            "span: providing",
+            "event: source provided data",
+            "span: providing",
+            "event: source has no data, skipping",
+            "span: providing",
+            "event: source provided data",
+            "span: merging",
+            "event: built configuration",
+        ]
+    );
+}
+
+#[test]
+fn records_failure() {
+    let recorder = Recorder::default();
+    let log = Arc::clone(&recorder.log);
+
+    tracing::subscriber::with_default(recorder, || {
+        Config::builder()
+            .override_with(TomlSource::new("port = \"eighty\""))
+            .try_build()
+            .expect_err("Built config from an invalid source");
+    });
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "span: building",
+            "span: providing",
+            "event: source failed",
+            "event: failed to build configuration",
+        ]
+    );
+}
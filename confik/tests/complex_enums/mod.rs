@@ -170,3 +170,95 @@ mod json {
         );
     }
 }
+
+#[cfg(feature = "toml")]
+mod default_variant {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
+
+    #[derive(Configuration, Debug, PartialEq, Eq)]
+    enum Level {
+        Debug,
+        #[confik(default)]
+        Info,
+    }
+
+    #[derive(Configuration, Debug, PartialEq, Eq)]
+    enum Backend {
+        Memory,
+        #[confik(default)]
+        Disk {
+            #[confik(default = "/data")]
+            path: String,
+            size: Option<u64>,
+        },
+    }
+
+    #[derive(Configuration, Debug, PartialEq, Eq)]
+    enum Listener {
+        #[confik(default)]
+        Tcp {
+            port: u16,
+        },
+        Unix {
+            path: String,
+        },
+    }
+
+    #[derive(Configuration, Debug, PartialEq, Eq)]
+    struct Config {
+        level: Level,
+        backend: Backend,
+    }
+
+    #[test]
+    fn unselected() {
+        let config = Config::builder()
+            .override_with(TomlSource::new(""))
+            .try_build()
+            .expect("Both enums have a default variant");
+        assert_eq!(
+            config,
+            Config {
+                level: Level::Info,
+                backend: Backend::Disk {
+                    path: "/data".to_owned(),
+                    size: None,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn selected() {
+        let config = Config::builder()
+            .override_with(TomlSource::new("level = \"Debug\"\nbackend = \"Memory\""))
+            .try_build()
+            .expect("Variants are selected");
+        assert_eq!(
+            config,
+            Config {
+                level: Level::Debug,
+                backend: Backend::Memory,
+            }
+        );
+    }
+
+    #[test]
+    fn default_variant_missing_field() {
+        #[derive(Configuration, Debug)]
+        struct Config {
+            #[allow(dead_code)]
+            listener: Listener,
+        }
+
+        let err = Config::builder()
+            .override_with(TomlSource::new(""))
+            .try_build()
+            .expect_err("The default variant has no default port");
+        assert_matches!(
+            err,
+            Error::MissingValue(path) if path.to_string().contains("`listener.Tcp.port`")
+        );
+    }
+}
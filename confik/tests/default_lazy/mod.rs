@@ -0,0 +1,38 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use confik::{Configuration, TomlSource};
+use once_cell::sync::Lazy;
+
+/// How many times `HOSTS` has been computed.
+static COMPUTED: AtomicUsize = AtomicUsize::new(0);
+
+static HOSTS: Lazy<Vec<String>> = Lazy::new(|| {
+    COMPUTED.fetch_add(1, Ordering::SeqCst);
+    vec!["a.example.com".to_owned(), "b.example.com".to_owned()]
+});
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    #[confik(default_lazy = &HOSTS)]
+    hosts: Vec<String>,
+}
+
+#[test]
+fn computed_once_across_builds() {
+    for _ in 0..3 {
+        let config = Config::builder().try_build().unwrap();
+        assert_eq!(config.hosts, *HOSTS);
+    }
+
+    assert_eq!(COMPUTED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn source_overrides_default() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("hosts = [\"c.example.com\"]"))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(config.hosts, ["c.example.com"]);
+}
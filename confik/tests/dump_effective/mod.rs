@@ -0,0 +1,205 @@
+use confik::{Configuration, Format, TomlSource};
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Configuration)]
+struct Database {
+    host: String,
+    #[confik(secret)]
+    password: String,
+}
+
+#[derive(Debug, Serialize, Configuration)]
+struct Config {
+    name: String,
+    database: Database,
+    replicas: Vec<Database>,
+    #[confik(secret)]
+    api_key: Option<String>,
+}
+
+const SECRETS: &str = r#"
+[database]
+password = "hunter2"
+
+[[replicas]]
+host = "replica"
+password = "hunter3"
+"#;
+
+fn builder() -> confik::ConfigBuilder<'static, Config> {
+    let mut builder = Config::builder();
+    builder
+        .override_with(TomlSource::new(SECRETS).allow_secrets())
+        .override_with(TomlSource::new("name = \"app\"\n[database]\nhost = \"db\""));
+    builder
+}
+
+#[test]
+fn json() {
+    let dump = builder().dump_effective(Format::Json).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&dump).unwrap(),
+        serde_json::json!({
+            "name": "app",
+            "database": { "host": "db", "password": "[redacted]" },
+            "replicas": [{ "host": "replica", "password": "[redacted]" }],
+            "api_key": null,
+        }),
+    );
+    assert!(!dump.contains("hunter"));
+}
+
+#[test]
+fn toml() {
+    let dump = builder().dump_effective(Format::Toml).unwrap();
+
+    assert_eq!(
+        dump.parse::<toml::Table>().unwrap(),
+        toml::toml! {
+            name = "app"
+
+            [database]
+            host = "db"
+            password = "[redacted]"
+
+            [[replicas]]
+            host = "replica"
+            password = "[redacted]"
+        },
+    );
+}
+
+#[test]
+fn build_error() {
+    let err = Config::builder()
+        .override_with(TomlSource::new("name = \"app\""))
+        .dump_effective(Format::Json)
+        .expect_err("The database is missing");
+
+    assert!(matches!(err, confik::Error::MissingValue(_)), "{err:?}");
+}
+
+#[test]
+fn renamed_fields() {
+    #[derive(Debug, Serialize, Configuration)]
+    #[serde(rename_all = "camelCase")]
+    #[confik(forward_serde(rename_all = "camelCase"))]
+    struct Renamed {
+        user_name: String,
+        #[confik(secret)]
+        api_key: String,
+        #[serde(rename = "dbPass")]
+        #[confik(secret, forward_serde(rename = "dbPass"))]
+        database_password: String,
+    }
+
+    let dump = Renamed::builder()
+        .override_with(
+            TomlSource::new("userName = \"admin\"\napiKey = \"hunter2\"\ndbPass = \"hunter3\"")
+                .allow_secrets(),
+        )
+        .dump_effective(Format::Json)
+        .unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&dump).unwrap(),
+        serde_json::json!({
+            "userName": "admin",
+            "apiKey": "[redacted]",
+            "dbPass": "[redacted]",
+        }),
+    );
+    assert!(!dump.contains("hunter"));
+}
+
+#[test]
+fn recursive_type() {
+    #[derive(Debug, Serialize, Configuration)]
+    struct Node {
+        name: String,
+        #[confik(secret)]
+        token: Option<String>,
+        #[confik(default)]
+        children: Vec<Node>,
+    }
+
+    let dump = Node::builder()
+        .override_with(
+            TomlSource::new(
+                r#"
+                name = "root"
+                token = "hunter2"
+
+                [[children]]
+                name = "child"
+                token = "hunter3"
+                "#,
+            )
+            .allow_secrets(),
+        )
+        .dump_effective(Format::Json)
+        .unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&dump).unwrap(),
+        serde_json::json!({
+            "name": "root",
+            "token": "[redacted]",
+            "children": ["[redacted]"],
+        }),
+    );
+}
+
+#[test]
+fn tagged_enums() {
+    #[derive(Debug, Serialize, Configuration)]
+    #[serde(tag = "type")]
+    #[confik(forward_serde(tag = "type"))]
+    enum Auth {
+        Basic {
+            user: String,
+            #[confik(secret)]
+            password: String,
+        },
+    }
+
+    #[derive(Debug, Serialize, Configuration)]
+    #[serde(untagged)]
+    #[confik(forward_serde(untagged))]
+    enum Credentials {
+        Token(#[confik(secret)] String),
+    }
+
+    #[derive(Debug, Serialize, Configuration)]
+    struct Config {
+        auth: Auth,
+        credentials: Credentials,
+    }
+
+    let dump = Config::builder()
+        .override_with(
+            TomlSource::new(
+                r#"
+                credentials = "hunter3"
+
+                [auth]
+                type = "Basic"
+                user = "u"
+                password = "hunter2"
+                "#,
+            )
+            .allow_secrets(),
+        )
+        .dump_effective(Format::Json)
+        .unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&dump).unwrap(),
+        serde_json::json!({
+            "auth": { "type": "Basic", "user": "u", "password": "[redacted]" },
+            "credentials": "[redacted]",
+        }),
+    );
+    assert!(!dump.contains("hunter"));
+}
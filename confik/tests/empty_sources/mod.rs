@@ -0,0 +1,75 @@
+//! Empty (or whitespace-only) input provides no values in every format, rather than erroring.
+
+use confik::{Configuration, Source};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    #[confik(default = 1usize)]
+    a: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+#[confik(forward_serde(rename_all = "lowercase"))]
+enum Mode {
+    Fast,
+}
+
+fn check_empty(source: impl Source + Clone) {
+    let config = Config::builder()
+        .override_with(source.clone())
+        .try_build()
+        .expect("Empty sources provide no values");
+    assert_eq!(config, Config { a: 1 });
+
+    // `Option` builders are left unset, rather than failing to parse an empty table.
+    Mode::builder()
+        .override_with(source)
+        .try_build()
+        .expect_err("Empty sources provide no values");
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml() {
+    check_empty(confik::TomlSource::new(""));
+    check_empty(confik::TomlSource::new(" \n\t"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json() {
+    check_empty(confik::JsonSource::new(""));
+    check_empty(confik::JsonSource::new(" \n\t"));
+}
+
+#[test]
+fn file() {
+    let dir = tempfile::TempDir::new().unwrap();
+
+    for (name, contents) in [
+        ("empty.toml", ""),
+        ("whitespace.toml", " \n"),
+        ("empty.json", ""),
+        ("whitespace.json", " \n"),
+    ] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents).unwrap();
+
+        let extension = path.extension().unwrap().to_str().unwrap();
+        if (extension == "toml" && cfg!(feature = "toml"))
+            || (extension == "json" && cfg!(feature = "json"))
+        {
+            check_empty(confik::FileSource::new(&path));
+        }
+    }
+
+    dir.close().unwrap();
+}
+
+#[cfg(feature = "env")]
+#[test]
+fn env() {
+    temp_env::with_vars([("CONFIK_EMPTY_TEST_UNRELATED", Some("1"))], || {
+        check_empty(confik::EnvSource::new().with_prefix("CONFIK_EMPTY_SOURCE_"));
+    });
+}
@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+
+use confik::{Configuration, EnvSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    host: String,
+    port: u16,
+}
+
+#[test]
+fn multiple_prefixes_populate_different_fields() {
+    let config = temp_env::with_vars(
+        [
+            ("APP_HOST", Some("localhost")),
+            ("MYSERVICE_PORT", Some("8080")),
+        ],
+        || {
+            Config::builder()
+                .override_with(EnvSource::new().with_prefixes(&["APP_", "MYSERVICE_"]))
+                .try_build()
+                .expect("Fields are spread across both prefixes")
+        },
+    );
+
+    assert_eq!(
+        config,
+        Config {
+            host: "localhost".to_owned(),
+            port: 8080,
+        }
+    );
+}
+
+#[test]
+fn earlier_prefix_takes_precedence() {
+    let config = temp_env::with_vars(
+        [
+            ("APP_HOST", Some("app.example")),
+            ("MYSERVICE_HOST", Some("service.example")),
+            ("MYSERVICE_PORT", Some("8080")),
+        ],
+        || {
+            Config::builder()
+                .override_with(EnvSource::new().with_prefixes(&["APP_", "MYSERVICE_"]))
+                .try_build()
+                .expect("All fields are present")
+        },
+    );
+
+    assert_eq!(config.host, "app.example");
+    assert_eq!(config.port, 8080);
+}
+
+#[test]
+fn from_map_populates_nested_fields() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        database: Database,
+        log_level: String,
+    }
+
+    let vars = HashMap::from([
+        ("APP_DATABASE__HOST".to_owned(), "db.example".to_owned()),
+        ("APP_DATABASE__PORT".to_owned(), "5432".to_owned()),
+        ("APP_LOG_LEVEL".to_owned(), "debug".to_owned()),
+        ("OTHER_LOG_LEVEL".to_owned(), "trace".to_owned()),
+    ]);
+
+    // Nothing is read from the process's environment.
+    let config = temp_env::with_var("APP_LOG_LEVEL", Some("info"), || {
+        Config::builder()
+            .override_with(EnvSource::from_map(vars).with_prefix("APP_"))
+            .try_build()
+            .expect("The map provides every value")
+    });
+
+    assert_eq!(
+        config,
+        Config {
+            database: Database {
+                host: "db.example".to_owned(),
+                port: 5432,
+            },
+            log_level: "debug".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn normalize_keys_with_rename_all() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    #[confik(forward_serde(rename_all = "camelCase"))]
+    struct Pool {
+        max_conns: usize,
+        idle_timeout_secs: u64,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    #[confik(forward_serde(rename_all = "camelCase"))]
+    struct Config {
+        database_pool: Pool,
+        log_level: String,
+    }
+
+    let config = temp_env::with_vars(
+        [
+            ("APP__DATABASE_POOL__MAX_CONNS", Some("16")),
+            ("APP__DATABASE_POOL__IDLE_TIMEOUT_SECS", Some("30")),
+            ("APP__LOG_LEVEL", Some("debug")),
+        ],
+        || {
+            Config::builder()
+                .override_with(EnvSource::new().with_prefix("APP__").normalize_keys())
+                .try_build()
+                .expect("Env var names should be matched to camelCase fields")
+        },
+    );
+
+    assert_eq!(
+        config,
+        Config {
+            database_pool: Pool {
+                max_conns: 16,
+                idle_timeout_secs: 30,
+            },
+            log_level: "debug".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn normalize_keys_of_recursive_type() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    #[confik(forward_serde(rename_all = "camelCase"))]
+    struct Node {
+        #[confik(default)]
+        node_value: u32,
+        #[confik(default)]
+        left: Vec<Node>,
+        #[confik(default)]
+        middle: Vec<Node>,
+        #[confik(default)]
+        right: Vec<Node>,
+    }
+
+    let leaf = |node_value| Node {
+        node_value,
+        left: Vec::new(),
+        middle: Vec::new(),
+        right: Vec::new(),
+    };
+
+    // With several recursive fields, discovering the expected names must not explore every path.
+    let config = temp_env::with_vars(
+        [
+            ("APP__NODE_VALUE", Some("1")),
+            ("APP__LEFT__0__MIDDLE__0__RIGHT__0__NODE_VALUE", Some("2")),
+        ],
+        || {
+            Node::builder()
+                .override_with(EnvSource::new().with_prefix("APP__").normalize_keys())
+                .try_build()
+                .expect("Env var names should be matched at any depth")
+        },
+    );
+
+    assert_eq!(
+        config,
+        Node {
+            left: vec![Node {
+                middle: vec![Node {
+                    right: vec![leaf(2)],
+                    ..leaf(0)
+                }],
+                ..leaf(0)
+            }],
+            ..leaf(1)
+        }
+    );
+}
+
+#[test]
+fn without_normalize_keys_renamed_fields_are_missed() {
+    #[derive(Debug, Configuration)]
+    #[confik(forward_serde(rename_all = "camelCase"))]
+    struct Config {
+        #[allow(dead_code)]
+        max_conns: usize,
+    }
+
+    temp_env::with_var("APP__MAX_CONNS", Some("16"), || {
+        Config::builder()
+            .override_with(EnvSource::new().with_prefix("APP__"))
+            .try_build()
+            .unwrap_err();
+    });
+}
+
+#[test]
+fn file_indirection_populates_secret() {
+    use std::io::Write as _;
+
+    #[derive(Debug, Configuration)]
+    struct Config {
+        user: String,
+        #[confik(secret)]
+        token: String,
+    }
+
+    let mut token_file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(token_file, "hunter2").unwrap();
+    let token_var = format!("@{}", token_file.path().display());
+
+    temp_env::with_vars(
+        [
+            ("USER", Some("@@admin")),
+            ("TOKEN", Some(token_var.as_str())),
+        ],
+        || {
+            let config = Config::builder()
+                .override_with(EnvSource::new().with_file_indirection(true).allow_secrets())
+                .try_build()
+                .expect("Token is read from the file");
+
+            assert_eq!(config.user, "@admin");
+            assert_eq!(config.token, "hunter2");
+
+            Config::builder()
+                .override_with(EnvSource::new().with_file_indirection(true))
+                .try_build()
+                .expect_err("File-sourced secrets still require `allow_secrets`");
+        },
+    );
+}
+
+#[test]
+fn file_indirection_missing_file() {
+    let err = temp_env::with_vars(
+        [
+            ("HOST", Some("@/nonexistent/confik/host")),
+            ("PORT", Some("8080")),
+        ],
+        || {
+            Config::builder()
+                .override_with(EnvSource::new().with_file_indirection(true))
+                .try_build()
+                .expect_err("Missing files are an error")
+        },
+    );
+
+    assert!(
+        matches!(&err, confik::Error::Source(err, _) if err.to_string().contains("`host`")),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn file_indirection_ignores_unrelated_variables() {
+    let config = temp_env::with_vars(
+        [
+            ("HOST", Some("localhost")),
+            ("PORT", Some("8080")),
+            ("UNRELATED_THING", Some("@/nonexistent/confik/unrelated")),
+        ],
+        || {
+            Config::builder()
+                .override_with(EnvSource::new().with_file_indirection(true))
+                .try_build()
+                .expect("Only variables read by the builder are resolved")
+        },
+    );
+
+    assert_eq!(config.host, "localhost");
+}
+
+#[test]
+fn file_indirection_disabled() {
+    let config = temp_env::with_vars(
+        [("HOST", Some("@localhost")), ("PORT", Some("8080"))],
+        || {
+            Config::builder()
+                .override_with(EnvSource::new())
+                .try_build()
+                .expect("Values are used as-is")
+        },
+    );
+
+    assert_eq!(config.host, "@localhost");
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn env_ignore_skips_field() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Hashing {
+        rounds: u32,
+        #[confik(env_ignore)]
+        salt: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        hashing: Hashing,
+    }
+
+    let config = temp_env::with_vars(
+        [
+            ("HASHING__ROUNDS", Some("12")),
+            ("HASHING__SALT", Some("from-env")),
+        ],
+        || {
+            Config::builder()
+                .override_with(confik::TomlSource::new(
+                    r#"hashing = { rounds = 10, salt = "from-file" }"#,
+                ))
+                .override_with(EnvSource::new().with_separator("__"))
+                .try_build()
+                .expect("All fields are present")
+        },
+    );
+
+    assert_eq!(
+        config.hashing,
+        Hashing {
+            rounds: 12,
+            salt: "from-file".to_owned(),
+        }
+    );
+
+    // Without another source, the variable is ignored and so the field is missing.
+    let err = temp_env::with_vars([("ROUNDS", Some("12")), ("SALT", Some("from-env"))], || {
+        Hashing::builder()
+            .override_with(EnvSource::new())
+            .try_build()
+            .expect_err("Salt can't come from the environment")
+    });
+    assert!(err.to_string().contains("`salt`"), "{err}");
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn env_only_secret() {
+    use std::io::Write as _;
+
+    use confik::{FileSource, SourceKind};
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Database {
+        host: String,
+        #[confik(secret = "credential", env_only)]
+        password: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        database: Database,
+    }
+
+    let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    writeln!(file, "[database]\nhost = \"localhost\"").unwrap();
+    let file_path = file.path().to_owned();
+
+    let mut secret_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    writeln!(secret_file, "[database]\npassword = \"from-file\"").unwrap();
+    let secret_file_path = secret_file.path().to_owned();
+
+    assert_eq!(
+        confik::Source::kind(&FileSource::new(&file_path)),
+        SourceKind::File
+    );
+    assert_eq!(confik::Source::kind(&EnvSource::new()), SourceKind::Env);
+
+    // Other fields may come from files, with the secret from the environment.
+    let config = temp_env::with_var("DATABASE__PASSWORD", Some("from-env"), || {
+        Config::builder()
+            .override_with(FileSource::new(&file_path))
+            .override_with(EnvSource::new().with_separator("__").allow_secrets())
+            .try_build()
+            .expect("The secret is only in the environment")
+    });
+
+    assert_eq!(
+        config.database,
+        Database {
+            host: "localhost".to_owned(),
+            password: "from-env".to_owned(),
+        }
+    );
+
+    // Files are rejected, even when allowed secrets.
+    let err = temp_env::with_var("DATABASE__PASSWORD", Some("from-env"), || {
+        Config::builder()
+            .override_with(FileSource::new(&file_path))
+            .override_with(FileSource::new(&secret_file_path).allow_secrets())
+            .override_with(EnvSource::new().with_separator("__").allow_secrets())
+            .try_build()
+            .expect_err("The secret can't come from a file")
+    });
+
+    assert!(
+        matches!(
+            &err,
+            confik::Error::EnvOnlySecret(secret, _)
+                if secret.to_string() == "Found credential secret at path `database.password`"
+        ),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn source_kinds_restrict_field() {
+    use std::io::Write as _;
+
+    use confik::{FileSource, SourceKind, TomlSource};
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        #[confik(source_kinds(file))]
+        data_dir: String,
+        port: u16,
+    }
+
+    let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    writeln!(file, "data_dir = \"/var/lib/app\"\nport = 80").unwrap();
+    let file_path = file.path().to_owned();
+
+    // Unrestricted fields may still be overridden by other kinds of source.
+    let config = temp_env::with_var("PORT", Some("8080"), || {
+        Config::builder()
+            .override_with(FileSource::new(&file_path))
+            .override_with(EnvSource::new())
+            .try_build()
+            .expect("The restricted field is only in the file")
+    });
+
+    assert_eq!(
+        config,
+        Config {
+            data_dir: "/var/lib/app".to_owned(),
+            port: 8080,
+        }
+    );
+
+    let err = temp_env::with_var("DATA_DIR", Some("/tmp"), || {
+        Config::builder()
+            .override_with(FileSource::new(&file_path))
+            .override_with(EnvSource::new())
+            .try_build()
+            .expect_err("The restricted field can't come from the environment")
+    });
+
+    assert!(
+        matches!(
+            &err,
+            confik::Error::UnexpectedSource(source, _)
+                if source.kind() == SourceKind::Env
+                    && source.to_string()
+                        == "Found value at path `data_dir`, which can't come from environment sources"
+        ),
+        "unexpected error: {err:?}"
+    );
+
+    let err = Config::builder()
+        .override_with(FileSource::new(&file_path))
+        .override_with(TomlSource::new("data_dir = \"/tmp\""))
+        .try_build()
+        .expect_err("The restricted field can't come from a string");
+
+    assert!(
+        matches!(&err, confik::Error::UnexpectedSource(source, _) if source.kind() == SourceKind::Other),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn env_style_nested() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    #[confik(env_style)]
+    struct Pool {
+        max_conns: usize,
+        idle_timeout_secs: u64,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    #[confik(env_style)]
+    struct Config {
+        database_pool: Pool,
+        log_level: String,
+    }
+
+    let vars = HashMap::from([
+        ("APP_DATABASE_POOL__MAX_CONNS".to_owned(), "16".to_owned()),
+        (
+            "APP_DATABASE_POOL__IDLE_TIMEOUT_SECS".to_owned(),
+            "30".to_owned(),
+        ),
+        ("APP_LOG_LEVEL".to_owned(), "debug".to_owned()),
+        // Not in the expected case, so ignored.
+        ("app_log_level".to_owned(), "trace".to_owned()),
+    ]);
+
+    // The separator is always `__` for these builders.
+    let config = Config::builder()
+        .override_with(
+            EnvSource::from_map(vars)
+                .with_prefix("APP_")
+                .with_separator("_"),
+        )
+        .try_build()
+        .expect("Every field is provided");
+
+    assert_eq!(
+        config,
+        Config {
+            database_pool: Pool {
+                max_conns: 16,
+                idle_timeout_secs: 30,
+            },
+            log_level: "debug".to_owned(),
+        }
+    );
+}
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    #[confik(expand_tilde)]
+    path: PathBuf,
+}
+
+fn build(path: &str) -> PathBuf {
+    temp_env::with_var("HOME", Some("/home/confik"), || {
+        Config::builder()
+            .override_with(TomlSource::new(format!("path = {path:?}")))
+            .try_build()
+            .expect("Valid path")
+            .path
+    })
+}
+
+#[test]
+fn expanded() {
+    assert_eq!(build("~"), PathBuf::from("/home/confik"));
+    assert_eq!(build("~/"), PathBuf::from("/home/confik"));
+    assert_eq!(
+        build("~/.config/app"),
+        PathBuf::from("/home/confik/.config/app")
+    );
+}
+
+#[test]
+fn not_expanded() {
+    assert_eq!(build("/etc/app"), PathBuf::from("/etc/app"));
+    assert_eq!(build("relative/~/app"), PathBuf::from("relative/~/app"));
+    assert_eq!(build("~user/app"), PathBuf::from("~user/app"));
+    assert_eq!(build("~app"), PathBuf::from("~app"));
+}
+
+#[test]
+fn default_expanded() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Defaulted {
+        #[confik(expand_tilde, default = "~/.cache")]
+        path: PathBuf,
+    }
+
+    let config = temp_env::with_var("HOME", Some("/home/confik"), || {
+        Defaulted::builder().try_build().expect("Defaulted")
+    });
+    assert_eq!(config.path, PathBuf::from("/home/confik/.cache"));
+}
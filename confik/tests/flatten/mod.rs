@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    host: String,
+    port: u16,
+    #[confik(flatten, default)]
+    extra: HashMap<String, String>,
+}
+
+#[test]
+fn unknown_keys_are_collected() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(
+            r#"
+            host = "localhost"
+            port = 8080
+            region = "eu-west-1"
+            tier = "gold"
+            "#,
+        ))
+        .try_build()
+        .expect("Known fields and extras are both read");
+
+    assert_eq!(
+        config,
+        Config {
+            host: "localhost".to_owned(),
+            port: 8080,
+            extra: HashMap::from([
+                ("region".to_owned(), "eu-west-1".to_owned()),
+                ("tier".to_owned(), "gold".to_owned()),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn extras_are_merged_across_sources() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(
+            r#"
+            host = "localhost"
+            port = 80
+            region = "eu-west-1"
+            tier = "silver"
+            "#,
+        ))
+        .override_with(TomlSource::new(
+            r#"
+            port = 8080
+            tier = "gold"
+            "#,
+        ))
+        .try_build()
+        .expect("Sources combine");
+
+    assert_eq!(config.port, 8080);
+    assert_eq!(
+        config.extra,
+        HashMap::from([
+            ("region".to_owned(), "eu-west-1".to_owned()),
+            ("tier".to_owned(), "gold".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn no_extras() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("host = \"localhost\"\nport = 80"))
+        .try_build()
+        .expect("Extras are optional");
+    assert!(config.extra.is_empty());
+}
+
+#[test]
+fn missing_known_field() {
+    let err = Config::builder()
+        .override_with(TomlSource::new(
+            "host = \"localhost\"\nregion = \"eu-west-1\"",
+        ))
+        .try_build()
+        .expect_err("Extras don't stand in for known fields");
+    assert!(err.to_string().contains("`port`"), "{err}");
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+#[confik(forward_serde(tag = "mode", rename_all = "snake_case"))]
+enum Mode {
+    Standalone { data_dir: String },
+    Cluster { peers: Vec<String>, quorum: usize },
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct ModeConfig {
+    port: u16,
+    #[confik(flatten)]
+    mode: Mode,
+}
+
+#[test]
+fn internally_tagged_enum() {
+    let config = ModeConfig::builder()
+        .override_with(TomlSource::new(
+            r#"
+            mode = "cluster"
+            peers = ["10.0.0.1", "10.0.0.2"]
+            quorum = 2
+            "#,
+        ))
+        // Sources without the tag leave the enum undefined, rather than failing.
+        .override_with(TomlSource::new("port = 8080"))
+        .try_build()
+        .expect("The tag and variant fields are read from the parent");
+
+    assert_eq!(
+        config,
+        ModeConfig {
+            port: 8080,
+            mode: Mode::Cluster {
+                peers: vec!["10.0.0.1".to_owned(), "10.0.0.2".to_owned()],
+                quorum: 2,
+            },
+        }
+    );
+
+    let err = ModeConfig::builder()
+        .override_with(TomlSource::new("port = 8080"))
+        .try_build()
+        .expect_err("No source provides the tag");
+    assert!(err.to_string().contains("`mode`"), "{err}");
+
+    ModeConfig::builder()
+        .override_with(TomlSource::new(
+            "port = 8080\nmode = \"standalone\"\ndata_dir = 5",
+        ))
+        .try_build()
+        .expect_err("Invalid variant data isn't mistaken for a missing tag");
+
+    ModeConfig::builder()
+        .override_with(TomlSource::new(
+            "port = 8080\nmode = \"ConfigBuilderUndefined\"",
+        ))
+        .try_build()
+        .expect_err("The builder's undefined variant can't be named");
+}
+
+#[test]
+fn tagged_enum_not_flattened() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        port: u16,
+        mode: Mode,
+    }
+
+    let config = Config::builder()
+        .override_with(TomlSource::new(
+            "[mode]\nmode = \"standalone\"\ndata_dir = \"/data\"",
+        ))
+        .override_with(TomlSource::new("port = 8080"))
+        .try_build()
+        .expect("Sources without the enum's key don't provide it");
+    assert_eq!(
+        config.mode,
+        Mode::Standalone {
+            data_dir: "/data".to_owned()
+        }
+    );
+
+    let err = Config::builder()
+        .override_with(TomlSource::new("port = 8080\n[mode]\ndata_dir = \"/data\""))
+        .try_build()
+        .expect_err("Only a flattened enum's tag may be missing");
+    let cause = std::error::Error::source(&err).unwrap().to_string();
+    assert!(cause.contains("missing field `mode`"), "{cause}");
+}
+
+#[test]
+fn generic_tagged_enum() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    #[confik(forward_serde(tag = "kind", rename_all = "snake_case", bound = "T: Configuration"))]
+    enum Store<T>
+    where
+        T: Configuration,
+    {
+        Memory { capacity: T },
+        Disk { path: String },
+    }
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        port: u16,
+        #[confik(flatten)]
+        store: Store<u64>,
+    }
+
+    let config = Config::builder()
+        .override_with(TomlSource::new("kind = \"memory\"\ncapacity = 64"))
+        .override_with(TomlSource::new("port = 8080"))
+        .try_build()
+        .expect("Generic enums can be flattened too");
+
+    assert_eq!(
+        config,
+        Config {
+            port: 8080,
+            store: Store::Memory { capacity: 64 },
+        }
+    );
+}
@@ -0,0 +1,156 @@
+use std::{collections::HashMap, io::Write as _, path::PathBuf};
+
+use confik::{Configuration, Error, FileSource, TomlSource};
+use tempfile::NamedTempFile;
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    database: Database,
+    #[confik(default)]
+    labels: HashMap<String, String>,
+    #[confik(default)]
+    peers: Vec<String>,
+}
+
+fn toml_file(contents: &str) -> (NamedTempFile, PathBuf) {
+    let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+    writeln!(file, "{contents}").unwrap();
+    let path = file.path().to_owned();
+    (file, path)
+}
+
+#[test]
+fn same_key_in_two_files() {
+    let (_host_file, host_path) = toml_file("[database]\nhost = \"localhost\"\nport = 5432");
+    let (_port_file, port_path) = toml_file("[database]\nport = 5433");
+
+    let config = Config::builder()
+        .override_with(FileSource::new(&host_path))
+        .override_with(FileSource::new(&port_path))
+        .try_build()
+        .expect("Conflicts are resolved by priority by default");
+    assert_eq!(config.database.port, 5433);
+
+    let err = Config::builder()
+        .override_with(FileSource::new(&host_path))
+        .override_with(FileSource::new(&port_path))
+        .forbid_conflicts()
+        .try_build()
+        .expect_err("Both files set the port");
+
+    let Error::Conflict(conflict, source, other_source) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(
+        conflict.to_string(),
+        "Found values for path `database.port` in more than one source"
+    );
+    assert!(source.contains(&*port_path.to_string_lossy()), "{source}");
+    assert!(
+        other_source.contains(&*host_path.to_string_lossy()),
+        "{other_source}"
+    );
+}
+
+#[test]
+fn disjoint_sources() {
+    let (_host_file, host_path) =
+        toml_file("[database]\nhost = \"localhost\"\n[labels]\na = \"1\"");
+    let (_port_file, port_path) = toml_file("[database]\nport = 5432\n[labels]\nb = \"2\"");
+
+    let config = Config::builder()
+        .override_with(FileSource::new(&host_path))
+        .override_with(FileSource::new(&port_path))
+        .override_with(TomlSource::new("peers = [\"10.0.0.1\"]"))
+        .forbid_conflicts()
+        .try_build()
+        .expect("No value is set twice");
+
+    assert_eq!(
+        config,
+        Config {
+            database: Database {
+                host: "localhost".to_owned(),
+                port: 5432,
+            },
+            labels: HashMap::from([
+                ("a".to_owned(), "1".to_owned()),
+                ("b".to_owned(), "2".to_owned()),
+            ]),
+            peers: vec!["10.0.0.1".to_owned()],
+        }
+    );
+}
+
+#[test]
+fn containers() {
+    let base = "[database]\nhost = \"localhost\"\nport = 5432";
+
+    let err = Config::builder()
+        .override_with(TomlSource::new(format!("{base}\n[labels]\na = \"1\"")))
+        .override_with(TomlSource::new("[labels]\na = \"2\""))
+        .forbid_conflicts()
+        .try_build()
+        .expect_err("Both sources set the same map key");
+    assert!(
+        matches!(&err, Error::Conflict(conflict, ..) if conflict.to_string().contains("`labels.a`")),
+        "unexpected error: {err:?}"
+    );
+
+    // Lists are replaced whole, so even different items conflict.
+    let err = Config::builder()
+        .override_with(TomlSource::new(format!("peers = [\"10.0.0.1\"]\n{base}")))
+        .override_with(TomlSource::new("peers = [\"10.0.0.2\"]"))
+        .forbid_conflicts()
+        .try_build()
+        .expect_err("Both sources set the list");
+    assert!(
+        matches!(&err, Error::Conflict(conflict, ..) if conflict.to_string().contains("`peers`")),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn enum_variants() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    enum Backend {
+        Disk { path: String, size: Option<u64> },
+        Memory,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        backend: Backend,
+    }
+
+    let config = Config::builder()
+        .override_with(TomlSource::new("[backend.Disk]\npath = \"/data\""))
+        .override_with(TomlSource::new("[backend.Disk]\nsize = 10"))
+        .forbid_conflicts()
+        .try_build()
+        .expect("The fields of the same variant are merged");
+    assert_eq!(
+        config.backend,
+        Backend::Disk {
+            path: "/data".to_owned(),
+            size: Some(10),
+        }
+    );
+
+    let err = Config::builder()
+        .override_with(TomlSource::new("[backend.Disk]\npath = \"/data\""))
+        .override_with(TomlSource::new("backend = \"Memory\""))
+        .forbid_conflicts()
+        .try_build()
+        .expect_err("Different variants are never merged");
+    assert!(
+        matches!(&err, Error::Conflict(conflict, ..) if conflict.to_string().contains("`backend`")),
+        "unexpected error: {err:?}"
+    );
+}
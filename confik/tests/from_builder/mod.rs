@@ -0,0 +1,135 @@
+use confik::{Configuration, Error, TomlSource};
+
+/// The previous version of `Config`, before `addr` was renamed to `address`.
+#[derive(Debug, Configuration)]
+struct OldConfig {
+    addr: String,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+#[confik(from_builder(ty = OldConfig, with = migrate))]
+struct Config {
+    address: String,
+    port: u16,
+}
+
+fn migrate(old: OldConfig) -> Config {
+    Config {
+        address: old.addr,
+        port: old.port,
+    }
+}
+
+#[test]
+fn current_shape() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("address = \"localhost\"\nport = 8080"))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            address: "localhost".to_owned(),
+            port: 8080,
+        }
+    );
+}
+
+#[test]
+fn previous_shape_is_migrated() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("addr = \"localhost\"\nport = 8080"))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            address: "localhost".to_owned(),
+            port: 8080,
+        }
+    );
+}
+
+#[test]
+fn current_shape_takes_precedence() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(
+            "addr = \"old\"\naddress = \"new\"\nport = 8080",
+        ))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(config.address, "new");
+}
+
+#[test]
+fn neither_shape_reports_current_error() {
+    let err = Config::builder()
+        .override_with(TomlSource::new("port = 8080"))
+        .try_build()
+        .unwrap_err();
+
+    assert!(matches!(err, Error::MissingValue(_)));
+    assert_eq!(err.to_string(), "Missing value for path `address`");
+}
+
+#[cfg(feature = "env")]
+mod env {
+    use std::collections::HashMap;
+
+    use confik::{Configuration, EnvSource};
+
+    use super::Config;
+
+    fn env(vars: &[(&str, &str)]) -> EnvSource<'static> {
+        EnvSource::from_map(
+            vars.iter()
+                .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn current_shape() {
+        let config = Config::builder()
+            .override_with(env(&[("ADDRESS", "localhost"), ("PORT", "8080")]))
+            .try_build()
+            .unwrap();
+
+        assert_eq!(config.address, "localhost");
+        assert_eq!(config.port, 8080);
+    }
+
+    #[test]
+    fn previous_shape_is_migrated() {
+        let config = Config::builder()
+            .override_with(env(&[("ADDR", "localhost"), ("PORT", "8080")]))
+            .try_build()
+            .unwrap();
+
+        assert_eq!(config.address, "localhost");
+        assert_eq!(config.port, 8080);
+    }
+
+    /// Nested, the data is buffered to be deserialized as both versions, losing the type hints
+    /// that `EnvSource` needs to read anything but strings.
+    #[test]
+    fn nested_requires_self_describing_source() {
+        #[derive(Debug, Configuration)]
+        struct Parent {
+            #[allow(dead_code)]
+            server: Config,
+        }
+
+        Parent::builder()
+            .override_with(env(&[
+                ("SERVER__ADDRESS", "localhost"),
+                ("SERVER__PORT", "8080"),
+            ]))
+            .try_build()
+            .expect_err("Nested ports are buffered as strings");
+    }
+}
@@ -0,0 +1,63 @@
+use confik::{Configuration, Error, JsonSource, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+#[confik(from_repr, gen_from_str, forward_serde(rename_all = "lowercase"))]
+#[repr(u8)]
+enum LogLevel {
+    Warn = 1,
+    Info,
+    Debug,
+    // Out of order, so that the builder's undefined variant would collide if it was numbered
+    // after this one.
+    Trace = 0,
+}
+
+#[derive(Debug, Configuration)]
+struct Config {
+    level: LogLevel,
+}
+
+fn build_json(json: &str) -> Result<Config, Error> {
+    Config::builder()
+        .override_with(JsonSource::new(json))
+        .try_build()
+}
+
+#[test]
+fn from_integer_in_json() {
+    let config = build_json(r#"{ "level": 2 }"#).unwrap();
+    assert_eq!(config.level, LogLevel::Info);
+
+    let config = build_json(r#"{ "level": 0 }"#).unwrap();
+    assert_eq!(config.level, LogLevel::Trace);
+}
+
+#[test]
+fn from_name_in_toml() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(r#"level = "debug""#))
+        .try_build()
+        .unwrap();
+    assert_eq!(config.level, LogLevel::Debug);
+
+    let config = build_json(r#"{ "level": "warn" }"#).unwrap();
+    assert_eq!(config.level, LogLevel::Warn);
+}
+
+#[test]
+fn unknown_discriminant() {
+    let err = build_json(r#"{ "level": 4 }"#).unwrap_err();
+    let source = std::error::Error::source(&err).unwrap().to_string();
+    assert!(
+        source.contains("invalid value: integer `4`, expected the discriminant of a variant"),
+        "{source}"
+    );
+
+    build_json(r#"{ "level": -1 }"#).unwrap_err();
+}
+
+#[test]
+fn names_still_parse() {
+    assert_eq!("trace".parse(), Ok(LogLevel::Trace));
+    assert!("0".parse::<LogLevel>().is_err());
+}
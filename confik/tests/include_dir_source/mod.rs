@@ -0,0 +1,43 @@
+use confik::{Configuration, IncludeDirSource, TomlSource};
+use include_dir::{include_dir, Dir};
+
+static CONFIG: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/include_dir_source/config");
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    host: String,
+    port: u16,
+}
+
+#[test]
+fn later_files_override_earlier_files() {
+    let config = Config::builder()
+        .override_with(IncludeDirSource::new(&CONFIG))
+        .try_build()
+        .expect("Embedded files contain all fields");
+
+    assert_eq!(
+        config,
+        Config {
+            host: "localhost".to_owned(),
+            port: 8080,
+        }
+    );
+}
+
+#[test]
+fn external_sources_override_embedded_defaults() {
+    let config = Config::builder()
+        .override_with(IncludeDirSource::new(&CONFIG))
+        .override_with(TomlSource::new("host = \"example.com\""))
+        .try_build()
+        .expect("Embedded files contain all fields");
+
+    assert_eq!(
+        config,
+        Config {
+            host: "example.com".to_owned(),
+            port: 8080,
+        }
+    );
+}
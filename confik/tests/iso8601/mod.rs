@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    #[confik(iso8601)]
+    timeout: Duration,
+
+    #[confik(iso8601, default = Duration::from_secs(30))]
+    idle: Duration,
+}
+
+#[test]
+fn parsed() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(r#"timeout = "PT1H30M""#))
+        .try_build()
+        .expect("Failed to parse ISO 8601 duration");
+
+    assert_eq!(
+        config,
+        Config {
+            timeout: Duration::from_secs(5400),
+            idle: Duration::from_secs(30),
+        }
+    );
+}
+
+#[test]
+fn later_source_wins() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(r#"timeout = "PT1M""#))
+        .override_with(TomlSource::new(r#"timeout = "PT0.5S""#))
+        .try_build()
+        .expect("Failed to parse ISO 8601 duration");
+
+    assert_eq!(config.timeout, Duration::from_millis(500));
+}
+
+#[test]
+fn invalid() {
+    let err = Config::builder()
+        .override_with(TomlSource::new(r#"timeout = "1h30m""#))
+        .try_build()
+        .expect_err("Parsed an invalid ISO 8601 duration");
+
+    assert!(
+        format!("{err:?}").contains("invalid ISO 8601 duration `1h30m`"),
+        "Unexpected error: {err:?}"
+    );
+}
@@ -0,0 +1,67 @@
+use confik::{Configuration, Error, JsonLinesSource};
+use indoc::indoc;
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Output {
+    path: String,
+    #[confik(default)]
+    rotate: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    level: String,
+    output: Output,
+    #[confik(default)]
+    fields: Vec<String>,
+}
+
+#[test]
+fn lines_are_merged_in_order() {
+    let config = Config::builder()
+        .override_with(JsonLinesSource::new(indoc! {r#"
+            {"level": "info", "output": {"path": "/var/log/app.log"}}
+
+            {"level": "debug", "fields": ["request_id"]}
+            {"output": {"rotate": true}}
+        "#}))
+        .try_build()
+        .expect("The lines combine into a full config");
+
+    assert_eq!(
+        config,
+        Config {
+            level: "debug".to_owned(),
+            output: Output {
+                path: "/var/log/app.log".to_owned(),
+                rotate: true,
+            },
+            fields: vec!["request_id".to_owned()],
+        }
+    );
+}
+
+#[test]
+fn malformed_line() {
+    let err = Config::builder()
+        .override_with(JsonLinesSource::new(indoc! {r#"
+            {"level": "info", "output": {"path": "/var/log/app.log"}}
+            {"level": "debug",
+        "#}))
+        .try_build()
+        .expect_err("The second line is malformed");
+
+    let Error::Source(source_err, _) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(source_err.to_string(), "Invalid JSON on line 2");
+    assert!(source_err.source().is_some());
+}
+
+#[test]
+fn empty() {
+    Config::builder()
+        .override_with(JsonLinesSource::new("\n\n"))
+        .try_build()
+        .expect_err("No line provides any values");
+}
@@ -156,3 +156,154 @@ mod btreemap {
 
     create_tests_for! { BTreeMap<String, TwoVals> }
 }
+
+mod numeric_keys {
+    use std::collections::HashMap;
+
+    use confik::Configuration;
+
+    #[derive(Debug, Configuration, PartialEq, Eq)]
+    struct Target {
+        val: HashMap<u32, usize>,
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml() {
+        let target = Target::builder()
+            .override_with(confik::TomlSource::new("[val]\n1 = 10\n2 = 20"))
+            .try_build()
+            .expect("Failed to build numeric keys from string keys");
+
+        assert_eq!(target.val, HashMap::from([(1, 10), (2, 20)]));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_invalid_key() {
+        let err = Target::builder()
+            .override_with(confik::TomlSource::new("[val]\none = 10"))
+            .try_build()
+            .expect_err("Built numeric keys from a non-numeric key");
+
+        assert!(
+            std::error::Error::source(&err)
+                .expect("Missing error source")
+                .to_string()
+                .contains(r#"invalid value: string "one", expected u32"#),
+            "Unexpected error: {err:?}"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        let target = Target::builder()
+            .override_with(confik::JsonSource::new(
+                r#"{ "val": { "1": 10, "2": 20 } }"#,
+            ))
+            .try_build()
+            .expect("Failed to build numeric keys from JSON");
+
+        assert_eq!(target.val, HashMap::from([(1, 10), (2, 20)]));
+    }
+
+    #[cfg(all(feature = "json", feature = "toml"))]
+    #[test]
+    fn merged() {
+        let target = Target::builder()
+            .override_with(confik::TomlSource::new("[val]\n1 = 10\n2 = 20"))
+            .override_with(confik::JsonSource::new(r#"{ "val": { "2": 30 } }"#))
+            .try_build()
+            .expect("Failed to merge numeric keys");
+
+        assert_eq!(target.val, HashMap::from([(1, 10), (2, 30)]));
+    }
+}
+
+mod enum_keys {
+    use std::{collections::BTreeMap, fmt};
+
+    use confik::Configuration;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum LogLevel {
+        Debug,
+        Info,
+        Warn,
+    }
+
+    /// Matches the serde names, so that error paths show the key as it was given.
+    impl fmt::Display for LogLevel {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self {
+                Self::Debug => "debug",
+                Self::Info => "info",
+                Self::Warn => "warn",
+            })
+        }
+    }
+
+    #[derive(Debug, Configuration, PartialEq, Eq)]
+    struct Sink {
+        path: String,
+    }
+
+    #[derive(Debug, Configuration, PartialEq, Eq)]
+    struct Target {
+        sinks: BTreeMap<LogLevel, Sink>,
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml() {
+        let target = Target::builder()
+            .override_with(confik::TomlSource::new(
+                "[sinks.debug]\npath = \"/tmp/debug.log\"\n[sinks.warn]\npath = \"/var/log/warn.log\"",
+            ))
+            .try_build()
+            .expect("Failed to build enum keys from a TOML table");
+
+        assert_eq!(
+            target.sinks,
+            BTreeMap::from([
+                (
+                    LogLevel::Debug,
+                    Sink {
+                        path: "/tmp/debug.log".to_owned()
+                    }
+                ),
+                (
+                    LogLevel::Warn,
+                    Sink {
+                        path: "/var/log/warn.log".to_owned()
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_missing_value_path() {
+        let err = Target::builder()
+            .override_with(confik::TomlSource::new("[sinks.info]"))
+            .try_build()
+            .expect_err("The sink's path is missing");
+
+        assert_eq!(err.to_string(), "Missing value for path `sinks.info.path`");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_unknown_key() {
+        Target::builder()
+            .override_with(confik::TomlSource::new(
+                "[sinks.trace]\npath = \"/tmp/trace.log\"",
+            ))
+            .try_build()
+            .expect_err("Built an enum key from an unknown variant");
+    }
+}
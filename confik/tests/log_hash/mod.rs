@@ -0,0 +1,66 @@
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, Configuration)]
+#[confik(redact_display)]
+struct Config {
+    user: String,
+
+    #[confik(secret, log_hash)]
+    api_key: String,
+
+    #[confik(secret, log_hash)]
+    fallback_key: Option<String>,
+
+    #[confik(secret)]
+    password: String,
+}
+
+fn build(api_key: &str) -> Config {
+    Config::builder()
+        .override_with(
+            TomlSource::new(format!(
+                "user = \"admin\"\napi_key = {api_key:?}\npassword = \"hunter2\""
+            ))
+            .allow_secrets(),
+        )
+        .try_build()
+        .unwrap()
+}
+
+#[test]
+fn secret_is_hashed() {
+    let config = build("hunter2");
+
+    assert_eq!(
+        config.to_string(),
+        r#"Config { user: "admin", api_key: "[redacted sha256:f52fbd32]", fallback_key: None, password: "[redacted]" }"#,
+    );
+}
+
+#[test]
+fn hash_is_stable() {
+    assert_eq!(build("hunter2").to_string(), build("hunter2").to_string());
+
+    let other = build("correct horse").to_string();
+    assert!(other.contains("[redacted sha256:4104d36f]"), "{other}");
+    assert!(!other.contains("correct horse"), "secret leaked: {other}");
+}
+
+#[test]
+fn optional_secret_is_hashed() {
+    let config = Config::builder()
+        .override_with(
+            TomlSource::new(
+                "user = \"admin\"\napi_key = \"a\"\nfallback_key = \"hunter2\"\npassword = \"b\"",
+            )
+            .allow_secrets(),
+        )
+        .try_build()
+        .unwrap();
+
+    let display = config.to_string();
+    assert!(
+        display.contains(r#"fallback_key: "[redacted sha256:f52fbd32]""#),
+        "{display}"
+    );
+}
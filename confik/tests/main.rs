@@ -1,16 +1,70 @@
 mod array;
+mod assert_addressable;
+#[cfg(feature = "toml")]
+mod build_timings;
+#[cfg(all(feature = "tracing", feature = "toml"))]
+mod build_tracing;
 #[cfg(all(feature = "common", feature = "toml"))]
 mod common;
 mod complex_enums;
+#[cfg(feature = "toml")]
+mod default_lazy;
 mod defaulting_containers;
+#[cfg(all(feature = "json", feature = "toml"))]
+mod dump_effective;
+mod empty_sources;
+#[cfg(feature = "env")]
+mod env_source;
+#[cfg(all(feature = "expand_tilde", feature = "toml"))]
+mod expand_tilde;
+#[cfg(feature = "toml")]
+mod flatten;
+#[cfg(feature = "toml")]
+mod forbid_conflicts;
+#[cfg(feature = "toml")]
+mod from_builder;
+#[cfg(all(feature = "json", feature = "toml"))]
+mod from_repr;
+#[cfg(all(feature = "include_dir", feature = "json", feature = "toml"))]
+mod include_dir_source;
+#[cfg(feature = "toml")]
+mod iso8601;
+#[cfg(feature = "json")]
+mod json_lines_source;
 mod keyed_containers;
+#[cfg(all(feature = "log_hash", feature = "toml"))]
+mod log_hash;
+#[cfg(feature = "toml")]
+mod merge_into;
+#[cfg(feature = "toml")]
+mod merge_key;
+#[cfg(feature = "toml")]
+mod one_of;
 mod option_builder;
+#[cfg(feature = "toml")]
+mod precedence;
+#[cfg(all(feature = "json", feature = "toml"))]
+mod raw_string;
+#[cfg(all(windows, feature = "winreg"))]
+mod registry_source;
+#[cfg(all(feature = "rfc3339", feature = "toml"))]
+mod rfc3339;
 mod secret;
 mod secret_option;
+#[cfg(feature = "toml")]
+mod serde_default;
 mod serde_forward;
 mod singly_nested_tests;
 mod third_party;
+#[cfg(feature = "toml")]
+mod to_builder;
+#[cfg(feature = "toml")]
+mod try_from;
 mod unkeyed_containers;
+#[cfg(all(feature = "url", feature = "toml"))]
+mod url_scheme;
+#[cfg(feature = "toml")]
+mod with_base;
 
 use assert_matches::assert_matches;
 use confik::{ConfigBuilder, Configuration, Error};
@@ -36,9 +90,104 @@ fn check_no_source_fails() {
     );
 }
 
+/// A source with no data, which fails if it is asked for any.
+#[derive(Debug)]
+struct AbsentSource;
+
+impl confik::Source for AbsentSource {
+    fn provide<T: confik::ConfigurationBuilder>(
+        &self,
+    ) -> Result<T, Box<dyn std::error::Error + Sync + Send>> {
+        Err("Absent sources have no data".into())
+    }
+
+    fn provide_optional<T: confik::ConfigurationBuilder>(
+        &self,
+    ) -> Result<Option<T>, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(None)
+    }
+}
+
+/// A source whose data can't be found, e.g. a remote config that hasn't been published yet.
+#[derive(Debug)]
+struct NotFoundSource {
+    optional: bool,
+    error_kind: std::io::ErrorKind,
+}
+
+impl confik::Source for NotFoundSource {
+    fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    fn provide<T: confik::ConfigurationBuilder>(
+        &self,
+    ) -> Result<T, Box<dyn std::error::Error + Sync + Send>> {
+        Err(std::io::Error::from(self.error_kind).into())
+    }
+}
+
+#[test]
+fn check_optional_sources_not_found_are_skipped() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Defaulted {
+        #[confik(default = 1usize)]
+        a: usize,
+    }
+
+    let build = |optional, error_kind| {
+        ConfigBuilder::<Defaulted>::default()
+            .override_with(NotFoundSource {
+                optional,
+                error_kind,
+            })
+            .try_build()
+    };
+
+    assert_eq!(
+        build(true, std::io::ErrorKind::NotFound).expect("Optional sources may be missing"),
+        Defaulted { a: 1 }
+    );
+    assert_matches!(
+        build(false, std::io::ErrorKind::NotFound),
+        Err(Error::Source(..)),
+        "Required sources must be found"
+    );
+    assert_matches!(
+        build(true, std::io::ErrorKind::PermissionDenied),
+        Err(Error::Source(..)),
+        "Optional sources only skip missing data"
+    );
+}
+
+#[test]
+fn check_absent_sources_are_skipped() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Defaulted {
+        #[confik(default = 1usize)]
+        a: usize,
+    }
+
+    assert_eq!(
+        ConfigBuilder::<Defaulted>::default()
+            .override_with(AbsentSource)
+            .try_build()
+            .expect("Skipped sources provide nothing"),
+        Defaulted { a: 1 }
+    );
+
+    assert_matches!(
+        ConfigBuilder::<Target>::default()
+            .override_with(AbsentSource)
+            .try_build(),
+        Err(Error::MissingValue(path)) if path.to_string().contains('a')
+    );
+}
+
 #[cfg(feature = "json")]
 mod json {
-    use confik::{ConfigBuilder, JsonSource};
+    use assert_matches::assert_matches;
+    use confik::{ConfigBuilder, Error, JsonSource};
 
     use crate::{Target, TargetEnum};
 
@@ -55,13 +204,41 @@ mod json {
             }
         );
     }
+
+    #[test]
+    fn check_json_bytes() {
+        assert_eq!(
+            ConfigBuilder::<Target>::default()
+                .override_with(JsonSource::from_bytes(
+                    br#"{"a": 1, "b": "First"}"#.as_slice()
+                ))
+                .try_build()
+                .expect("JSON deserialization from bytes should succeed"),
+            Target {
+                a: 1,
+                b: TargetEnum::First,
+            }
+        );
+
+        let err = ConfigBuilder::<Target>::default()
+            .override_with(JsonSource::from_bytes(
+                b"{\"a\": 1, \"b\": \"\xff\"}".to_vec(),
+            ))
+            .try_build()
+            .expect_err("JSON must be valid UTF-8");
+        assert_matches!(
+            err,
+            Error::Source(err, _) if err.is::<std::str::Utf8Error>()
+        );
+    }
 }
 
 #[cfg(feature = "toml")]
 mod toml {
     use std::time::Duration;
 
-    use confik::{ConfigBuilder, TomlSource};
+    use assert_matches::assert_matches;
+    use confik::{ConfigBuilder, Error, TomlSource};
     use confik_macros::Configuration;
 
     use crate::{Target, TargetEnum};
@@ -80,6 +257,259 @@ mod toml {
         );
     }
 
+    #[test]
+    fn check_toml_bytes() {
+        assert_eq!(
+            ConfigBuilder::<Target>::default()
+                .override_with(TomlSource::from_bytes(b"a = 2\nb = \"Second\"".to_vec()))
+                .try_build()
+                .expect("Toml deserialization from bytes should succeed"),
+            Target {
+                a: 2,
+                b: TargetEnum::Second,
+            }
+        );
+
+        let err = ConfigBuilder::<Target>::default()
+            .override_with(TomlSource::from_bytes(b"a = 2\nb = \"\xff\"".as_slice()))
+            .try_build()
+            .expect_err("Toml must be valid UTF-8");
+        assert_matches!(
+            err,
+            Error::Source(err, _) if err.is::<std::str::Utf8Error>()
+        );
+    }
+
+    #[test]
+    fn try_build_or_default() {
+        #[derive(Debug, Default, PartialEq, Eq, Configuration)]
+        struct Config {
+            #[confik(default = 1usize)]
+            a: usize,
+            b: usize,
+        }
+
+        let config = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("b = \"invalid\""))
+            .try_build_or_default();
+        assert_eq!(config, Config::default());
+
+        let config = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("b = 2"))
+            .try_build_or_default();
+        assert_eq!(config, Config { a: 1, b: 2 });
+    }
+
+    #[test]
+    fn override_with_builder() {
+        #[derive(Debug, PartialEq, Eq, Configuration)]
+        #[confik(gen_setters)]
+        struct Config {
+            a: usize,
+            #[confik(secret)]
+            b: usize,
+        }
+
+        let defaults = <Config as confik::Configuration>::Builder::default()
+            .with_a(1usize)
+            .with_b(2usize);
+
+        let config = ConfigBuilder::<Config>::default()
+            .override_with_builder(defaults)
+            .override_with(TomlSource::new("a = 3"))
+            .try_build()
+            .expect("Builders may contain secrets");
+        assert_eq!(config, Config { a: 3, b: 2 });
+    }
+
+    #[test]
+    fn optional_source_falls_back() {
+        use confik::{FileSource, OptionalSource};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let invalid = dir.path().join("invalid.toml");
+        std::fs::write(&invalid, "a = ").unwrap();
+
+        let config = ConfigBuilder::<Target>::default()
+            .override_with(TomlSource::new("a = 1\nb = \"First\""))
+            .override_with(OptionalSource::new(FileSource::new(
+                dir.path().join("missing.toml"),
+            )))
+            .override_with(OptionalSource::new(FileSource::new(&invalid)))
+            .try_build()
+            .expect("Failing optional sources are skipped");
+        assert_eq!(
+            config,
+            Target {
+                a: 1,
+                b: TargetEnum::First,
+            }
+        );
+
+        let err = ConfigBuilder::<Target>::default()
+            .override_with(TomlSource::new("a = 1\nb = \"First\""))
+            .override_with(FileSource::new(&invalid))
+            .try_build()
+            .expect_err("Without the wrapper, the source fails the build");
+        assert!(matches!(err, confik::Error::Source(..)), "{err}");
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn missing_file_source_is_skipped() {
+        use confik::FileSource;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let invalid = dir.path().join("invalid.toml");
+        std::fs::write(&invalid, "a = ").unwrap();
+
+        let config = ConfigBuilder::<Target>::default()
+            .override_with(TomlSource::new("a = 1\nb = \"First\""))
+            .override_with(FileSource::new(dir.path().join("missing.toml")).allow_missing())
+            .try_build()
+            .expect("Missing optional files are skipped");
+        assert_eq!(
+            config,
+            Target {
+                a: 1,
+                b: TargetEnum::First,
+            }
+        );
+
+        let err = ConfigBuilder::<Target>::default()
+            .override_with(TomlSource::new("a = 1\nb = \"First\""))
+            .override_with(FileSource::new(&invalid).allow_missing())
+            .try_build()
+            .expect_err("Invalid optional files still fail the build");
+        assert!(matches!(err, confik::Error::Source(..)), "{err}");
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn default_is_lazy() {
+        use std::cell::Cell;
+
+        thread_local! {
+            static EVALUATED: Cell<usize> = const { Cell::new(0) };
+        }
+
+        fn counted_default() -> usize {
+            EVALUATED.with(|evaluated| evaluated.set(evaluated.get() + 1));
+            7
+        }
+
+        #[derive(Debug, PartialEq, Eq, Configuration)]
+        enum Mode {
+            Fixed {
+                #[confik(default = counted_default())]
+                value: usize,
+            },
+        }
+
+        #[derive(Debug, PartialEq, Eq, Configuration)]
+        struct Config {
+            #[confik(default = counted_default())]
+            a: usize,
+            #[confik(secret, default = counted_default())]
+            b: usize,
+            mode: Mode,
+        }
+
+        let config = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("a = 1\nb = 2\nmode.Fixed.value = 3").allow_secrets())
+            .try_build()
+            .expect("All values are present");
+        assert_eq!(
+            config,
+            Config {
+                a: 1,
+                b: 2,
+                mode: Mode::Fixed { value: 3 },
+            }
+        );
+        assert_eq!(
+            EVALUATED.with(Cell::get),
+            0,
+            "Defaults are not evaluated when values are present"
+        );
+
+        let config = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("mode.Fixed = {}"))
+            .try_build()
+            .expect("All values are defaulted");
+        assert_eq!(config.mode, Mode::Fixed { value: 7 });
+        assert_eq!(EVALUATED.with(Cell::get), 3);
+    }
+
+    #[test]
+    fn try_build_lenient() {
+        #[derive(Debug, PartialEq, Eq, Configuration)]
+        struct Config {
+            a: usize,
+            #[confik(secret)]
+            b: usize,
+        }
+
+        let (config, errors) = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("a = 1\nb = 2").allow_secrets())
+            .override_with(TomlSource::new("a = "))
+            .override_with(TomlSource::new("b = 3"))
+            .try_build_lenient();
+        assert_eq!(config, Some(Config { a: 1, b: 2 }));
+        assert_matches!(
+            &errors[..],
+            [Error::UnexpectedSecret(..), Error::Source(..)],
+            "Non-fatal errors are collected from highest priority"
+        );
+
+        let (config, errors) = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("a = 1"))
+            .override_with(TomlSource::new("a = "))
+            .try_build_lenient();
+        assert_eq!(config, None);
+        assert_matches!(
+            &errors[..],
+            [Error::Source(..), Error::MissingValue(path)] if path.to_string().contains('b'),
+            "Missing values are fatal"
+        );
+
+        let (config, errors) = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("a = 1\nb = 2").allow_secrets())
+            .override_with(TomlSource::new("a = "))
+            .override_with(TomlSource::new("a = 3"))
+            .forbid_conflicts()
+            .try_build_lenient();
+        assert_eq!(config, None);
+        assert_matches!(
+            &errors[..],
+            [Error::Source(..), Error::Conflict(..)],
+            "Conflicts between the remaining sources are fatal"
+        );
+    }
+
+    #[test]
+    fn try_build_lenient_with_base() {
+        #[derive(Debug, PartialEq, Eq, Configuration)]
+        #[confik(gen_to_builder)]
+        struct Config {
+            a: usize,
+            b: usize,
+        }
+
+        let base = Config { a: 1, b: 2 };
+
+        let (config, errors) = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("a = "))
+            .override_with(TomlSource::new("a = 3"))
+            .with_base(&base)
+            .forbid_conflicts()
+            .try_build_lenient();
+        assert_eq!(config, Some(Config { a: 3, b: 2 }));
+        assert_matches!(&errors[..], [Error::Source(..)]);
+    }
+
     #[test]
     fn from_humantime() {
         #[derive(Debug, PartialEq, Eq, Configuration)]
@@ -107,6 +537,22 @@ mod toml {
 
         use crate::{Target, TargetEnum};
 
+        #[test]
+        fn check_absent_source_between() {
+            assert_eq!(
+                ConfigBuilder::<Target>::default()
+                    .override_with(TomlSource::new("a = 1"))
+                    .override_with(crate::AbsentSource)
+                    .override_with(JsonSource::new(r#"{"b": "Second"}"#))
+                    .try_build()
+                    .expect("The other sources are combined"),
+                Target {
+                    a: 1,
+                    b: TargetEnum::Second,
+                }
+            );
+        }
+
         #[test]
         fn check_multi_source() {
             assert_eq!(
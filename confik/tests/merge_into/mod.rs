@@ -0,0 +1,95 @@
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+#[confik(gen_to_builder)]
+struct Limits {
+    max_connections: usize,
+    #[confik(default = 30u64)]
+    timeout_secs: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+#[confik(gen_to_builder)]
+struct Config {
+    log_level: String,
+    limits: Limits,
+}
+
+fn initial() -> Config {
+    Config::builder()
+        .override_with(TomlSource::new(
+            "log_level = \"info\"\n[limits]\nmax_connections = 100",
+        ))
+        .try_build()
+        .unwrap()
+}
+
+#[test]
+fn unspecified_fields_keep_runtime_values() {
+    let mut config = initial();
+
+    // Changed at runtime, including a field which has a default.
+    config.limits.max_connections = 250;
+    config.limits.timeout_secs = 5;
+
+    Config::builder()
+        .override_with(TomlSource::new("log_level = \"debug\""))
+        .merge_into(&mut config)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            log_level: "debug".to_owned(),
+            limits: Limits {
+                max_connections: 250,
+                timeout_secs: 5,
+            },
+        }
+    );
+}
+
+#[test]
+fn provided_nested_fields_are_replaced() {
+    let mut config = initial();
+    config.limits.timeout_secs = 5;
+
+    Config::builder()
+        .override_with(TomlSource::new("[limits]\nmax_connections = 10"))
+        .merge_into(&mut config)
+        .unwrap();
+
+    assert_eq!(config.log_level, "info");
+    assert_eq!(
+        config.limits,
+        Limits {
+            max_connections: 10,
+            timeout_secs: 5,
+        }
+    );
+}
+
+#[test]
+fn existing_unchanged_on_error() {
+    let mut config = initial();
+
+    Config::builder()
+        .override_with(TomlSource::new("log_level = 1"))
+        .merge_into(&mut config)
+        .unwrap_err();
+
+    assert_eq!(config, initial());
+}
+
+#[test]
+fn existing_is_not_a_conflict() {
+    let mut config = initial();
+
+    Config::builder()
+        .override_with(TomlSource::new("log_level = \"debug\""))
+        .forbid_conflicts()
+        .merge_into(&mut config)
+        .unwrap();
+
+    assert_eq!(config.log_level, "debug");
+}
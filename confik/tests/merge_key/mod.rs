@@ -0,0 +1,118 @@
+use confik::{ConfigBuilder, Configuration, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Server {
+    name: String,
+    port: u16,
+    #[confik(default)]
+    weight: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    #[confik(merge_key = "name")]
+    servers: Vec<Server>,
+}
+
+#[test]
+fn merges_by_key() {
+    let config = ConfigBuilder::<Config>::default()
+        .override_with(TomlSource::new(
+            r#"
+            [[servers]]
+            name = "primary"
+            port = 80
+
+            [[servers]]
+            name = "secondary"
+            port = 81
+            weight = 1
+            "#,
+        ))
+        .override_with(TomlSource::new(
+            r#"
+            [[servers]]
+            name = "tertiary"
+            port = 82
+
+            [[servers]]
+            name = "primary"
+            port = 8080
+            "#,
+        ))
+        .try_build()
+        .expect("Failed to merge servers by name");
+
+    assert_eq!(
+        config.servers,
+        [
+            Server {
+                name: "primary".to_owned(),
+                port: 8080,
+                weight: 0,
+            },
+            Server {
+                name: "secondary".to_owned(),
+                port: 81,
+                weight: 1,
+            },
+            Server {
+                name: "tertiary".to_owned(),
+                port: 82,
+                weight: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn partial_items_are_completed() {
+    let config = ConfigBuilder::<Config>::default()
+        .override_with(TomlSource::new(
+            r#"
+            [[servers]]
+            name = "primary"
+            port = 80
+            "#,
+        ))
+        .override_with(TomlSource::new(
+            r#"
+            [[servers]]
+            name = "primary"
+            weight = 5
+            "#,
+        ))
+        .try_build()
+        .expect("Failed to merge partial server");
+
+    assert_eq!(
+        config.servers,
+        [Server {
+            name: "primary".to_owned(),
+            port: 80,
+            weight: 5,
+        }]
+    );
+}
+
+#[test]
+fn missing_fields_are_reported_with_index() {
+    let err = ConfigBuilder::<Config>::default()
+        .override_with(TomlSource::new(
+            r#"
+            [[servers]]
+            name = "primary"
+            port = 80
+
+            [[servers]]
+            name = "secondary"
+            "#,
+        ))
+        .try_build()
+        .expect_err("Missing port should be reported");
+
+    assert!(
+        err.to_string().contains("servers.1.port"),
+        "unexpected error: {err}"
+    );
+}
@@ -0,0 +1,106 @@
+use confik::{Configuration, Error, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct S3 {
+    bucket: String,
+    region: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Gcs {
+    bucket: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct LocalDisk {
+    path: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+#[confik(one_of)]
+enum Storage {
+    S3(S3),
+    Gcs(Gcs),
+    LocalDisk(LocalDisk),
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    storage: Storage,
+}
+
+#[test]
+fn one_section() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("[storage.local_disk]\npath = \"/data\""))
+        .try_build()
+        .expect("Exactly one section is given");
+    assert_eq!(
+        config.storage,
+        Storage::LocalDisk(LocalDisk {
+            path: "/data".to_owned(),
+        })
+    );
+
+    // A section may be spread across sources.
+    let config = Config::builder()
+        .override_with(TomlSource::new("[storage.s3]\nbucket = \"assets\""))
+        .override_with(TomlSource::new("[storage.s3]\nregion = \"eu-west-1\""))
+        .try_build()
+        .expect("Exactly one section is given");
+    assert_eq!(
+        config.storage,
+        Storage::S3(S3 {
+            bucket: "assets".to_owned(),
+            region: Some("eu-west-1".to_owned()),
+        })
+    );
+}
+
+#[test]
+fn no_sections() {
+    let err = Config::builder()
+        .override_with(TomlSource::new("[storage]"))
+        .try_build()
+        .expect_err("No section is given");
+
+    let Error::NotOneOf(not_one_of) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert!(not_one_of.found().is_empty());
+    assert_eq!(
+        err.to_string(),
+        "Expected exactly one of `s3`, `gcs` or `local_disk` for path `storage`, but found none"
+    );
+}
+
+#[test]
+fn multiple_sections() {
+    let err = Config::builder()
+        .override_with(TomlSource::new("[storage.s3]\nbucket = \"assets\""))
+        .override_with(TomlSource::new("[storage.gcs]\nbucket = \"assets\""))
+        .try_build()
+        .expect_err("Two sections are given");
+
+    let Error::NotOneOf(not_one_of) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(not_one_of.found(), ["s3", "gcs"]);
+    assert_eq!(
+        err.to_string(),
+        "Expected exactly one of `s3`, `gcs` or `local_disk` for path `storage`, but found `s3` and `gcs`"
+    );
+}
+
+#[test]
+fn incomplete_section() {
+    let err = Config::builder()
+        .override_with(TomlSource::new("[storage.s3]\nregion = \"eu-west-1\""))
+        .try_build()
+        .expect_err("The section is missing its bucket");
+
+    assert!(
+        matches!(&err, Error::MissingValue(path) if path.to_string().contains("storage.s3.bucket")),
+        "unexpected error: {err:?}"
+    );
+}
@@ -80,6 +80,81 @@ mod json {
     }
 }
 
+mod defaults {
+    use confik::Configuration;
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        #[confik(default)]
+        default: Option<usize>,
+        #[confik(default = None)]
+        none: Option<usize>,
+        #[confik(default = Some(5))]
+        some: Option<usize>,
+        #[confik(default = 6_usize)]
+        into_some: Option<usize>,
+    }
+
+    #[test]
+    fn unspecified() {
+        let config = Config::builder()
+            .try_build()
+            .expect("Every field has a default");
+        assert_eq!(
+            config,
+            Config {
+                default: None,
+                none: None,
+                some: Some(5),
+                into_some: Some(6),
+            }
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn explicit_value() {
+        use confik::TomlSource;
+
+        let config = Config::builder()
+            .override_with(TomlSource::new(
+                "default = 1\nnone = 2\nsome = 3\ninto_some = 4",
+            ))
+            .try_build()
+            .expect("Explicit values are used over defaults");
+        assert_eq!(
+            config,
+            Config {
+                default: Some(1),
+                none: Some(2),
+                some: Some(3),
+                into_some: Some(4),
+            }
+        );
+    }
+
+    /// Toml doesn't have a null type
+    #[cfg(feature = "json")]
+    #[test]
+    fn explicit_null() {
+        use confik::JsonSource;
+
+        let config = Config::builder()
+            .override_with(JsonSource::new(r#"{ "some": null, "into_some": null }"#))
+            .try_build()
+            .expect("Explicit nulls are used over defaults");
+        assert_eq!(
+            config,
+            Config {
+                default: None,
+                none: None,
+                some: None,
+                into_some: None,
+            }
+        );
+    }
+}
+
 mod complex {
     use confik::Configuration;
 
@@ -197,3 +272,114 @@ mod complex {
         }
     }
 }
+
+#[cfg(feature = "json")]
+mod vec {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, JsonSource};
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        list: Option<Vec<usize>>,
+    }
+
+    const UNSPECIFIED: &str = "{}";
+    const NULL: &str = r#"{ "list": null }"#;
+    const EMPTY: &str = r#"{ "list": [] }"#;
+    const FILLED: &str = r#"{ "list": [1, 2] }"#;
+
+    fn build(lower: &str, higher: &str) -> Option<Vec<usize>> {
+        Config::builder()
+            .override_with(JsonSource::new(lower))
+            .override_with(JsonSource::new(higher))
+            .try_build()
+            .expect("Optional lists always build")
+            .list
+    }
+
+    #[test]
+    fn single_source() {
+        assert_eq!(build(UNSPECIFIED, UNSPECIFIED), None);
+        assert_eq!(build(UNSPECIFIED, NULL), None);
+        assert_eq!(build(UNSPECIFIED, EMPTY), Some(vec![]));
+        assert_eq!(build(UNSPECIFIED, FILLED), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn unspecified_never_overrides() {
+        assert_eq!(build(NULL, UNSPECIFIED), None);
+        assert_eq!(build(EMPTY, UNSPECIFIED), Some(vec![]));
+        assert_eq!(build(FILLED, UNSPECIFIED), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn explicit_null_overrides() {
+        assert_eq!(build(EMPTY, NULL), None);
+        assert_eq!(build(FILLED, NULL), None);
+    }
+
+    #[test]
+    fn explicit_empty_overrides() {
+        assert_eq!(build(NULL, EMPTY), Some(vec![]));
+        assert_eq!(build(FILLED, EMPTY), Some(vec![]));
+    }
+
+    #[test]
+    fn explicit_list_replaces() {
+        assert_eq!(build(NULL, FILLED), Some(vec![1, 2]));
+        assert_eq!(build(EMPTY, FILLED), Some(vec![1, 2]));
+        assert_eq!(
+            build(r#"{ "list": [3, 4, 5] }"#, FILLED),
+            Some(vec![1, 2]),
+            "Lists are replaced, not merged item-by-item"
+        );
+    }
+
+    #[test]
+    fn default_only_when_unspecified() {
+        #[derive(Debug, PartialEq, Eq, Configuration)]
+        struct Defaulted {
+            #[confik(default = Some(vec![7]))]
+            list: Option<Vec<usize>>,
+        }
+
+        let build = |source| {
+            Defaulted::builder()
+                .override_with(JsonSource::new(source))
+                .try_build()
+                .expect("Optional lists always build")
+                .list
+        };
+
+        assert_eq!(build(UNSPECIFIED), Some(vec![7]));
+        assert_eq!(build(NULL), None);
+        assert_eq!(build(EMPTY), Some(vec![]));
+        assert_eq!(build(FILLED), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn explicit_values_are_secret_data() {
+        #[derive(Debug, Configuration)]
+        struct Secret {
+            #[confik(secret)]
+            #[allow(dead_code)]
+            list: Option<Vec<usize>>,
+        }
+
+        // An explicit `null` is data, but not a secret
+        for source in [UNSPECIFIED, NULL] {
+            Secret::builder()
+                .override_with(JsonSource::new(source))
+                .try_build()
+                .expect("No secret data provided");
+        }
+
+        for source in [EMPTY, FILLED] {
+            let err = Secret::builder()
+                .override_with(JsonSource::new(source))
+                .try_build()
+                .expect_err("Explicit values are data");
+            assert_matches!(err, Error::UnexpectedSecret(..), "{source}");
+        }
+    }
+}
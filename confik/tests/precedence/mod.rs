@@ -0,0 +1,90 @@
+use confik::{ConfigBuilder, Configuration, FileSource, Precedence, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    host: String,
+    port: u16,
+}
+
+fn build(precedence: Option<Precedence>) -> Config {
+    let mut builder = Config::builder();
+    if let Some(precedence) = precedence {
+        builder.precedence(precedence);
+    }
+
+    builder
+        .override_with(TomlSource::new("host = \"localhost\"\nport = 80"))
+        .override_with(TomlSource::new("port = 8080"))
+        .try_build()
+        .expect("Both sources together provide every value")
+}
+
+#[test]
+fn last_wins_by_default() {
+    let expected = Config {
+        host: "localhost".to_owned(),
+        port: 8080,
+    };
+
+    assert_eq!(build(None), expected);
+    assert_eq!(build(Some(Precedence::LastWins)), expected);
+}
+
+#[test]
+fn first_wins() {
+    assert_eq!(
+        build(Some(Precedence::FirstWins)),
+        Config {
+            host: "localhost".to_owned(),
+            port: 80,
+        }
+    );
+}
+
+#[test]
+fn source_labels_follow_precedence() {
+    let mut builder = ConfigBuilder::<Config>::default();
+    builder
+        .override_with(FileSource::new("first.toml"))
+        .override_with(FileSource::new("second.toml"))
+        .precedence(Precedence::FirstWins);
+
+    assert_eq!(
+        builder.source_labels(),
+        [
+            r#"FileSource { path: "first.toml", allow_secrets: false }"#,
+            r#"FileSource { path: "second.toml", allow_secrets: false }"#,
+        ]
+    );
+}
+
+#[test]
+fn base_is_always_lowest() {
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    #[confik(gen_to_builder)]
+    struct Config {
+        host: String,
+        port: u16,
+    }
+
+    let base = Config {
+        host: "base".to_owned(),
+        port: 1,
+    };
+
+    let config = Config::builder()
+        .with_base(&base)
+        .precedence(Precedence::FirstWins)
+        .override_with(TomlSource::new("port = 80"))
+        .override_with(TomlSource::new("port = 8080"))
+        .try_build()
+        .expect("The base fills in the host");
+
+    assert_eq!(
+        config,
+        Config {
+            host: "base".to_owned(),
+            port: 80,
+        }
+    );
+}
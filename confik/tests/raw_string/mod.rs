@@ -0,0 +1,58 @@
+use confik::{Configuration, JsonSource, RawString, TomlSource};
+use indoc::indoc;
+
+#[derive(Debug, Configuration)]
+struct Config {
+    script: RawString,
+    #[confik(default)]
+    plugin: RawString,
+}
+
+#[test]
+fn string_is_captured_as_is() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(indoc! {r#"
+            script = """
+            set -e
+            echo "hello"
+            """
+        "#}))
+        .try_build()
+        .expect("Failed to capture the script");
+
+    assert_eq!(config.script.as_str(), "set -e\necho \"hello\"\n");
+    assert_eq!(config.plugin.as_str(), "");
+}
+
+#[test]
+fn other_values_are_reserialized() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(indoc! {r#"
+            script = 1
+
+            [plugin]
+            name = "fmt"
+            args = ["--check", true]
+        "#}))
+        .try_build()
+        .expect("Failed to capture the plugin config");
+
+    assert_eq!(config.script.as_str(), "1");
+    assert_eq!(
+        config.plugin.as_str(),
+        r#"{"args":["--check",true],"name":"fmt"}"#
+    );
+}
+
+#[test]
+fn not_merged() {
+    let config = Config::builder()
+        .override_with(JsonSource::new(
+            r#"{ "script": "", "plugin": { "name": "fmt", "args": [] } }"#,
+        ))
+        .override_with(TomlSource::new("plugin = { args = [\"--check\"] }"))
+        .try_build()
+        .expect("Failed to capture the plugin config");
+
+    assert_eq!(config.plugin.as_str(), r#"{"args":["--check"]}"#);
+}
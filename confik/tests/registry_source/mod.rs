@@ -0,0 +1,77 @@
+use confik::{Configuration, RegistrySource};
+use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Limits {
+    max_connections: u32,
+    max_body_bytes: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    host: String,
+    port: u16,
+    peers: Vec<String>,
+    limits: Limits,
+}
+
+/// A key under `HKEY_CURRENT_USER`, deleted when dropped.
+struct TempKey(String);
+
+impl TempKey {
+    fn new() -> (Self, RegKey) {
+        let path = format!(r"Software\confik-test-{}", std::process::id());
+        let (key, _) = RegKey::predef(HKEY_CURRENT_USER)
+            .create_subkey(&path)
+            .unwrap();
+        (Self(path), key)
+    }
+}
+
+impl Drop for TempKey {
+    fn drop(&mut self) {
+        let _ = RegKey::predef(HKEY_CURRENT_USER).delete_subkey_all(&self.0);
+    }
+}
+
+#[test]
+fn values_and_subkeys() {
+    let (temp, key) = TempKey::new();
+    key.set_value("host", &"localhost").unwrap();
+    key.set_value("port", &8080_u32).unwrap();
+    key.set_value("peers", &vec!["a".to_owned(), "b".to_owned()])
+        .unwrap();
+
+    let (limits, _) = key.create_subkey("limits").unwrap();
+    limits.set_value("max_connections", &100_u32).unwrap();
+    limits.set_value("max_body_bytes", &(1_u64 << 40)).unwrap();
+
+    let config = Config::builder()
+        .override_with(RegistrySource::new(HKEY_CURRENT_USER, &temp.0))
+        .try_build()
+        .expect("Every value is set in the registry");
+
+    assert_eq!(
+        config,
+        Config {
+            host: "localhost".to_owned(),
+            port: 8080,
+            peers: vec!["a".to_owned(), "b".to_owned()],
+            limits: Limits {
+                max_connections: 100,
+                max_body_bytes: 1 << 40,
+            },
+        }
+    );
+}
+
+#[test]
+fn missing_key() {
+    Config::builder()
+        .override_with(RegistrySource::new(
+            HKEY_CURRENT_USER,
+            r"Software\confik-test-missing",
+        ))
+        .try_build()
+        .expect_err("The key doesn't exist");
+}
@@ -0,0 +1,47 @@
+use std::time::{Duration, SystemTime};
+
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    #[confik(rfc3339)]
+    starts_at: SystemTime,
+}
+
+fn build(toml: &str) -> Result<Config, confik::Error> {
+    Config::builder()
+        .override_with(TomlSource::new(toml))
+        .try_build()
+}
+
+#[test]
+fn parsed() {
+    let config = build(r#"starts_at = "2024-01-01T00:00:00Z""#).expect("Valid timestamp");
+
+    assert_eq!(
+        config.starts_at,
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_704_067_200)
+    );
+}
+
+#[test]
+fn fractional_seconds() {
+    let config = build(r#"starts_at = "1970-01-01T00:00:01.5Z""#).expect("Valid timestamp");
+
+    assert_eq!(
+        config.starts_at,
+        SystemTime::UNIX_EPOCH + Duration::from_millis(1_500)
+    );
+}
+
+#[test]
+fn invalid() {
+    for timestamp in ["2024-01-01", "2024-01-01T00:00:00+01:00", "yesterday"] {
+        let err = build(&format!("starts_at = {timestamp:?}")).expect_err("Invalid timestamp");
+
+        assert!(
+            format!("{err:?}").contains(&format!("invalid RFC 3339 timestamp `{timestamp}`")),
+            "Unexpected error: {err:?}"
+        );
+    }
+}
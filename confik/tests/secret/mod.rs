@@ -43,12 +43,323 @@ struct MaybeSecretArray {
     seq: [MaybeSecret; 2],
 }
 
+#[test]
+fn secret_paths() {
+    #[derive(Configuration)]
+    #[allow(unused)]
+    enum Auth {
+        Token(#[confik(secret)] String),
+        Basic {
+            user: String,
+            password: PartiallySecret,
+        },
+    }
+
+    #[derive(Configuration)]
+    #[allow(unused)]
+    struct Config {
+        not_secret: NotSecret,
+        #[confik(secret)]
+        whole: PartiallySecret,
+        optional: Option<MaybeSecret>,
+        auth: Auth,
+    }
+
+    assert_eq!(Num::secret_paths(), Vec::<String>::new());
+    assert_eq!(PartiallySecret::secret_paths(), ["secret"]);
+    assert_eq!(NotSecret::secret_paths(), ["public.secret"]);
+    assert_eq!(MaybeSecretVec::secret_paths(), ["seq.*.secret"]);
+    assert_eq!(MaybeSecretArray::secret_paths(), ["seq.*.secret"]);
+    assert_eq!(
+        Config::secret_paths(),
+        [
+            "not_secret.public.secret",
+            "whole",
+            "optional.secret",
+            "auth.Token",
+            "auth.Basic.password.secret",
+        ]
+    );
+}
+
+#[test]
+fn secret_paths_follow_enum_representation() {
+    #[derive(Configuration)]
+    #[confik(forward_serde(tag = "type"))]
+    #[allow(unused)]
+    enum Internal {
+        Basic {
+            user: String,
+            #[confik(secret)]
+            password: String,
+        },
+        Nested(PartiallySecret),
+    }
+
+    #[derive(Configuration)]
+    #[confik(forward_serde(tag = "type", content = "data"))]
+    #[allow(unused)]
+    enum Adjacent {
+        Basic {
+            #[confik(secret)]
+            password: String,
+        },
+        Token(#[confik(secret)] String),
+    }
+
+    #[derive(Configuration)]
+    #[confik(forward_serde(untagged))]
+    #[allow(unused)]
+    enum Untagged {
+        Basic {
+            #[confik(secret)]
+            password: String,
+        },
+        Token(#[confik(secret)] String),
+    }
+
+    #[derive(Configuration)]
+    #[allow(unused)]
+    struct Config {
+        internal: Internal,
+        adjacent: Adjacent,
+        untagged: Untagged,
+    }
+
+    assert_eq!(Internal::secret_paths(), ["password", "secret"]);
+    assert_eq!(Adjacent::secret_paths(), ["data.password", "data"]);
+    // An inline secret is the whole value.
+    assert_eq!(Untagged::secret_paths(), ["password", ""]);
+    assert_eq!(
+        Config::secret_paths(),
+        [
+            "internal.password",
+            "internal.secret",
+            "adjacent.data.password",
+            "adjacent.data",
+            "untagged.password",
+            "untagged",
+        ]
+    );
+}
+
+#[test]
+fn secret_paths_of_unknown_layout() {
+    #[derive(Deserialize)]
+    struct Raw {}
+
+    #[derive(Configuration)]
+    #[confik(forward_serde(from = "Raw"))]
+    #[allow(unused)]
+    enum Converted {
+        Basic {
+            user: String,
+            #[confik(secret)]
+            password: String,
+        },
+    }
+
+    impl From<Raw> for <Converted as Configuration>::Builder {
+        fn from(_: Raw) -> Self {
+            Self::default()
+        }
+    }
+
+    #[derive(Configuration)]
+    #[allow(unused)]
+    struct Config {
+        converted: Converted,
+    }
+
+    // The data could have any layout, so it's secret as a whole.
+    assert_eq!(Converted::secret_paths(), [""]);
+    assert_eq!(Config::secret_paths(), ["converted"]);
+}
+
+#[test]
+fn secret_paths_of_recursive_types() {
+    #[derive(Configuration)]
+    #[allow(unused)]
+    struct Node {
+        name: String,
+        children: Vec<Node>,
+    }
+
+    #[derive(Configuration)]
+    #[allow(unused)]
+    struct SecretNode {
+        #[confik(secret)]
+        token: String,
+        children: Vec<SecretNode>,
+        named: std::collections::BTreeMap<String, SecretNode>,
+    }
+
+    #[derive(Configuration)]
+    #[allow(unused)]
+    struct Config {
+        tree: Node,
+        secret_tree: SecretNode,
+    }
+
+    assert_eq!(Node::secret_paths(), Vec::<String>::new());
+
+    // Where a type contains itself, it's secret as a whole.
+    assert_eq!(
+        SecretNode::secret_paths(),
+        ["token", "children.*", "named.*"]
+    );
+    assert_eq!(
+        Config::secret_paths(),
+        [
+            "secret_tree.token",
+            "secret_tree.children.*",
+            "secret_tree.named.*",
+        ]
+    );
+}
+
+#[test]
+fn secret_paths_follow_renames() {
+    #[derive(Configuration)]
+    #[confik(forward_serde(rename_all = "kebab-case"))]
+    #[allow(unused)]
+    struct Database {
+        #[confik(secret)]
+        admin_password: String,
+    }
+
+    #[derive(Configuration)]
+    #[confik(forward_serde(rename_all = "snake_case", rename_all_fields = "camelCase"))]
+    #[allow(unused)]
+    enum Auth {
+        BearerToken {
+            #[confik(secret)]
+            token_value: String,
+        },
+        #[confik(forward_serde(rename = "basic"))]
+        Basic {
+            #[confik(secret, forward_serde(rename(deserialize = "pass")))]
+            password: String,
+        },
+    }
+
+    #[derive(Configuration)]
+    #[confik(forward_serde(rename_all = "camelCase"))]
+    #[allow(unused)]
+    struct Config {
+        main_database: Database,
+        #[confik(flatten)]
+        flattened: Database,
+        auth: Auth,
+        #[confik(secret)]
+        r#type: String,
+    }
+
+    assert_eq!(
+        Config::secret_paths(),
+        [
+            "mainDatabase.admin-password",
+            "admin-password",
+            "auth.bearer_token.tokenValue",
+            "auth.basic.pass",
+            "type",
+        ]
+    );
+}
+
+#[test]
+fn redact_display() {
+    #[derive(Debug, Configuration)]
+    #[confik(redact_display)]
+    struct Config {
+        host: String,
+        #[confik(secret)]
+        password: String,
+        port: u16,
+    }
+
+    let config = Config {
+        host: "localhost".to_owned(),
+        password: "hunter2".to_owned(),
+        port: 5432,
+    };
+
+    let formatted = config.to_string();
+    assert!(!formatted.contains("hunter2"), "secret leaked: {formatted}");
+    assert_eq!(
+        formatted,
+        r#"Config { host: "localhost", password: "[redacted]", port: 5432 }"#
+    );
+
+    let formatted = format!("{config:#}");
+    assert!(!formatted.contains("hunter2"), "secret leaked: {formatted}");
+    assert!(formatted.contains("localhost"));
+}
+
+#[test]
+fn redact_display_nested() {
+    #[derive(Debug, Configuration)]
+    #[confik(redact_display)]
+    struct Db {
+        user: String,
+        #[confik(secret)]
+        password: String,
+    }
+
+    #[derive(Debug, Configuration)]
+    struct Unredacted {
+        #[confik(secret)]
+        token: String,
+    }
+
+    #[derive(Debug, Configuration)]
+    #[confik(redact_display)]
+    struct Outer {
+        name: String,
+        db: Db,
+        replicas: Vec<Db>,
+        other: Unredacted,
+        #[confik(from = Unredacted)]
+        converted: String,
+    }
+
+    impl From<Unredacted> for String {
+        fn from(value: Unredacted) -> Self {
+            value.token
+        }
+    }
+
+    let db = || Db {
+        user: "u".to_owned(),
+        password: "hunter2".to_owned(),
+    };
+    let outer = Outer {
+        name: "n".to_owned(),
+        db: db(),
+        replicas: vec![db()],
+        other: Unredacted {
+            token: "hunter3".to_owned(),
+        },
+        converted: "hunter4".to_owned(),
+    };
+
+    // Nested types are redacted by their own `redact_display`, or entirely if they have none.
+    assert_eq!(
+        outer.to_string(),
+        r#"Outer { name: "n", db: Db { user: "u", password: "[redacted]" }, replicas: "[redacted]", other: "[redacted]", converted: "[redacted]" }"#
+    );
+    let formatted = format!("{outer:#}");
+    assert!(!formatted.contains("hunter"), "secret leaked: {formatted}");
+    assert!(formatted.contains(r#"user: "u""#), "{formatted}");
+}
+
 #[cfg(feature = "json")]
 mod json {
     use assert_matches::assert_matches;
-    use confik::{ConfigBuilder, Error, JsonSource};
+    use confik::{ConfigBuilder, Configuration, Error, JsonSource, SecretOption};
+    use serde::Deserialize;
 
-    use super::NotSecret;
+    use super::{MaybeSecret, NotSecret};
 
     #[test]
     fn check_json_is_not_secret() {
@@ -62,6 +373,75 @@ mod json {
             Error::UnexpectedSecret(path, _) if path.to_string().contains("public.secret")
         );
     }
+
+    #[test]
+    fn check_json_explicit_null_secret_is_not_secret() {
+        let target = ConfigBuilder::<MaybeSecret>::default()
+            .override_with(JsonSource::new(r#"{"secret": null}"#))
+            .try_build()
+            .expect("An explicit `null` is not a secret");
+        assert_eq!(target.secret, None);
+
+        let target = ConfigBuilder::<MaybeSecret>::default()
+            .override_with(JsonSource::new(r#"{"secret": "hunter2"}"#))
+            .try_build()
+            .expect_err("JSON deserialization is not a secret source");
+        assert_matches!(
+            &target,
+            Error::UnexpectedSecret(path, _) if path.to_string().contains("secret")
+        );
+    }
+
+    #[test]
+    fn check_json_explicit_null_secret_overrides_default() {
+        #[derive(Debug, Configuration)]
+        struct Config {
+            #[confik(secret, default = Some("default".to_owned()))]
+            token: Option<String>,
+        }
+
+        let target = ConfigBuilder::<Config>::default()
+            .override_with(JsonSource::new(r#"{"token": null}"#))
+            .try_build()
+            .expect("An explicit `null` is not a secret");
+        assert_eq!(target.token, None);
+
+        let target = ConfigBuilder::<Config>::default()
+            .try_build()
+            .expect("Defaulted");
+        assert_eq!(target.token.as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn check_json_explicit_null_secret_option_is_not_secret() {
+        #[derive(Debug, PartialEq, Eq, Deserialize)]
+        #[serde(transparent)]
+        struct SecretString(String);
+
+        impl Configuration for SecretString {
+            type Builder = SecretOption<Self>;
+        }
+
+        #[derive(Debug, Configuration)]
+        struct Config {
+            token: Option<SecretString>,
+        }
+
+        let target = ConfigBuilder::<Config>::default()
+            .override_with(JsonSource::new(r#"{"token": null}"#))
+            .try_build()
+            .expect("An explicit `null` is not a secret");
+        assert_eq!(target.token, None);
+
+        let target = ConfigBuilder::<Config>::default()
+            .override_with(JsonSource::new(r#"{"token": "hunter2"}"#))
+            .try_build()
+            .expect_err("JSON deserialization is not a secret source");
+        assert_matches!(
+            &target,
+            Error::UnexpectedSecret(path, _) if path.to_string().contains("token")
+        );
+    }
 }
 
 #[cfg(feature = "toml")]
@@ -127,6 +507,80 @@ mod toml {
         check_secret_error_seq_propagation::<MaybeSecretArray>("seq.1.secret");
     }
 
+    #[test]
+    fn check_secret_error_enum_propagation() {
+        use confik::Error;
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        enum Mode {
+            Plain,
+            Encrypted { key: usize },
+        }
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Config {
+            public: usize,
+            #[confik(secret)]
+            mode: Mode,
+        }
+
+        for (source, expected_path) in [
+            (
+                "public = 1
+mode = \"Plain\"",
+                "mode",
+            ),
+            (
+                "public = 1
+mode = { Encrypted = { key = 1 } }",
+                "mode",
+            ),
+        ] {
+            let target = ConfigBuilder::<Config>::default()
+                .override_with(TomlSource::new(source))
+                .try_build()
+                .expect_err("Toml deserialization is not a secret source");
+
+            assert_matches!(
+                &target,
+                Error::UnexpectedSecret(path, _) if path.to_string().contains(&format!("`{expected_path}`")),
+                "source: {source}"
+            );
+        }
+    }
+
+    #[test]
+    fn check_secret_error_seq_of_enums_propagation() {
+        use confik::Error;
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        enum Auth {
+            Anonymous,
+            Token(#[confik(secret)] String),
+        }
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Config {
+            seq: Vec<Auth>,
+        }
+
+        let target = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new(
+                r#"seq = ["Anonymous", "Anonymous", { Token = "secret" }]"#,
+            ))
+            .try_build()
+            .expect_err("Toml deserialization is not a secret source");
+
+        assert_matches!(
+            &target,
+            Error::UnexpectedSecret(path, _) if path.to_string().contains("`seq.2.Token.0`")
+        );
+    }
+
     fn check_secret_error_map_propagation<M>()
     where
         M: for<'a> Deserialize<'a> + Configuration + Debug,
@@ -149,6 +603,98 @@ mod toml {
         );
     }
 
+    #[test]
+    fn check_secret_classification_in_error() {
+        use confik::Error;
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Database {
+            host: String,
+            #[confik(secret = "credential")]
+            password: String,
+        }
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Config {
+            database: Database,
+        }
+
+        let target = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new(indoc! {r#"
+                [database]
+                host = "localhost"
+                password = "hunter2"
+            "#}))
+            .try_build()
+            .expect_err("Toml deserialization is not a secret source");
+
+        assert_matches!(
+            &target,
+            Error::UnexpectedSecret(secret, _) if secret.classification() == Some("credential")
+                && secret.to_string() == "Found credential secret at path `database.password`"
+        );
+    }
+
+    #[test]
+    fn check_secrets_allowed_under_path() {
+        use confik::Error;
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Credentials {
+            #[confik(secret)]
+            password: String,
+        }
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Config {
+            credentials: Credentials,
+            database: Credentials,
+        }
+
+        let config = ConfigBuilder::<Config>::default()
+            .override_with(
+                TomlSource::new(indoc! {r#"
+                    [credentials]
+                    password = "hunter2"
+                "#})
+                .allow_secrets_under("credentials"),
+            )
+            .override_with(
+                TomlSource::new(indoc! {r#"
+                    [database]
+                    password = "hunter3"
+                "#})
+                .allow_secrets(),
+            )
+            .try_build()
+            .expect("Secrets are allowed under `credentials`");
+        assert_eq!(config.credentials.password, "hunter2");
+        assert_eq!(config.database.password, "hunter3");
+
+        let target = ConfigBuilder::<Config>::default()
+            .override_with(
+                TomlSource::new(indoc! {r#"
+                    [credentials]
+                    password = "hunter2"
+
+                    [database]
+                    password = "hunter3"
+                "#})
+                .allow_secrets_under("credentials"),
+            )
+            .try_build()
+            .expect_err("Secrets are only allowed under `credentials`");
+
+        assert_matches!(
+            &target,
+            Error::UnexpectedSecret(path, _) if path.to_string().contains("`database.password`")
+        );
+    }
+
     #[test]
     fn check_secret_error_hashmap_propagation() {
         check_secret_error_map_propagation::<HashMap<String, MaybeSecret, DeterministicHash>>();
@@ -158,6 +704,104 @@ mod toml {
     fn check_secret_error_btreemap_propagation() {
         check_secret_error_map_propagation::<BTreeMap<String, MaybeSecret>>();
     }
+
+    /// A secret field containing further secrets is reported at the path of any further secret the
+    /// source gives, or at its own path if the source gives only non-secret data, with its own
+    /// classification either way.
+    fn check_secret_error_nested_secret_container<T>(entries: &[(&str, &str)])
+    where
+        T: Configuration + Debug,
+    {
+        use confik::Error;
+
+        for (entry, path) in entries {
+            let target = ConfigBuilder::<T>::default()
+                .override_with(TomlSource::new(*entry))
+                .try_build()
+                .expect_err("Toml deserialization is not a secret source");
+
+            assert_matches!(
+                &target,
+                Error::UnexpectedSecret(secret, _)
+                    if secret.to_string() == format!("Found credential secret at path `{path}`"),
+                "source: {entry}, error: {target:?}"
+            );
+        }
+
+        ConfigBuilder::<T>::default()
+            .override_with(TomlSource::new(entries[0].0).allow_secrets())
+            .try_build()
+            .expect("Secrets are allowed");
+    }
+
+    #[derive(Debug, Configuration)]
+    #[allow(unused)]
+    struct SecretLeaf {
+        name: Option<String>,
+        #[confik(secret = "token")]
+        token: Option<String>,
+    }
+
+    #[test]
+    fn check_secret_error_nested_secret_vec() {
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Config {
+            #[confik(secret = "credential")]
+            leaves: Vec<SecretLeaf>,
+        }
+
+        check_secret_error_nested_secret_container::<Config>(&[
+            (
+                "[[leaves]]\nname = \"a\"\n[[leaves]]\ntoken = \"b\"",
+                "leaves.1.token",
+            ),
+            ("[[leaves]]\ntoken = \"b\"", "leaves.0.token"),
+            ("[[leaves]]\nname = \"a\"", "leaves"),
+        ]);
+    }
+
+    #[test]
+    fn check_secret_error_nested_secret_hashmap() {
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Config {
+            #[confik(secret = "credential")]
+            leaves: HashMap<String, SecretLeaf, DeterministicHash>,
+        }
+
+        check_secret_error_nested_secret_container::<Config>(&[
+            (
+                "[leaves.a]\nname = \"a\"\n[leaves.b]\ntoken = \"b\"",
+                "leaves.b.token",
+            ),
+            ("[leaves.b]\ntoken = \"b\"", "leaves.b.token"),
+            ("[leaves.a]\nname = \"a\"", "leaves"),
+        ]);
+    }
+
+    #[test]
+    fn check_secret_error_nested_secret_keeps_classification() {
+        use confik::Error;
+
+        #[derive(Debug, Configuration)]
+        #[allow(unused)]
+        struct Config {
+            #[confik(secret)]
+            leaves: Vec<SecretLeaf>,
+        }
+
+        let target = ConfigBuilder::<Config>::default()
+            .override_with(TomlSource::new("[[leaves]]\ntoken = \"b\""))
+            .try_build()
+            .expect_err("Toml deserialization is not a secret source");
+
+        assert_matches!(
+            &target,
+            Error::UnexpectedSecret(secret, _)
+                if secret.to_string() == "Found token secret at path `leaves.0.token`"
+        );
+    }
 }
 
 #[cfg(feature = "env")]
@@ -239,6 +883,61 @@ mod env {
     }
 }
 
+mod defaults {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error};
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct Config {
+        #[confik(secret, default = "hunter2")]
+        password: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Configuration)]
+    struct PlaceholderConfig {
+        #[confik(secret, default = "hunter2", default_is_placeholder)]
+        password: String,
+    }
+
+    /// A default isn't provided by any source, so is never checked for being in an insecure one.
+    #[test]
+    fn default_is_used() {
+        let config = Config::builder()
+            .try_build()
+            .expect("The secret has a default");
+        assert_eq!(config.password, "hunter2");
+    }
+
+    #[test]
+    fn placeholder_default_is_missing() {
+        let err = PlaceholderConfig::builder()
+            .try_build()
+            .expect_err("A placeholder default isn't used");
+        assert_matches!(
+            err,
+            Error::MissingValue(path) if path.to_string().contains("`password`")
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn placeholder_default_from_secret_source() {
+        use confik::TomlSource;
+
+        let config = PlaceholderConfig::builder()
+            .override_with(TomlSource::new("password = \"correct horse\"").allow_secrets())
+            .try_build()
+            .expect("The secret is provided");
+        assert_eq!(config.password, "correct horse");
+
+        let err = PlaceholderConfig::builder()
+            .override_with(TomlSource::new("password = \"correct horse\""))
+            .try_build()
+            .expect_err("The secret is in an insecure source");
+        assert_matches!(err, Error::UnexpectedSecret(..));
+    }
+}
+
 /// In order to have the `HashMap` case fail FUT-5298 determinstically,
 /// we beed to ensure the entires are ordered deterministically.
 #[derive(Debug, Default)]
@@ -0,0 +1,55 @@
+use confik::{Configuration, TomlSource};
+
+/// Gives the builder of `port`, so `None` would leave it unset.
+fn default_port() -> Option<u16> {
+    Some(8080)
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    #[confik(default = 80u16, serde_default = "default_port")]
+    port: u16,
+    host: Option<String>,
+}
+
+#[test]
+fn without_sources() {
+    // No source was read, so only the build-time default applies.
+    let config = Config::builder().try_build().unwrap();
+
+    assert_eq!(config.port, 80);
+}
+
+#[test]
+fn source_without_field() {
+    // The source doesn't contain the field, so gives the serde default.
+    let config = Config::builder()
+        .override_with(TomlSource::new("host = \"localhost\""))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(config.port, 8080);
+}
+
+#[test]
+fn source_with_field() {
+    let config = Config::builder()
+        .override_with(TomlSource::new("port = 443"))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(config.port, 443);
+}
+
+#[test]
+fn overrides_lower_priority_sources() {
+    // Each source gives the serde default when it doesn't contain the field, which then takes
+    // priority over the value from a lower priority source.
+    let config = Config::builder()
+        .override_with(TomlSource::new("port = 443"))
+        .override_with(TomlSource::new("host = \"localhost\""))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(config.port, 8080);
+}
@@ -1,6 +1,9 @@
 #[cfg(feature = "secrecy")]
 mod secrecy {
-    use confik::{Configuration, TomlSource};
+    use std::collections::HashMap;
+
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
     use indoc::indoc;
     use secrecy::{ExposeSecret as _, SecretString};
 
@@ -32,6 +35,17 @@ mod secrecy {
         assert_eq!(config.secret_string.expose_secret(), "SeriouslySecret");
     }
 
+    #[test]
+    fn secret_string_paths() {
+        #[derive(Debug, Configuration)]
+        #[allow(dead_code)]
+        struct Config {
+            secret_string: SecretString,
+        }
+
+        assert_eq!(Config::secret_paths(), ["secret_string"]);
+    }
+
     #[test]
     fn secret_string_in_field_not_marked_secret() {
         #[derive(Debug, Configuration)]
@@ -65,6 +79,54 @@ mod secrecy {
         );
         assert_eq!(config.secret_string.expose_secret(), "SeriouslySecret");
     }
+
+    #[test]
+    fn secret_string_map() {
+        #[derive(Debug, Configuration)]
+        struct Config {
+            #[confik(secret)]
+            keys: HashMap<String, SecretString>,
+        }
+
+        let toml = indoc! {r#"
+            [keys]
+            tenant1 = "SeriouslySecret"
+        "#};
+
+        let err = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .expect_err("Every value is a secret");
+        assert_matches!(
+            &err,
+            Error::UnexpectedSecret(secret, _)
+                if secret.to_string() == "Found secret at path `keys.tenant1`"
+        );
+
+        let config = Config::builder()
+            .override_with(TomlSource::new(toml).allow_secrets())
+            .try_build()
+            .unwrap();
+        assert_eq!(config.keys["tenant1"].expose_secret(), "SeriouslySecret");
+    }
+
+    /// Fails to compile if `strict_secrets` warns about any field.
+    #[deny(deprecated)]
+    mod strict_secrets {
+        use confik::Configuration;
+        use secrecy::SecretString;
+
+        #[derive(Configuration)]
+        #[confik(strict_secrets)]
+        #[allow(dead_code)]
+        struct Config {
+            #[confik(secret)]
+            api_key: SecretString,
+            #[confik(secret)]
+            fallback_api_key: Option<SecretString>,
+            name: String,
+        }
+    }
 }
 
 #[cfg(feature = "bigdecimal")]
@@ -126,3 +188,583 @@ mod bigdecimal {
         }
     }
 }
+
+#[cfg(feature = "fixed")]
+mod fixed {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
+    use fixed::types::{I32F32, U16F16};
+    use indoc::indoc;
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        rate: I32F32,
+        fee: U16F16,
+    }
+
+    #[test]
+    fn fixed() {
+        let toml = indoc! {r#"
+            rate = "-1.125"
+            fee = "0.5"
+        "#};
+
+        let config = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.rate, I32F32::from_num(-1.125));
+        assert_eq!(config.fee, U16F16::from_num(0.5));
+    }
+
+    #[test]
+    fn fixed_invalid() {
+        let toml = indoc! {r#"
+            rate = "one"
+            fee = "0.5"
+        "#};
+
+        let err = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .expect_err("Not a number");
+
+        assert_matches!(err, Error::Source(..));
+    }
+}
+
+#[cfg(feature = "http")]
+mod http {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
+    use http::Uri;
+    use indoc::indoc;
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        uri: Uri,
+    }
+
+    #[test]
+    fn uri() {
+        let toml = indoc! {r#"
+            uri = "https://example.com:8080/api?query=1"
+        "#};
+
+        let config = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.uri.scheme_str(), Some("https"));
+        assert_eq!(config.uri.port_u16(), Some(8080));
+        assert_eq!(config.uri.path(), "/api");
+    }
+
+    #[test]
+    fn uri_later_source_wins() {
+        let config = Config::builder()
+            .override_with(TomlSource::new(r#"uri = "http://first.example""#))
+            .override_with(TomlSource::new(r#"uri = "http://second.example""#))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.uri.host(), Some("second.example"));
+    }
+
+    #[test]
+    fn invalid_uri() {
+        let err = Config::builder()
+            .override_with(TomlSource::new(r#"uri = "http://[::1""#))
+            .try_build()
+            .unwrap_err();
+
+        assert_matches!(&err, Error::TryInto(_));
+        assert_eq!(err.to_string(), "Failed try_into for path `uri`");
+    }
+
+    #[test]
+    fn missing_uri() {
+        let err = Config::builder().try_build().unwrap_err();
+
+        assert_matches!(&err, Error::MissingValue(_));
+        assert_eq!(err.to_string(), "Missing value for path `uri`");
+    }
+}
+
+#[cfg(feature = "mime")]
+mod mime {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
+    use indoc::indoc;
+    use mime::Mime;
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        content_type: Mime,
+        accept: Vec<Mime>,
+    }
+
+    #[test]
+    fn mime() {
+        let toml = indoc! {r#"
+            content_type = "application/json; charset=utf-8"
+            accept = ["text/html", "image/*"]
+        "#};
+
+        let config = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.content_type.essence_str(), "application/json");
+        assert_eq!(
+            config.content_type.get_param(mime::CHARSET),
+            Some(mime::UTF_8)
+        );
+        assert_eq!(config.accept, [mime::TEXT_HTML, mime::IMAGE_STAR]);
+    }
+
+    #[test]
+    fn invalid_mime() {
+        let toml = indoc! {r#"
+            content_type = "not a mime"
+            accept = []
+        "#};
+
+        let err = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .unwrap_err();
+
+        assert_matches!(&err, Error::TryInto(_));
+        assert_eq!(err.to_string(), "Failed try_into for path `content_type`");
+    }
+}
+
+#[cfg(feature = "regex")]
+mod regex {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
+    use indoc::indoc;
+    use regex::Regex;
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        route: Regex,
+        ignored: Vec<Regex>,
+    }
+
+    #[test]
+    fn regex() {
+        let toml = indoc! {r#"
+            route = '^/users/(\d+)$'
+            ignored = ['^/health$', '\.ico$']
+        "#};
+
+        let config = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.route.as_str(), r"^/users/(\d+)$");
+        assert!(config.route.is_match("/users/42"));
+        assert!(!config.route.is_match("/users/me"));
+        assert_eq!(config.ignored.len(), 2);
+        assert!(config.ignored[1].is_match("/favicon.ico"));
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let toml = indoc! {r#"
+            route = '^/users/(\d+$'
+            ignored = []
+        "#};
+
+        let err = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .unwrap_err();
+
+        assert_matches!(&err, Error::TryInto(_));
+        assert_eq!(err.to_string(), "Failed try_into for path `route`");
+
+        let source = std::error::Error::source(&err).unwrap().to_string();
+        assert!(source.contains("unclosed group"), "{source}");
+    }
+}
+
+#[cfg(feature = "smol_str")]
+mod smol_str {
+    use confik::{Configuration, TomlSource};
+    use indoc::indoc;
+    use smol_str::SmolStr;
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        name: SmolStr,
+        #[confik(default = "localhost")]
+        host: SmolStr,
+    }
+
+    #[test]
+    fn smol_str() {
+        let toml = indoc! {r#"
+            name = "service"
+            host = "example.com"
+        "#};
+
+        let config = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.name, "service");
+        assert_eq!(config.host, "example.com");
+    }
+
+    #[test]
+    fn smol_str_default() {
+        let config = Config::builder()
+            .override_with(TomlSource::new(r#"name = "service""#))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.host, "localhost");
+    }
+}
+
+#[cfg(feature = "compact_str")]
+mod compact_str {
+    use compact_str::CompactString;
+    use confik::{Configuration, TomlSource};
+    use indoc::indoc;
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        name: CompactString,
+        #[confik(default = "localhost")]
+        host: CompactString,
+    }
+
+    #[test]
+    fn compact_str() {
+        let toml = indoc! {r#"
+            name = "service"
+            host = "example.com"
+        "#};
+
+        let config = Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.name, "service");
+        assert_eq!(config.host, "example.com");
+    }
+
+    #[test]
+    fn compact_str_default() {
+        let config = Config::builder()
+            .override_with(TomlSource::new(r#"name = "service""#))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.host, "localhost");
+    }
+}
+
+#[cfg(feature = "enumflags2")]
+mod enumflags2 {
+    use confik::{Configuration, TomlSource};
+    use enumflags2::{bitflags, BitFlags};
+    use serde::Deserialize;
+
+    #[bitflags]
+    #[repr(u8)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Permission {
+        Read,
+        Write,
+        Execute,
+    }
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        permissions: BitFlags<Permission>,
+    }
+
+    #[test]
+    fn flags_from_list() {
+        let config = Config::builder()
+            .override_with(TomlSource::new(r#"permissions = ["read", "write"]"#))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.permissions, Permission::Read | Permission::Write);
+    }
+
+    #[test]
+    fn flags_union_across_sources() {
+        let config = Config::builder()
+            .override_with(TomlSource::new(r#"permissions = ["read"]"#))
+            .override_with(TomlSource::new(r#"permissions = ["execute"]"#))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.permissions, Permission::Read | Permission::Execute);
+    }
+
+    #[test]
+    fn empty_list_is_no_flags() {
+        let config = Config::builder()
+            .override_with(TomlSource::new("permissions = []"))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert!(config.permissions.is_empty());
+    }
+
+    #[test]
+    fn unknown_flag() {
+        Config::builder()
+            .override_with(TomlSource::new(r#"permissions = ["delete"]"#))
+            .try_build()
+            .unwrap_err();
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec {
+    use arrayvec::ArrayVec;
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        ports: ArrayVec<u16, 2>,
+    }
+
+    #[test]
+    fn within_capacity() {
+        let config = Config::builder()
+            .override_with(TomlSource::new("ports = [80, 443]"))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.ports.as_slice(), [80, 443]);
+    }
+
+    #[test]
+    fn over_capacity() {
+        let err = Config::builder()
+            .override_with(TomlSource::new("ports = [80, 443, 8080]"))
+            .try_build()
+            .expect_err("Too many items should fail");
+
+        assert_matches!(err, Error::Source(..));
+    }
+}
+
+#[cfg(feature = "tinyvec")]
+mod tinyvec {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
+    use tinyvec::{ArrayVec, TinyVec};
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        ports: ArrayVec<[u16; 2]>,
+        hosts: TinyVec<[String; 1]>,
+    }
+
+    #[test]
+    fn within_capacity() {
+        let config = Config::builder()
+            .override_with(TomlSource::new(
+                r#"ports = [80, 443]
+                hosts = ["a", "b"]"#,
+            ))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.ports.as_slice(), [80, 443]);
+        assert_eq!(config.hosts.as_slice(), ["a", "b"]);
+    }
+
+    #[test]
+    fn over_capacity() {
+        let err = Config::builder()
+            .override_with(TomlSource::new(
+                r#"ports = [80, 443, 8080]
+                hosts = []"#,
+            ))
+            .try_build()
+            .expect_err("Too many items should fail");
+
+        assert_matches!(err, Error::Source(..));
+    }
+}
+
+#[cfg(feature = "json")]
+mod serde_json {
+    use confik::{Configuration, JsonSource};
+    use serde_json::{json, Value};
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        plugin: Value,
+    }
+
+    #[test]
+    fn objects_merge_across_sources() {
+        let config = Config::builder()
+            .override_with(JsonSource::new(
+                r#"{"plugin": {"name": "a", "list": [1, 2], "nested": {"x": 1, "y": 2}}}"#,
+            ))
+            .override_with(JsonSource::new(
+                r#"{"plugin": {"list": [3], "enabled": true, "nested": {"y": 3, "z": null}}}"#,
+            ))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(
+            config.plugin,
+            json!({
+                "name": "a",
+                "list": [3],
+                "enabled": true,
+                "nested": {"x": 1, "y": 3, "z": null},
+            })
+        );
+    }
+
+    #[test]
+    fn non_objects_are_replaced() {
+        let config = Config::builder()
+            .override_with(JsonSource::new(r#"{"plugin": {"name": "a"}}"#))
+            .override_with(JsonSource::new(r#"{"plugin": null}"#))
+            .try_build()
+            .expect("Failed to parse config");
+
+        assert_eq!(config.plugin, Value::Null);
+    }
+
+    #[test]
+    fn missing() {
+        Config::builder()
+            .override_with(JsonSource::new("{}"))
+            .try_build()
+            .unwrap_err();
+    }
+}
+
+#[cfg(feature = "toml")]
+mod toml {
+    use confik::{Configuration, TomlSource};
+    use indoc::indoc;
+    use toml::Value;
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        plugin: Value,
+    }
+
+    #[test]
+    fn tables_merge_across_sources() {
+        let config = Config::builder()
+            .override_with(TomlSource::new(indoc! {r#"
+                [plugin]
+                name = "a"
+                list = [1, 2]
+
+                [plugin.nested]
+                x = 1
+                y = 2
+            "#}))
+            .override_with(TomlSource::new(indoc! {r#"
+                [plugin]
+                list = [3]
+                enabled = true
+
+                [plugin.nested]
+                y = 3
+            "#}))
+            .try_build()
+            .expect("Failed to parse config");
+
+        let expected = indoc! {r#"
+            enabled = true
+            list = [3]
+            name = "a"
+
+            [nested]
+            x = 1
+            y = 3
+        "#}
+        .parse::<toml::Table>()
+        .unwrap();
+
+        assert_eq!(config.plugin, Value::Table(expected));
+    }
+
+    #[test]
+    fn missing() {
+        Config::builder()
+            .override_with(TomlSource::new(""))
+            .try_build()
+            .unwrap_err();
+    }
+}
+
+#[cfg(feature = "bytesize")]
+mod bytesize {
+    use assert_matches::assert_matches;
+    use confik::{Configuration, Error, TomlSource};
+
+    #[derive(Configuration, Debug)]
+    struct Config {
+        #[confik(bytesize)]
+        cache: u64,
+        #[confik(bytesize, default = 1024u32)]
+        buffer: u32,
+    }
+
+    fn build(toml: &str) -> Result<Config, Error> {
+        Config::builder()
+            .override_with(TomlSource::new(toml))
+            .try_build()
+    }
+
+    #[test]
+    fn human_readable() {
+        let config = build(r#"cache = "512MiB""#).expect("Failed to parse config");
+        assert_eq!(config.cache, 536_870_912);
+        assert_eq!(config.buffer, 1024);
+
+        // As with `bytesize::ByteSize`, SI units are powers of 1000.
+        let config = build(r#"cache = "512MB""#).expect("Failed to parse config");
+        assert_eq!(config.cache, 512_000_000);
+    }
+
+    #[test]
+    fn plain_integer() {
+        let config = build("cache = 4096\nbuffer = \"4 KiB\"").expect("Failed to parse config");
+        assert_eq!(config.cache, 4096);
+        assert_eq!(config.buffer, 4096);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let err = build("cache = 1\nbuffer = \"8 GiB\"").expect_err("Too large for a u32");
+        assert_matches!(err, Error::Source(..));
+    }
+
+    #[test]
+    fn invalid() {
+        let err = build(r#"cache = "lots""#).expect_err("Not a byte size");
+        assert_matches!(err, Error::Source(..));
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::{IpAddr, Ipv4Addr},
+    time::Duration,
+};
+
+use confik::{Configuration, ConfigurationBuilder, ToBuilder, TomlSource};
+
+#[derive(Debug, Clone, PartialEq, Eq, Configuration)]
+#[confik(gen_to_builder)]
+enum Storage {
+    Memory,
+    Disk { path: String, size: Option<u64> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Configuration)]
+#[confik(gen_to_builder)]
+struct Database {
+    host: IpAddr,
+    #[confik(secret)]
+    password: String,
+    timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Configuration)]
+#[confik(gen_to_builder)]
+struct Config {
+    name: String,
+    database: Database,
+    replicas: Vec<Database>,
+    storage: Storage,
+    labels: BTreeMap<String, String>,
+    tags: HashSet<String>,
+    ports: [u16; 2],
+    #[confik(default = 8_usize)]
+    workers: usize,
+    fallback: Option<Database>,
+    comment: Option<String>,
+}
+
+fn database(octet: u8) -> Database {
+    Database {
+        host: IpAddr::V4(Ipv4Addr::new(10, 0, 0, octet)),
+        password: format!("hunter{octet}"),
+        timeout: Duration::from_secs(octet.into()),
+    }
+}
+
+fn config() -> Config {
+    Config {
+        name: "app".to_owned(),
+        database: database(1),
+        replicas: vec![database(2), database(3)],
+        storage: Storage::Disk {
+            path: "/data".to_owned(),
+            size: None,
+        },
+        labels: BTreeMap::from([("env".to_owned(), "prod".to_owned())]),
+        tags: HashSet::from(["a".to_owned(), "b".to_owned()]),
+        ports: [80, 443],
+        workers: 4,
+        fallback: Some(database(4)),
+        comment: None,
+    }
+}
+
+#[test]
+fn round_trip() {
+    let config = config();
+
+    assert_eq!(config.to_builder().try_build().unwrap(), config);
+
+    let memory = Config {
+        storage: Storage::Memory,
+        ..config
+    };
+    assert_eq!(memory.to_builder().try_build().unwrap(), memory);
+}
+
+#[test]
+fn modify_and_rebuild() {
+    let config = config();
+
+    // Every value is set, so overrides must be given priority over the converted builder.
+    let modified = Config::builder()
+        .override_with_builder(config.to_builder())
+        .override_with(TomlSource::new("workers = 16\ncomment = \"scaled up\""))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(
+        modified,
+        Config {
+            workers: 16,
+            comment: Some("scaled up".to_owned()),
+            ..config
+        }
+    );
+}
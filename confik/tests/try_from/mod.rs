@@ -0,0 +1,100 @@
+use std::{error::Error as _, fmt};
+
+use confik::{Configuration, Error, TomlSource};
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize, Configuration)]
+struct Raw(i64);
+
+#[derive(Debug, PartialEq, Eq)]
+struct Port(u16);
+
+#[derive(Debug)]
+struct OutOfRange(i64);
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a valid port", self.0)
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+impl TryFrom<Raw> for Port {
+    type Error = OutOfRange;
+
+    fn try_from(raw: Raw) -> Result<Self, Self::Error> {
+        raw.0.try_into().map(Self).map_err(|_| OutOfRange(raw.0))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Listener {
+    #[confik(try_from = Raw)]
+    port: Port,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Server {
+    listener: Listener,
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+enum Upstream {
+    Tcp {
+        #[confik(try_from = Raw)]
+        port: Port,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Configuration)]
+struct Config {
+    server: Server,
+    upstream: Upstream,
+}
+
+#[test]
+fn nested_path() {
+    let config = Config::builder()
+        .override_with(TomlSource::new(
+            "[server.listener]\nport = 80\n[upstream.Tcp]\nport = 8080",
+        ))
+        .try_build()
+        .expect("Both ports are in range");
+    assert_eq!(config.server.listener.port, Port(80));
+
+    let err = Config::builder()
+        .override_with(TomlSource::new(
+            "[server.listener]\nport = 100000\n[upstream.Tcp]\nport = 8080",
+        ))
+        .try_build()
+        .expect_err("The listener's port is out of range");
+
+    assert!(
+        matches!(err, Error::TryInto(_)),
+        "unexpected error: {err:?}"
+    );
+    assert_eq!(
+        err.to_string(),
+        "Failed try_into for path `server.listener.port`"
+    );
+    assert_eq!(
+        err.source().expect("Missing error source").to_string(),
+        "100000 is not a valid port"
+    );
+}
+
+#[test]
+fn nested_enum_path() {
+    let err = Config::builder()
+        .override_with(TomlSource::new(
+            "[server.listener]\nport = 80\n[upstream.Tcp]\nport = -1",
+        ))
+        .try_build()
+        .expect_err("The upstream's port is out of range");
+
+    assert_eq!(
+        err.to_string(),
+        "Failed try_into for path `upstream.Tcp.port`"
+    );
+}
@@ -76,3 +76,67 @@ mod btreeset {
 mod vec {
     create_tests_for! { Vec<TwoVals> }
 }
+
+#[cfg(feature = "toml")]
+mod association_list {
+    use confik::{Configuration, TomlSource};
+
+    #[derive(Debug, Configuration, PartialEq, Eq)]
+    struct Target {
+        headers: Vec<(String, String)>,
+    }
+
+    #[test]
+    fn order_and_duplicates() {
+        let target = Target::builder()
+            .override_with(TomlSource::new(
+                r#"headers = [["Via", "a"], ["Accept", "*/*"], ["Via", "b"]]"#,
+            ))
+            .try_build()
+            .expect("Failed to build association list");
+
+        assert_eq!(
+            target.headers,
+            [
+                ("Via".to_owned(), "a".to_owned()),
+                ("Accept".to_owned(), "*/*".to_owned()),
+                ("Via".to_owned(), "b".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let lower = TomlSource::new(r#"headers = [["Via", "a"]]"#);
+
+        let target = Target::builder()
+            .override_with(lower.clone())
+            .override_with(TomlSource::new(""))
+            .try_build()
+            .expect("Unspecified lists don't override");
+        assert_eq!(target.headers, [("Via".to_owned(), "a".to_owned())]);
+
+        let target = Target::builder()
+            .override_with(lower)
+            .override_with(TomlSource::new(r#"headers = [["Accept", "*/*"]]"#))
+            .try_build()
+            .expect("Lists are replaced whole");
+        assert_eq!(target.headers, [("Accept".to_owned(), "*/*".to_owned())]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn missing_value() {
+        use confik::{Error, JsonSource};
+
+        let err = Target::builder()
+            .override_with(JsonSource::new(r#"{ "headers": [["Via", null]] }"#))
+            .try_build()
+            .expect_err("The pair has no value");
+
+        assert_matches::assert_matches!(
+            &err,
+            Error::MissingValue(path) if path.to_string().contains("`headers.1`")
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use assert_matches::assert_matches;
+use confik::{Configuration, Error, TomlSource};
+use url::Url;
+
+#[derive(Debug, Configuration)]
+struct Config {
+    #[confik(url_scheme = "https")]
+    endpoint: Url,
+
+    #[confik(url_scheme = "https")]
+    fallback: Option<Url>,
+}
+
+fn build(toml: &str) -> Result<Config, Error> {
+    Config::builder()
+        .override_with(TomlSource::new(toml))
+        .try_build()
+}
+
+#[test]
+fn matching_scheme() {
+    let config = build(
+        r#"
+        endpoint = "https://example.com/api"
+        fallback = "https://backup.example.com/api"
+        "#,
+    )
+    .expect("Valid schemes");
+    assert_eq!(config.endpoint.as_str(), "https://example.com/api");
+    assert_eq!(
+        config.fallback.unwrap().as_str(),
+        "https://backup.example.com/api"
+    );
+
+    let config = build(r#"endpoint = "https://example.com/api""#).expect("Optional URL");
+    assert!(config.fallback.is_none());
+}
+
+#[test]
+fn unexpected_scheme() {
+    let err = build(r#"endpoint = "http://example.com/api""#).unwrap_err();
+    assert_matches!(&err, Error::TryInto(_));
+    assert_eq!(err.to_string(), "Failed try_into for path `endpoint`");
+    assert_eq!(
+        std::error::Error::source(&err).unwrap().to_string(),
+        "Expected a URL with scheme `https`, found `http`"
+    );
+
+    let err = build(
+        r#"
+        endpoint = "https://example.com/api"
+        fallback = "ftp://backup.example.com/api"
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Failed try_into for path `fallback`");
+}
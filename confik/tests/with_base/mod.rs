@@ -0,0 +1,97 @@
+use confik::{Configuration, TomlSource};
+
+#[derive(Debug, Clone, PartialEq, Eq, Configuration)]
+#[confik(gen_to_builder)]
+struct Limits {
+    max_body_bytes: u64,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Configuration)]
+#[confik(gen_to_builder)]
+struct Config {
+    name: String,
+    limits: Limits,
+    allowed_origins: Vec<String>,
+    #[confik(secret)]
+    api_key: String,
+    #[confik(source_kinds(file))]
+    data_dir: String,
+}
+
+fn base() -> Config {
+    Config {
+        name: "base".to_owned(),
+        limits: Limits {
+            max_body_bytes: 1024,
+            timeout_secs: None,
+        },
+        allowed_origins: vec!["https://example.com".to_owned()],
+        api_key: "hunter2".to_owned(),
+        data_dir: "/var/lib/app".to_owned(),
+    }
+}
+
+#[test]
+fn base_only() {
+    let base = base();
+
+    let config = Config::builder()
+        .with_base(&base)
+        .try_build()
+        .expect("The base provides every value");
+
+    assert_eq!(config, base);
+}
+
+#[test]
+fn overrides_layered_over_base() {
+    let base = base();
+
+    let config = Config::builder()
+        .override_with(TomlSource::new(
+            "allowed_origins = [\"https://example.org\"]\n[limits]\ntimeout_secs = 5",
+        ))
+        .with_base(&base)
+        .try_build()
+        .expect("Missing values fall back to the base");
+
+    assert_eq!(
+        config,
+        Config {
+            limits: Limits {
+                max_body_bytes: 1024,
+                timeout_secs: Some(5),
+            },
+            allowed_origins: vec!["https://example.org".to_owned()],
+            ..base.clone()
+        }
+    );
+
+    // Overrides still can't contain secrets, even though the base does.
+    Config::builder()
+        .override_with(TomlSource::new("api_key = \"hunter3\""))
+        .with_base(&base)
+        .try_build()
+        .expect_err("The override contains a secret");
+}
+
+#[test]
+fn base_does_not_conflict() {
+    let base = base();
+
+    let config = Config::builder()
+        .override_with(TomlSource::new("name = \"request\""))
+        .with_base(&base)
+        .forbid_conflicts()
+        .try_build()
+        .expect("The base is only a fallback");
+
+    assert_eq!(
+        config,
+        Config {
+            name: "request".to_owned(),
+            ..base
+        }
+    );
+}